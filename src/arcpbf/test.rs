@@ -1,18 +0,0 @@
-#![cfg(test)]
-use std::fs;
-use std::path::Path;
-use super::super::OSMReader;
-
-use super::PBFReader;
-
-#[test]
-fn test_file() {
-    //let file = fs::File::open(&Path::new("/home/rory/code/rust/osmio/monaco-latest.osm.pbf")).unwrap();
-    //let mut reader = PBFReader::new(file);
-
-    //for obj in reader.objects() {
-    //    //println!("{:?}", obj);
-    //}
-
-
-}
@@ -2,7 +2,6 @@ extern crate anyhow;
 extern crate iter_progress;
 extern crate osmio;
 extern crate rusqlite;
-extern crate serde;
 extern crate serde_json;
 use iter_progress::OptionalProgressableIter;
 use rusqlite::{Connection, params};
@@ -10,6 +9,7 @@ use std::collections::HashMap;
 
 use anyhow::{Result, ensure};
 use osmio::changesets::ChangesetTagReader;
+use osmio::progress::ReadProgress;
 use std::env::args;
 use std::path::PathBuf;
 
@@ -64,12 +64,12 @@ fn main() -> Result<()> {
     //let mut host;
     //let mut changesets_count;
 
-    for (state, changeset_res) in osc
-        .into_iter()
-        .optional_progress(10000)
-        .assume_size(110_000_000)
-    {
-        if let Some(state) = state {
+    let mut changesets = osc.into_iter().optional_progress(10000);
+    while let Some((state, changeset_res)) = changesets.next() {
+        if let Some(mut state) = state {
+            if let Some(fraction) = changesets.inner().fraction_done() {
+                state.assume_fraction(fraction);
+            }
             state.do_every_n_sec(2.0_f32, |state| {
                 println!(
                     "{:?}s {}k / {:.1}% done. eta: {} sec {:.0} per sec",
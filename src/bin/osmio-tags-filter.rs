@@ -0,0 +1,56 @@
+extern crate osmio;
+
+use anyhow::{Context, Result};
+use osmio::pbf::{PBFReader, PBFWriter};
+use osmio::tagfilter::TagFilter;
+use osmio::{OSMReader, OSMWriter};
+use std::env::args;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+
+fn filter<W: Write>(
+    reader: &mut impl OSMReader,
+    writer: &mut impl OSMWriter<W>,
+    filter: &TagFilter,
+) -> Result<u64> {
+    let mut num_written = 0;
+    for obj in filter.filter(reader.objects()) {
+        writer.write_obj(&obj)?;
+        num_written += 1;
+    }
+    writer.close()?;
+    Ok(num_written)
+}
+
+fn with_writer(reader: &mut impl OSMReader, output: &str, tag_filter: &TagFilter) -> Result<u64> {
+    if output.ends_with(".pbf") {
+        let mut writer = PBFWriter::new(BufWriter::new(File::create(output)?));
+        filter(reader, &mut writer, tag_filter)
+    } else {
+        let mut writer = osmio::xml::to_filename(output)?;
+        filter(reader, &mut writer, tag_filter)
+    }
+}
+
+fn main() -> Result<()> {
+    let usage = "Usage: osmio-tags-filter INPUT OUTPUT EXPRESSION...";
+    let input = args().nth(1).context(usage)?;
+    let output = args().nth(2).context(usage)?;
+    let tag_filter = TagFilter::parse_all(args().skip(3))?;
+    if tag_filter.exprs().is_empty() {
+        anyhow::bail!(usage);
+    }
+
+    let num_written = if input.ends_with(".pbf") {
+        with_writer(&mut PBFReader::from_filename(&input)?, &output, &tag_filter)?
+    } else {
+        with_writer(
+            &mut osmio::xml::from_filename(&input)?,
+            &output,
+            &tag_filter,
+        )?
+    };
+    eprintln!("Wrote {} objects to {}", num_written, output);
+
+    Ok(())
+}
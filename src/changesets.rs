@@ -23,7 +23,13 @@
 //!
 //! The `ChangesetReader` reads the file fully, but `ChangesetTagReader` is optimized to just
 //! return the tags
+//!
+//! Use `ChangesetReader::open` & `ChangesetWriter::create` for gzip, bzip2 or zstd compressed
+//! files.
 use super::*;
+use crate::compression::{CompressedReader, CompressedWriter, Compression};
+use crate::progress::ReadProgress;
+use crate::xml::write_xml_escaped;
 use anyhow::{bail, ensure};
 use bzip2::read::MultiBzDecoder;
 use quick_xml::XmlVersion;
@@ -36,9 +42,9 @@ use std::io::{BufReader, Read};
 #[derive(Debug, Builder)]
 pub struct Changeset {
     pub id: u32,
-    pub created: TimestampFormat,
+    pub created: Timestamp,
     #[builder(setter(strip_option), default)]
-    pub closed: Option<TimestampFormat>,
+    pub closed: Option<Timestamp>,
     pub open: bool,
     #[builder(setter(strip_option), default)]
     pub uid: Option<i64>,
@@ -127,14 +133,14 @@ impl<R: Read> ChangesetReader<R> {
                                     .id(attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?);
                             }
                             b"created_at" => {
-                                changeset_builder.created(TimestampFormat::ISOString(
-                                    attr.normalized_value(XmlVersion::Implicit1_0)?.to_string(),
-                                ));
+                                changeset_builder.created(
+                                    attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?,
+                                );
                             }
                             b"closed_at" => {
-                                changeset_builder.closed(TimestampFormat::ISOString(
-                                    attr.normalized_value(XmlVersion::Implicit1_0)?.to_string(),
-                                ));
+                                changeset_builder.closed(
+                                    attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?,
+                                );
                             }
                             b"open" => {
                                 changeset_builder.open(match attr.value.as_ref() {
@@ -229,14 +235,14 @@ impl<R: Read> ChangesetReader<R> {
                                     .id(attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?);
                             }
                             b"created_at" => {
-                                changeset_builder.created(TimestampFormat::ISOString(
-                                    attr.normalized_value(XmlVersion::Implicit1_0)?.to_string(),
-                                ));
+                                changeset_builder.created(
+                                    attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?,
+                                );
                             }
                             b"closed_at" => {
-                                changeset_builder.closed(TimestampFormat::ISOString(
-                                    attr.normalized_value(XmlVersion::Implicit1_0)?.to_string(),
-                                ));
+                                changeset_builder.closed(
+                                    attr.normalized_value(XmlVersion::Implicit1_0)?.parse()?,
+                                );
                             }
                             b"open" => {
                                 changeset_builder.open(match attr.value.as_ref() {
@@ -291,6 +297,16 @@ impl<R: Read> Iterator for ChangesetReader<R> {
     }
 }
 
+impl<R: Read + ReadProgress> ReadProgress for ChangesetReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.reader.get_ref().bytes_read()
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.reader.get_ref().total_bytes()
+    }
+}
+
 impl ChangesetReader<bzip2::read::MultiBzDecoder<std::fs::File>> {
     pub fn from_filename(filename: &str) -> Result<Self> {
         let f = File::open(filename)?;
@@ -300,6 +316,14 @@ impl ChangesetReader<bzip2::read::MultiBzDecoder<std::fs::File>> {
     }
 }
 
+impl ChangesetReader<CompressedReader<File>> {
+    /// Open a changeset file, decompressing it based on the filename extension (`.gz`, `.bz2` or
+    /// `.zst`).
+    pub fn open(filename: impl AsRef<Path>) -> Result<Self> {
+        Ok(ChangesetReader::new(CompressedReader::open(filename)?))
+    }
+}
+
 /// A `Reader` which “returns” BZ2 compressed data
 impl<R: Read> ChangesetReader<bzip2::read::MultiBzDecoder<R>> {
     pub fn from_bz2_reader(rdr: R) -> Self {
@@ -329,6 +353,14 @@ impl ChangesetTagReader<bzip2::read::MultiBzDecoder<std::fs::File>> {
     }
 }
 
+impl ChangesetTagReader<CompressedReader<File>> {
+    /// Open a changeset file, decompressing it based on the filename extension (`.gz`, `.bz2` or
+    /// `.zst`).
+    pub fn open(filename: impl AsRef<Path>) -> Result<Self> {
+        Ok(ChangesetTagReader::new(CompressedReader::open(filename)?))
+    }
+}
+
 impl<R: Read> ChangesetTagReader<R> {
     fn new(reader: R) -> Self {
         ChangesetTagReader {
@@ -405,3 +437,183 @@ impl<R: Read> Iterator for ChangesetTagReader<R> {
         self.next_tag().transpose()
     }
 }
+
+impl<R: Read + ReadProgress> ReadProgress for ChangesetTagReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.reader.get_ref().bytes_read()
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.reader.get_ref().total_bytes()
+    }
+}
+
+/// Writes `Changeset`s in the same XML format as the changeset dump file.
+pub struct ChangesetWriter<W: Write> {
+    writer: W,
+    finish: fn(&mut W) -> std::io::Result<()>,
+    started: bool,
+    closed: bool,
+}
+
+impl<W: Write> ChangesetWriter<W> {
+    pub fn new(writer: W) -> Self {
+        ChangesetWriter {
+            writer,
+            finish: W::flush,
+            started: false,
+            closed: false,
+        }
+    }
+
+    fn ensure_header(&mut self) -> Result<(), OSMWriteError> {
+        if !self.started {
+            writeln!(self.writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+            write!(
+                self.writer,
+                "<osm version=\"0.6\" generator=\"osmio/{}\">",
+                version()
+            )?;
+            self.started = true;
+        }
+        Ok(())
+    }
+
+    /// Write one changeset
+    pub fn write_changeset(&mut self, changeset: &Changeset) -> Result<(), OSMWriteError> {
+        if self.closed {
+            return Err(OSMWriteError::AlreadyClosed);
+        }
+        self.ensure_header()?;
+
+        write!(
+            self.writer,
+            "\n\t<changeset id=\"{}\" created_at=\"{}\"",
+            changeset.id, changeset.created
+        )?;
+        if let Some(closed) = &changeset.closed {
+            write!(self.writer, " closed_at=\"{}\"", closed)?;
+        }
+        write!(self.writer, " open=\"{}\"", changeset.open)?;
+        if let Some(user) = &changeset.user {
+            write!(self.writer, " user=\"")?;
+            write_xml_escaped(&mut self.writer, user)?;
+            write!(self.writer, "\"")?;
+        }
+        if let Some(uid) = changeset.uid {
+            write!(self.writer, " uid=\"{}\"", uid)?;
+        }
+        write!(
+            self.writer,
+            " num_changes=\"{}\" comments_count=\"{}\"",
+            changeset.num_changes, changeset.comments_count
+        )?;
+
+        if changeset.untagged() {
+            write!(self.writer, " />")?;
+            return Ok(());
+        }
+        write!(self.writer, ">")?;
+        let mut tags: Vec<_> = changeset.tags.iter().collect();
+        tags.sort();
+        for (k, v) in tags {
+            write!(self.writer, "\n\t\t<tag k=\"")?;
+            write_xml_escaped(&mut self.writer, k)?;
+            write!(self.writer, "\" v=\"")?;
+            write_xml_escaped(&mut self.writer, v)?;
+            write!(self.writer, "\" />")?;
+        }
+        write!(self.writer, "\n\t</changeset>")?;
+
+        Ok(())
+    }
+
+    /// Write the end of the file (and finish any compression). No more changesets can be
+    /// written.
+    pub fn close(&mut self) -> Result<(), OSMWriteError> {
+        if self.closed {
+            return Ok(());
+        }
+        self.ensure_header()?;
+        write!(self.writer, "\n</osm>")?;
+        (self.finish)(&mut self.writer)?;
+        self.closed = true;
+        Ok(())
+    }
+
+    /// Return true iff this writer is not closed.
+    pub fn is_open(&self) -> bool {
+        !self.closed
+    }
+}
+
+impl<W: Write> ChangesetWriter<CompressedWriter<W>> {
+    /// Write changesets to `writer`, compressed with `compression`.
+    pub fn new_compressed(writer: W, compression: Compression) -> Result<Self> {
+        Ok(Self::from_compressed(CompressedWriter::new(
+            writer,
+            compression,
+        )?))
+    }
+
+    /// Write changesets to this compressed writer. Closing this will finish the compression.
+    pub fn from_compressed(writer: CompressedWriter<W>) -> Self {
+        let mut changeset_writer = ChangesetWriter::new(writer);
+        changeset_writer.finish = CompressedWriter::finish;
+        changeset_writer
+    }
+}
+
+impl ChangesetWriter<CompressedWriter<File>> {
+    /// Create a changeset file, compressing it based on the filename extension (`.gz`, `.bz2` or
+    /// `.zst`).
+    pub fn create(filename: impl AsRef<Path>) -> Result<Self> {
+        Ok(ChangesetWriter::from_compressed(CompressedWriter::create(
+            filename,
+        )?))
+    }
+}
+
+impl<W: Write> Drop for ChangesetWriter<W> {
+    fn drop(&mut self) {
+        self.close().unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_then_read_compressed() {
+        let mut tags = HashMap::new();
+        tags.insert("comment".to_string(), "Fix \"roads\" & paths".to_string());
+        let changeset = ChangesetBuilder::default()
+            .id(42)
+            .created("2020-01-01T00:00:00Z".parse().unwrap())
+            .open(false)
+            .user("Jane".to_string())
+            .uid(7)
+            .tags(tags)
+            .num_changes(3)
+            .comments_count(0)
+            .build()
+            .unwrap();
+
+        for compression in [Compression::Gzip, Compression::Bzip2, Compression::Zstd] {
+            let mut output = Vec::new();
+            {
+                let mut writer = ChangesetWriter::new_compressed(&mut output, compression).unwrap();
+                writer.write_changeset(&changeset).unwrap();
+                writer.close().unwrap();
+            }
+
+            let reader = CompressedReader::new(output.as_slice(), compression).unwrap();
+            let changesets: Vec<_> = ChangesetReader::new(reader).collect::<Result<_>>().unwrap();
+            assert_eq!(changesets.len(), 1);
+            assert_eq!(changesets[0].id, 42);
+            assert_eq!(changesets[0].user.as_deref(), Some("Jane"));
+            assert_eq!(changesets[0].tag("comment"), Some("Fix \"roads\" & paths"));
+        }
+    }
+}
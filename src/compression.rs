@@ -0,0 +1,271 @@
+//! Transparent compression for XML based file formats
+//!
+//! OSM XML (`.osm`), change files (`.osc`) and changeset dumps are often distributed compressed,
+//! e.g. `planet-latest.osm.bz2`, minutely replication diffs as `.osc.gz`, or archives as
+//! `.osm.zst`. [`CompressedReader`] & [`CompressedWriter`] wrap a plain reader/writer with the
+//! right (de)compressor.
+//!
+//! ```no_run
+//! use osmio::compression::{Compression, CompressedReader};
+//! let reader = CompressedReader::open("region.osm.zst")?;
+//! assert_eq!(reader.compression(), Compression::Zstd);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::Result;
+use bzip2::read::MultiBzDecoder;
+use bzip2::write::BzEncoder;
+use flate2::read::MultiGzDecoder;
+use flate2::write::GzEncoder;
+
+/// Which (if any) compression is used on a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Not compressed
+    None,
+    /// gzip (`.gz`)
+    Gzip,
+    /// bzip2 (`.bz2`)
+    Bzip2,
+    /// Zstandard (`.zst`)
+    Zstd,
+}
+
+impl Compression {
+    /// Guess the compression from the filename extension.
+    ///
+    /// ```
+    /// use osmio::compression::Compression;
+    /// assert_eq!(Compression::from_path("changes.osc.gz"), Compression::Gzip);
+    /// assert_eq!(Compression::from_path("planet.osm.bz2"), Compression::Bzip2);
+    /// assert_eq!(Compression::from_path("region.osm.zst"), Compression::Zstd);
+    /// assert_eq!(Compression::from_path("region.osm"), Compression::None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("bz2") => Compression::Bzip2,
+            Some("zst") | Some("zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// A `Read` which decompresses the underlying reader.
+///
+/// Reads from the underlying reader are buffered.
+pub enum CompressedReader<R: Read> {
+    Plain(BufReader<R>),
+    Gzip(MultiGzDecoder<R>),
+    Bzip2(MultiBzDecoder<R>),
+    Zstd(zstd::Decoder<'static, BufReader<R>>),
+}
+
+impl<R: Read> CompressedReader<R> {
+    /// Wrap `reader`, decompressing with `compression`.
+    pub fn new(reader: R, compression: Compression) -> Result<Self> {
+        Ok(match compression {
+            Compression::None => CompressedReader::Plain(BufReader::new(reader)),
+            Compression::Gzip => CompressedReader::Gzip(MultiGzDecoder::new(reader)),
+            Compression::Bzip2 => CompressedReader::Bzip2(MultiBzDecoder::new(reader)),
+            Compression::Zstd => CompressedReader::Zstd(zstd::Decoder::new(reader)?),
+        })
+    }
+
+    /// The compression this reader is decompressing.
+    pub fn compression(&self) -> Compression {
+        match self {
+            CompressedReader::Plain(_) => Compression::None,
+            CompressedReader::Gzip(_) => Compression::Gzip,
+            CompressedReader::Bzip2(_) => Compression::Bzip2,
+            CompressedReader::Zstd(_) => Compression::Zstd,
+        }
+    }
+
+    /// Reference to the underlying (compressed) reader.
+    pub fn get_ref(&self) -> &R {
+        match self {
+            CompressedReader::Plain(r) => r.get_ref(),
+            CompressedReader::Gzip(r) => r.get_ref(),
+            CompressedReader::Bzip2(r) => r.get_ref(),
+            CompressedReader::Zstd(r) => r.get_ref().get_ref(),
+        }
+    }
+
+    /// Consumes this, returning the underlying (compressed) reader. Any buffered data is lost.
+    pub fn into_inner(self) -> R {
+        match self {
+            CompressedReader::Plain(r) => r.into_inner(),
+            CompressedReader::Gzip(r) => r.into_inner(),
+            CompressedReader::Bzip2(r) => r.into_inner(),
+            CompressedReader::Zstd(r) => r.finish().into_inner(),
+        }
+    }
+}
+
+impl CompressedReader<File> {
+    /// Open a file, guessing the compression from the filename.
+    pub fn open(filename: impl AsRef<Path>) -> Result<Self> {
+        let filename: &Path = filename.as_ref();
+        CompressedReader::new(File::open(filename)?, Compression::from_path(filename))
+    }
+}
+
+impl<R: Read> Read for CompressedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            CompressedReader::Plain(r) => r.read(buf),
+            CompressedReader::Gzip(r) => r.read(buf),
+            CompressedReader::Bzip2(r) => r.read(buf),
+            CompressedReader::Zstd(r) => r.read(buf),
+        }
+    }
+}
+
+/// A `Write` which compresses everything written to it.
+///
+/// Compressed formats have a trailer which must be written at the end. Call
+/// [`finish`](Self::finish) when done. The `XMLWriter`, `OSCWriter` & `ChangesetWriter` do this
+/// when they are closed. Writes to the underlying writer are buffered.
+pub enum CompressedWriter<W: Write> {
+    Plain(BufWriter<W>),
+    Gzip(GzEncoder<W>),
+    Bzip2(BzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    /// Wrap `writer`, compressing with `compression` (at the default level).
+    pub fn new(writer: W, compression: Compression) -> Result<Self> {
+        Ok(match compression {
+            Compression::None => CompressedWriter::Plain(BufWriter::new(writer)),
+            Compression::Gzip => {
+                CompressedWriter::Gzip(GzEncoder::new(writer, flate2::Compression::default()))
+            }
+            Compression::Bzip2 => {
+                CompressedWriter::Bzip2(BzEncoder::new(writer, bzip2::Compression::default()))
+            }
+            Compression::Zstd => CompressedWriter::Zstd(zstd::Encoder::new(writer, 0)?),
+        })
+    }
+
+    /// The compression this writer uses.
+    pub fn compression(&self) -> Compression {
+        match self {
+            CompressedWriter::Plain(_) => Compression::None,
+            CompressedWriter::Gzip(_) => Compression::Gzip,
+            CompressedWriter::Bzip2(_) => Compression::Bzip2,
+            CompressedWriter::Zstd(_) => Compression::Zstd,
+        }
+    }
+
+    /// Reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        match self {
+            CompressedWriter::Plain(w) => w.get_ref(),
+            CompressedWriter::Gzip(w) => w.get_ref(),
+            CompressedWriter::Bzip2(w) => w.get_ref(),
+            CompressedWriter::Zstd(w) => w.get_ref(),
+        }
+    }
+
+    /// Write out any compression trailer, and flush the underlying writer.
+    ///
+    /// Nothing more should be written after this.
+    pub fn finish(&mut self) -> std::io::Result<()> {
+        match self {
+            CompressedWriter::Plain(w) => w.flush(),
+            CompressedWriter::Gzip(w) => {
+                w.try_finish()?;
+                w.get_mut().flush()
+            }
+            CompressedWriter::Bzip2(w) => {
+                w.try_finish()?;
+                w.get_mut().flush()
+            }
+            CompressedWriter::Zstd(w) => {
+                w.do_finish()?;
+                w.get_mut().flush()
+            }
+        }
+    }
+}
+
+impl CompressedWriter<File> {
+    /// Create a file, guessing the compression from the filename.
+    pub fn create(filename: impl AsRef<Path>) -> Result<Self> {
+        let filename: &Path = filename.as_ref();
+        CompressedWriter::new(File::create(filename)?, Compression::from_path(filename))
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            CompressedWriter::Plain(w) => w.write(buf),
+            CompressedWriter::Gzip(w) => w.write(buf),
+            CompressedWriter::Bzip2(w) => w.write(buf),
+            CompressedWriter::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            CompressedWriter::Plain(w) => w.flush(),
+            CompressedWriter::Gzip(w) => w.flush(),
+            CompressedWriter::Bzip2(w) => w.flush(),
+            CompressedWriter::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(compression: Compression) {
+        let input = "<osm>\n\t<node id=\"1\" />\n</osm>".repeat(100);
+        let mut writer = CompressedWriter::new(Vec::new(), compression).unwrap();
+        writer.write_all(input.as_bytes()).unwrap();
+        writer.finish().unwrap();
+        let compressed = writer.get_ref().clone();
+
+        let mut output = String::new();
+        CompressedReader::new(compressed.as_slice(), compression)
+            .unwrap()
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(input, output);
+    }
+
+    #[test]
+    fn roundtrip_all() {
+        roundtrip(Compression::None);
+        roundtrip(Compression::Gzip);
+        roundtrip(Compression::Bzip2);
+        roundtrip(Compression::Zstd);
+    }
+
+    #[test]
+    fn plain_files() {
+        let dir = std::env::temp_dir().join(format!("osmio-compression-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.osm");
+        let input = "<osm>\n\t<node id=\"1\" />\n</osm>".repeat(100);
+        let mut writer = CompressedWriter::create(&path).unwrap();
+        assert!(matches!(writer, CompressedWriter::Plain(_)));
+        writer.write_all(input.as_bytes()).unwrap();
+        writer.finish().unwrap();
+        drop(writer);
+
+        let mut reader = CompressedReader::open(&path).unwrap();
+        assert_eq!(reader.compression(), Compression::None);
+        let mut output = String::new();
+        reader.read_to_string(&mut output).unwrap();
+        assert_eq!(input, output);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+//! Cut out the part of a file in a region, like `osmium extract`
+//!
+//! The source is read several times, so [`extract`] takes a function which opens it. The source
+//! must be sorted (nodes, then ways, then relations), as normal OSM files are. The ids of
+//! everything to keep are stored in [`IdSet`]s.
+//!
+//! ```no_run
+//! use osmio::extract::{Region, Strategy, extract};
+//! use osmio::pbf::PBFReader;
+//! use osmio::xml::XMLWriter;
+//! use osmio::{BBox, Lat, Lon, OSMWriter};
+//! let bbox = BBox::new(Lat::from_inner(515000000), Lon::from_inner(-1000000),
+//!                      Lat::from_inner(516000000), Lon::from_inner(0));
+//! let mut writer = XMLWriter::new(std::fs::File::create("london.osm")?);
+//! extract(
+//!     || PBFReader::from_filename("england-latest.osm.pbf"),
+//!     &Region::from(bbox),
+//!     Strategy::CompleteWays,
+//!     &mut writer,
+//! )?;
+//! writer.close()?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::idset::IdSet;
+use crate::multipolygon::{MultiPolygon, Polygon};
+use crate::{BBox, Lat, Lon, OSMObj, OSMObjectType, OSMReader, OSMWriter};
+use crate::{NodeRead, OSMObjBaseRead, ObjId, RelationRead, WayRead};
+
+use anyhow::Result;
+use std::io::Write;
+
+/// The area to extract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// `None` for an empty region
+    bbox: Option<BBox>,
+    /// `None` if this is just the bbox
+    polygons: Option<Vec<Polygon>>,
+}
+
+impl Region {
+    /// Is this location in this region?
+    pub fn contains(&self, lat: Lat, lon: Lon) -> bool {
+        let Some(bbox) = &self.bbox else {
+            return false;
+        };
+        if !bbox.contains(lat, lon) {
+            return false;
+        }
+        match &self.polygons {
+            None => true,
+            Some(polygons) => polygons.iter().any(|p| p.contains(lat, lon)),
+        }
+    }
+
+    /// The bbox of this region, `None` if it is empty
+    pub fn bbox(&self) -> Option<BBox> {
+        self.bbox
+    }
+}
+
+impl From<BBox> for Region {
+    fn from(bbox: BBox) -> Self {
+        Region {
+            bbox: Some(bbox),
+            polygons: None,
+        }
+    }
+}
+
+impl From<Vec<Polygon>> for Region {
+    fn from(polygons: Vec<Polygon>) -> Self {
+        Region {
+            bbox: BBox::from_locations(polygons.iter().flat_map(|p| p.outer.iter().copied())),
+            polygons: Some(polygons),
+        }
+    }
+}
+
+impl From<MultiPolygon> for Region {
+    fn from(multipolygon: MultiPolygon) -> Self {
+        multipolygon.polygons.into()
+    }
+}
+
+/// Which objects, outside the region, are also included
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// Nodes in the region, and ways & relations with a member in the region. Ways will be
+    /// missing the nodes outside the region.
+    Simple,
+    /// Like [`Simple`](Strategy::Simple), but with all the nodes of the ways.
+    #[default]
+    CompleteWays,
+    /// Like [`CompleteWays`](Strategy::CompleteWays), but `type=multipolygon` relations also
+    /// have all their member ways (and their nodes).
+    Smart,
+}
+
+/// How many objects were written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ExtractStats {
+    pub nodes: usize,
+    pub ways: usize,
+    pub relations: usize,
+}
+
+/// Write the objects in `region` to `writer`.
+///
+/// `open` is called to read the source for each pass. There are 2 passes, or 3 for
+/// [`Strategy::Smart`]. Objects are written in the same order as the source. Relations which
+/// have an included relation as a member are also included. The writer is not closed.
+pub fn extract<R, W>(
+    mut open: impl FnMut() -> Result<R>,
+    region: &Region,
+    strategy: Strategy,
+    writer: &mut impl OSMWriter<W>,
+) -> Result<ExtractStats>
+where
+    R: OSMReader,
+    W: Write,
+{
+    let mut nodes = IdSet::new();
+    let mut ways = IdSet::new();
+    let mut relations = IdSet::new();
+    // Nodes of included ways which aren't in the region
+    let mut way_nodes = IdSet::new();
+    // Member ways of multipolygons, for the smart strategy
+    let mut extra_ways = IdSet::new();
+    // (relation, relation members) for relations not (yet) included
+    let mut parent_relations: Vec<(ObjId, Vec<ObjId>)> = Vec::new();
+
+    let mut reader = open()?;
+    while let Some(obj) = reader.next() {
+        if let Some(node) = obj.as_node() {
+            if let Some((lat, lon)) = node.lat_lon()
+                && region.contains(lat, lon)
+            {
+                nodes.insert(node.id());
+            }
+        } else if let Some(way) = obj.as_way() {
+            if way.nodes().iter().any(|&nid| nodes.contains(nid)) {
+                ways.insert(way.id());
+                if strategy != Strategy::Simple {
+                    way_nodes.extend(way.nodes().iter().copied());
+                }
+            }
+        } else if let Some(relation) = obj.as_relation() {
+            let included = relation
+                .members()
+                .any(|(member_type, id, _)| match member_type {
+                    OSMObjectType::Node => nodes.contains(id),
+                    OSMObjectType::Way => ways.contains(id),
+                    OSMObjectType::Relation => relations.contains(id),
+                });
+            if included {
+                relations.insert(relation.id());
+                if strategy == Strategy::Smart && relation.tag("type") == Some("multipolygon") {
+                    extra_ways.extend(relation.members().filter_map(|(t, id, _)| {
+                        (t == OSMObjectType::Way && !ways.contains(id)).then_some(id)
+                    }));
+                }
+            } else {
+                let child_relations: Vec<_> = relation
+                    .members()
+                    .filter_map(|(t, id, _)| (t == OSMObjectType::Relation).then_some(id))
+                    .collect();
+                if !child_relations.is_empty() {
+                    parent_relations.push((relation.id(), child_relations));
+                }
+            }
+        }
+    }
+
+    // Relations can be members of relations which come before them in the file.
+    loop {
+        let mut added = false;
+        parent_relations.retain(|(id, children)| {
+            if children.iter().any(|&child| relations.contains(child)) {
+                relations.insert(*id);
+                added = true;
+                false
+            } else {
+                true
+            }
+        });
+        if !added {
+            break;
+        }
+    }
+
+    if !extra_ways.is_empty() {
+        let mut reader = open()?;
+        while let Some(obj) = reader.next() {
+            if let Some(way) = obj.as_way()
+                && extra_ways.contains(way.id())
+            {
+                ways.insert(way.id());
+                way_nodes.extend(way.nodes().iter().copied());
+            } else if obj.is_relation() {
+                break;
+            }
+        }
+    }
+    nodes.union_with(&way_nodes);
+
+    let mut stats = ExtractStats::default();
+    let mut reader = open()?;
+    while let Some(obj) = reader.next() {
+        let (wanted, count) = match obj.object_type() {
+            OSMObjectType::Node => (&nodes, &mut stats.nodes),
+            OSMObjectType::Way => (&ways, &mut stats.ways),
+            OSMObjectType::Relation => (&relations, &mut stats.relations),
+        };
+        if wanted.contains(obj.id()) {
+            writer.write_obj(&obj)?;
+            *count += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::xml::XMLReader;
+
+    const INPUT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="1" lon="1"/>
+  <node id="2" lat="5" lon="5"/>
+  <node id="3" lat="2" lon="2"/>
+  <node id="4" lat="6" lon="6"/>
+  <node id="5" lat="7" lon="7"/>
+  <way id="10"><nd ref="1"/><nd ref="2"/></way>
+  <way id="11"><nd ref="4"/><nd ref="5"/></way>
+  <way id="12"><nd ref="2"/><nd ref="4"/></way>
+  <relation id="19">
+    <member type="relation" ref="22" role=""/>
+  </relation>
+  <relation id="20">
+    <member type="way" ref="10" role="outer"/>
+    <member type="way" ref="12" role="outer"/>
+    <tag k="type" v="multipolygon"/>
+  </relation>
+  <relation id="21">
+    <member type="node" ref="3" role=""/>
+  </relation>
+  <relation id="22">
+    <member type="relation" ref="21" role=""/>
+  </relation>
+  <relation id="23">
+    <member type="node" ref="5" role=""/>
+  </relation>
+</osm>"#;
+
+    fn run(region: &Region, strategy: Strategy) -> (ExtractStats, Vec<(OSMObjectType, ObjId)>) {
+        let mut output = Vec::new();
+        let mut writer = crate::xml::XMLWriter::new(&mut output);
+        let stats = extract(
+            || Ok(XMLReader::new(INPUT.as_bytes())),
+            region,
+            strategy,
+            &mut writer,
+        )
+        .unwrap();
+        writer.close().unwrap();
+        drop(writer);
+        let ids = XMLReader::new(output.as_slice())
+            .objects()
+            .map(|o| (o.object_type(), o.id()))
+            .collect();
+        (stats, ids)
+    }
+
+    fn ids(nodes: &[ObjId], ways: &[ObjId], relations: &[ObjId]) -> Vec<(OSMObjectType, ObjId)> {
+        let with_type = |t, ids: &[ObjId]| ids.iter().map(|&id| (t, id)).collect::<Vec<_>>();
+        [
+            with_type(OSMObjectType::Node, nodes),
+            with_type(OSMObjectType::Way, ways),
+            with_type(OSMObjectType::Relation, relations),
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn strategies() {
+        let deg = |d: i32| d * 10_000_000;
+        let region = Region::from(BBox::new(Lat(0), Lon(0), Lat(deg(3)), Lon(deg(3))));
+
+        let (stats, written) = run(&region, Strategy::Simple);
+        assert_eq!(written, ids(&[1, 3], &[10], &[19, 20, 21, 22]));
+        assert_eq!(
+            stats,
+            ExtractStats {
+                nodes: 2,
+                ways: 1,
+                relations: 4
+            }
+        );
+
+        let (_, written) = run(&region, Strategy::CompleteWays);
+        assert_eq!(written, ids(&[1, 2, 3], &[10], &[19, 20, 21, 22]));
+
+        // Way 12 is in the multipolygon, but not in the region
+        let (_, written) = run(&region, Strategy::Smart);
+        assert_eq!(written, ids(&[1, 2, 3, 4], &[10, 12], &[19, 20, 21, 22]));
+    }
+
+    #[test]
+    fn polygon() {
+        let deg = |d: i32| d * 10_000_000;
+        // A small triangle around node 3
+        let region = Region::from(vec![Polygon {
+            outer: vec![
+                (Lat(deg(2) - 1), Lon(deg(2) - 1)),
+                (Lat(deg(2) - 1), Lon(deg(2) + 1)),
+                (Lat(deg(2) + 1), Lon(deg(2) + 1)),
+                (Lat(deg(2) - 1), Lon(deg(2) - 1)),
+            ],
+            inners: vec![],
+        }]);
+        assert!(region.contains(Lat(deg(2)), Lon(deg(2))));
+        assert!(!region.contains(Lat(deg(1)), Lon(deg(1))));
+        let (_, written) = run(&region, Strategy::Smart);
+        assert_eq!(written, ids(&[3], &[], &[19, 21, 22]));
+    }
+}
@@ -0,0 +1,286 @@
+//! Way geometries, from node locations
+//!
+//! [`WayRead::nodes`] only has node ids. Use [`way_coords`] to look up the locations in a
+//! [`NodeLocationStore`]. [`PBFWayGeometries`] does both passes over a PBF file for you.
+//! [`add_locations_to_ways`] writes a PBF file with the locations stored on the ways, so later
+//! readers can get them from [`WayRead::node_locations`].
+//!
+//! ```no_run
+//! use osmio::geometry::PBFWayGeometries;
+//! use osmio::OSMObjBaseRead;
+//! let ways = PBFWayGeometries::from_filename("region-latest.osm.pbf", |w| w.has_tag("building"))?;
+//! for (way, coords) in ways {
+//!     let coords = coords?;
+//!     println!("way {} has {} points", way.id(), coords.len());
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::idset::IdSet;
+use crate::nodestore::{NodeLocationStore, SparseStore};
+use crate::obj_types::{StringOSMObj, StringWay};
+use crate::pbf::{PBFReader, PBFWriter};
+use crate::{
+    Lat, Lon, NodeRead, OSMObjBaseRead, OSMObjectType, OSMReader, OSMWriter, ObjId, WayRead,
+};
+
+use anyhow::Result;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+/// Some nodes of a way aren't in the node location store
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingNodes {
+    pub way_id: ObjId,
+    /// The missing node ids, in way order. Nodes which are in the way more than once are
+    /// included more than once.
+    pub node_ids: Vec<ObjId>,
+}
+
+impl fmt::Display for MissingNodes {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "way {} has {} node(s) without a location: {:?}",
+            self.way_id,
+            self.node_ids.len(),
+            self.node_ids
+        )
+    }
+}
+
+impl std::error::Error for MissingNodes {}
+
+/// The location of every node in this way, in order.
+///
+/// If the way already has [`node_locations`](WayRead::node_locations), those are used.
+pub fn way_coords(
+    way: &impl WayRead,
+    store: &(impl NodeLocationStore + ?Sized),
+) -> Result<Vec<(Lat, Lon)>, MissingNodes> {
+    if let Some(locations) = way.node_locations() {
+        return Ok(locations.to_vec());
+    }
+    let mut coords = Vec::with_capacity(way.num_nodes());
+    let mut missing = Vec::new();
+    for &nid in way.nodes() {
+        match store.get(nid) {
+            Some(lat_lon) => coords.push(lat_lon),
+            None => missing.push(nid),
+        }
+    }
+    if missing.is_empty() {
+        Ok(coords)
+    } else {
+        Err(MissingNodes {
+            way_id: way.id(),
+            node_ids: missing,
+        })
+    }
+}
+
+/// Add the coordinates to each way.
+pub fn way_geometries<'a, W: WayRead + 'a>(
+    ways: impl IntoIterator<Item = W> + 'a,
+    store: &'a (impl NodeLocationStore + ?Sized),
+) -> impl Iterator<Item = (W, Result<Vec<(Lat, Lon)>, MissingNodes>)> + 'a {
+    ways.into_iter().map(move |way| {
+        let coords = way_coords(&way, store);
+        (way, coords)
+    })
+}
+
+/// Copy a PBF file, adding the node locations to every way (`LocationsOnWays`), like
+/// `osmium add-locations-to-ways`.
+///
+/// Node locations are stored in `store` as they are read, so the input must be sorted (nodes
+/// before ways). An error is returned if a way has a node which hasn't been seen. The writer is
+/// closed at the end.
+pub fn add_locations_to_ways<R: Read, W: Write>(
+    reader: &mut PBFReader<R>,
+    writer: &mut PBFWriter<W>,
+    store: &mut impl NodeLocationStore,
+) -> Result<()> {
+    writer.set_locations_on_ways(true)?;
+    while let Some(obj) = reader.next() {
+        match &obj {
+            StringOSMObj::Node(node) => {
+                if let Some(lat_lon) = node.lat_lon() {
+                    store.set(node.id(), lat_lon)?;
+                }
+                writer.write_obj(&obj)?;
+            }
+            StringOSMObj::Way(way) => {
+                let coords = way_coords(way, store)?;
+                writer.write_way_with_locations(way, &coords)?;
+            }
+            StringOSMObj::Relation(_) => writer.write_obj(&obj)?,
+        }
+    }
+    writer.close()?;
+    Ok(())
+}
+
+/// Reads a PBF file twice, and returns the ways you want with their coordinates.
+///
+/// The first pass finds which nodes are used by the ways. The second pass keeps the location of
+/// only those nodes, and returns each way (with coordinates) as it is read. The file must be
+/// sorted (nodes before ways), otherwise ways will have [`MissingNodes`].
+pub struct PBFWayGeometries<F: FnMut(&StringWay) -> bool> {
+    reader: PBFReader<BufReader<File>>,
+    store: SparseStore,
+    filter: F,
+}
+
+impl<F: FnMut(&StringWay) -> bool> PBFWayGeometries<F> {
+    /// Read the ways from `filename` for which `filter` returns true.
+    ///
+    /// This does the first pass.
+    pub fn from_filename(filename: impl AsRef<Path>, mut filter: F) -> Result<Self> {
+        let filename: &Path = filename.as_ref();
+
+        let mut needed = IdSet::new();
+        let mut reader = PBFReader::from_filename(filename)?;
+        for way in reader.ways() {
+            if filter(&way) {
+                needed.extend(way.nodes().iter().copied());
+            }
+        }
+
+        let mut reader = PBFReader::from_filename(filename)?;
+        reader.set_object_filter((true, true, false));
+        reader.set_id_filter(OSMObjectType::Node, Some(needed));
+        Ok(PBFWayGeometries {
+            reader,
+            store: SparseStore::new(),
+            filter,
+        })
+    }
+
+    /// The node locations read so far
+    pub fn store(&self) -> &SparseStore {
+        &self.store
+    }
+}
+
+impl<F: FnMut(&StringWay) -> bool> Iterator for PBFWayGeometries<F> {
+    type Item = (StringWay, Result<Vec<(Lat, Lon)>, MissingNodes>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.next()? {
+                StringOSMObj::Node(node) => {
+                    // Only needed nodes are read
+                    if let Some(lat_lon) = node.lat_lon() {
+                        // Can't fail for SparseStore
+                        self.store.set(node.id(), lat_lon).unwrap();
+                    }
+                }
+                StringOSMObj::Way(way) => {
+                    if (self.filter)(&way) {
+                        let coords = way_coords(&way, &self.store);
+                        return Some((way, coords));
+                    }
+                }
+                StringOSMObj::Relation(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodestore::HashMapStore;
+    use crate::obj_types::StringWayBuilder;
+
+    #[test]
+    fn coords() {
+        let mut store = HashMapStore::new();
+        store
+            .load(vec![(1, (Lat(10), Lon(20))), (2, (Lat(30), Lon(40)))])
+            .unwrap();
+        let way = StringWayBuilder::default()
+            ._id(100)
+            ._nodes(smallvec::smallvec![1, 2, 1])
+            .build()
+            .unwrap();
+        assert_eq!(
+            way_coords(&way, &store),
+            Ok(vec![
+                (Lat(10), Lon(20)),
+                (Lat(30), Lon(40)),
+                (Lat(10), Lon(20))
+            ])
+        );
+
+        let way = StringWayBuilder::default()
+            ._id(101)
+            ._nodes(smallvec::smallvec![1, 3, 4])
+            .build()
+            .unwrap();
+        let results: Vec<_> = way_geometries(vec![way], &store).collect();
+        assert_eq!(
+            results[0].1,
+            Err(MissingNodes {
+                way_id: 101,
+                node_ids: vec![3, 4]
+            })
+        );
+    }
+
+    #[test]
+    fn locations_on_ways() {
+        use crate::obj_types::StringNodeBuilder;
+
+        let node = |id, lat| -> StringOSMObj {
+            StringNodeBuilder::default()
+                ._id(id)
+                ._version(1)
+                ._changeset_id(1)
+                ._timestamp(0.into())
+                ._uid(1)
+                ._user("".into())
+                ._lat_lon((Lat(lat), Lon(lat)))
+                .build()
+                .unwrap()
+                .into()
+        };
+        let way: StringOSMObj = StringWayBuilder::default()
+            ._id(10)
+            ._version(1)
+            ._changeset_id(1)
+            ._timestamp(0.into())
+            ._uid(1)
+            ._user("".into())
+            ._nodes(smallvec::smallvec![2, 1])
+            .build()
+            .unwrap()
+            .into();
+
+        let mut input = Vec::new();
+        let mut writer = PBFWriter::new(&mut input);
+        for obj in [node(1, 10), node(2, 20), way] {
+            writer.write_obj(&obj).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        add_locations_to_ways(
+            &mut PBFReader::new(input.as_slice()),
+            &mut writer,
+            &mut HashMapStore::new(),
+        )
+        .unwrap();
+        drop(writer);
+
+        let way = PBFReader::new(output.as_slice()).ways().next().unwrap();
+        assert_eq!(
+            way.node_locations(),
+            Some(&[(Lat(20), Lon(20)), (Lat(10), Lon(10))][..])
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Callbacks for each object, like osmium's handlers
+//!
+//! Implement [`OSMHandler`] with the hooks you need, then [`apply`] a reader to several handlers
+//! in one pass. Implementing it with [`osm_handler!`](crate::osm_handler) means only the object
+//! types with hooks are read.
+//!
+//! ```no_run
+//! use osmio::handler::apply;
+//! use osmio::obj_types::StringWay;
+//! use osmio::OSMObjBaseRead;
+//!
+//! #[derive(Default)]
+//! struct Highways(usize);
+//!
+//! osmio::osm_handler! {
+//!     impl OSMHandler for Highways {
+//!         fn way(&mut self, way: &StringWay) {
+//!             if way.has_tag("highway") {
+//!                 self.0 += 1;
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! let mut highways = Highways::default();
+//! apply(&mut osmio::read_pbf("planet.osm.pbf")?, &mut [&mut highways]);
+//! println!("{} highways", highways.0);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::changesets::{Changeset, ChangesetReader};
+use crate::obj_types::StringOSMObj;
+use crate::{OSMObj, OSMReader};
+use anyhow::Result;
+use std::io::Read;
+
+/// Hooks which are called for each object. They all do nothing by default.
+#[allow(unused_variables)]
+pub trait OSMHandler<O: OSMObj = StringOSMObj> {
+    /// Which object types (nodes, ways, relations) this has hooks for. All of them by default.
+    ///
+    /// [`apply`] only reads the types some handler wants (e.g. PBF blocks of other types aren't
+    /// decoded), and only calls the hooks of these types. [`osm_handler!`](crate::osm_handler)
+    /// sets this from the hooks.
+    ///
+    /// **If you override this by hand, and return `false` for a type whose hook is overridden,
+    /// that hook is never called, and those objects are silently dropped.**
+    fn object_types(&self) -> (bool, bool, bool) {
+        (true, true, true)
+    }
+
+    fn node(&mut self, node: &O::Node) {}
+    fn way(&mut self, way: &O::Way) {}
+    fn relation(&mut self, relation: &O::Relation) {}
+    fn changeset(&mut self, changeset: &Changeset) {}
+
+    /// Called once, after all the objects
+    fn finish(&mut self) {}
+}
+
+/// Implement [`OSMHandler`], with [`object_types`](OSMHandler::object_types) set from the
+/// hooks which are written, so only the object types with hooks are read.
+///
+/// ```
+/// use osmio::obj_types::StringRelation;
+/// use osmio::handler::OSMHandler;
+///
+/// struct Relations(usize);
+///
+/// osmio::osm_handler! {
+///     impl OSMHandler for Relations {
+///         fn relation(&mut self, _: &StringRelation) {
+///             self.0 += 1;
+///         }
+///     }
+/// }
+/// assert_eq!(Relations(0).object_types(), (false, false, true));
+/// ```
+///
+/// Use `impl OSMHandler<ArcOSMObj> for …` for other object types.
+#[macro_export]
+macro_rules! osm_handler {
+    (
+        impl OSMHandler $(<$obj:ty>)? for $handler:ty {
+            $( $(#[$attr:meta])* fn $hook:ident $args:tt $body:block )*
+        }
+    ) => {
+        impl $crate::handler::OSMHandler $(<$obj>)? for $handler {
+            fn object_types(&self) -> (bool, bool, bool) {
+                let hooks: &[&str] = &[$(stringify!($hook)),*];
+                (
+                    hooks.contains(&"node"),
+                    hooks.contains(&"way"),
+                    hooks.contains(&"relation"),
+                )
+            }
+
+            $( $(#[$attr])* fn $hook $args $body )*
+        }
+    };
+}
+
+/// Call the hooks of all `handlers` for every object in `reader`, in order, then `finish`.
+///
+/// Only the types in the reader's object filter are read. While reading, the filter is narrowed to
+/// the types the handlers want, then restored afterwards.
+pub fn apply<R: OSMReader>(reader: &mut R, handlers: &mut [&mut dyn OSMHandler<R::Obj>]) {
+    let previous_filter = reader.get_object_filter();
+    let types: Vec<(bool, bool, bool)> = handlers
+        .iter()
+        .map(|h| h.object_types())
+        .map(|t| {
+            (
+                t.0 && previous_filter.0,
+                t.1 && previous_filter.1,
+                t.2 && previous_filter.2,
+            )
+        })
+        .collect();
+    let wanted = types.iter().fold((false, false, false), |acc, t| {
+        (acc.0 || t.0, acc.1 || t.1, acc.2 || t.2)
+    });
+    if wanted != (false, false, false) {
+        reader.set_object_filter(wanted);
+        while let Some(obj) = reader.next() {
+            let handlers = handlers.iter_mut().zip(types.iter());
+            if let Some(node) = obj.as_node() {
+                handlers
+                    .filter(|(_, t)| t.0)
+                    .for_each(|(h, _)| h.node(node));
+            } else if let Some(way) = obj.as_way() {
+                handlers.filter(|(_, t)| t.1).for_each(|(h, _)| h.way(way));
+            } else if let Some(relation) = obj.as_relation() {
+                handlers
+                    .filter(|(_, t)| t.2)
+                    .for_each(|(h, _)| h.relation(relation));
+            }
+        }
+        reader.set_object_filter(previous_filter);
+    }
+
+    for handler in handlers.iter_mut() {
+        handler.finish();
+    }
+}
+
+/// Call the `changeset` hook of all `handlers` for every changeset in `reader`, then `finish`.
+///
+/// Stops at the first error, without calling `finish`.
+pub fn apply_changesets<R: Read, O: OSMObj>(
+    reader: ChangesetReader<R>,
+    handlers: &mut [&mut dyn OSMHandler<O>],
+) -> Result<()> {
+    for changeset in reader {
+        let changeset = changeset?;
+        for handler in handlers.iter_mut() {
+            handler.changeset(&changeset);
+        }
+    }
+    for handler in handlers.iter_mut() {
+        handler.finish();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::{StringNode, StringRelation, StringWay};
+    use crate::xml::XMLReader;
+    use crate::{OSMObjBaseRead, RelationRead};
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="1" lon="1"><tag k="amenity" v="bench"/></node>
+  <node id="2" lat="2" lon="2"/>
+  <way id="10"><nd ref="1"/><nd ref="2"/><tag k="highway" v="path"/></way>
+  <relation id="20"><member type="way" ref="10" role="outer"/></relation>
+</osm>"#;
+
+    #[derive(Default)]
+    struct Counter {
+        counts: [usize; 3],
+        finished: bool,
+    }
+
+    impl OSMHandler for Counter {
+        fn node(&mut self, _: &StringNode) {
+            self.counts[0] += 1;
+        }
+        fn way(&mut self, _: &StringWay) {
+            self.counts[1] += 1;
+        }
+        fn relation(&mut self, _: &StringRelation) {
+            self.counts[2] += 1;
+        }
+        fn finish(&mut self) {
+            self.finished = true;
+        }
+    }
+
+    /// Only wants relations
+    #[derive(Default)]
+    struct Roles(Vec<String>);
+
+    crate::osm_handler! {
+        impl OSMHandler for Roles {
+            fn relation(&mut self, relation: &StringRelation) {
+                self.0
+                    .extend(relation.members().map(|(_, _, role)| role.to_string()));
+            }
+            /// Not a type of object
+            fn finish(&mut self) {}
+        }
+    }
+
+    #[test]
+    fn several_handlers() {
+        let mut counter = Counter::default();
+        let mut roles = Roles::default();
+        apply(
+            &mut XMLReader::new(XML.as_bytes()),
+            &mut [&mut counter, &mut roles],
+        );
+        assert_eq!(counter.counts, [2, 1, 1]);
+        assert!(counter.finished);
+        assert_eq!(roles.0, ["outer"]);
+    }
+
+    #[test]
+    fn object_types_from_hooks() {
+        use crate::obj_types::{ArcNode, ArcOSMObj, ArcWay};
+        assert_eq!(Roles::default().object_types(), (false, false, true));
+
+        struct NodesAndWays;
+        crate::osm_handler! {
+            impl OSMHandler<ArcOSMObj> for NodesAndWays {
+                fn node(&mut self, _: &ArcNode) {}
+                fn way(&mut self, _: &ArcWay) {}
+            }
+        }
+        assert_eq!(NodesAndWays.object_types(), (true, true, false));
+        // By hand, all types by default
+        assert_eq!(Counter::default().object_types(), (true, true, true));
+    }
+
+    #[test]
+    fn changesets() {
+        #[derive(Default)]
+        struct Comments(Vec<String>);
+        impl OSMHandler for Comments {
+            fn changeset(&mut self, changeset: &Changeset) {
+                self.0.extend(changeset.tag("comment").map(String::from));
+            }
+        }
+
+        let xml = r#"<osm><changeset id="1" created_at="2020-01-01T00:00:00Z" open="false" num_changes="1" comments_count="0"><tag k="comment" v="Add bench"/></changeset></osm>"#;
+        let mut comments = Comments::default();
+        let handlers: &mut [&mut dyn OSMHandler] = &mut [&mut comments];
+        apply_changesets(ChangesetReader::new(xml.as_bytes()), handlers).unwrap();
+        assert_eq!(comments.0, ["Add bench"]);
+    }
+
+    #[test]
+    fn nothing_wanted() {
+        struct Nothing;
+        impl OSMHandler for Nothing {
+            fn object_types(&self) -> (bool, bool, bool) {
+                (false, false, false)
+            }
+        }
+        let mut reader = XMLReader::new(XML.as_bytes());
+        apply(&mut reader, &mut [&mut Nothing]);
+        assert_eq!(reader.next().map(|o| o.id()), Some(1));
+    }
+
+    #[test]
+    fn filter_restored() {
+        use crate::OSMWriter;
+        use crate::stringpbf::{PBFReader, PBFWriter};
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        for obj in XMLReader::new(XML.as_bytes()).objects() {
+            writer.write_obj(&obj).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        // Relations are excluded by the caller
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.set_object_filter((true, true, false));
+        let mut counter = Counter::default();
+        let mut roles = Roles::default();
+        apply(&mut reader, &mut [&mut counter, &mut roles]);
+        assert_eq!(counter.counts, [2, 1, 0]);
+        assert!(roles.0.is_empty());
+        assert_eq!(reader.get_object_filter(), (true, true, false));
+
+        // Narrowed to what the handlers want while reading
+        let mut reader = PBFReader::new(output.as_slice());
+        let mut roles = Roles::default();
+        apply(&mut reader, &mut [&mut roles]);
+        assert_eq!(roles.0, ["outer"]);
+        assert_eq!(reader.get_object_filter(), (true, true, true));
+    }
+}
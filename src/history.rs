@@ -0,0 +1,366 @@
+//! Time travel over full history files
+//!
+//! A history file (e.g. `.osh`) has every version of every object, sorted by type, id, then
+//! version, with deleted objects as `visible="false"` versions.
+//!
+//! ```no_run
+//! use osmio::OSMReader;
+//! use osmio::history::state_at;
+//! let mut reader = osmio::xml::from_filename("history.osh.gz")?;
+//! let time = "2015-01-01T00:00:00Z".parse()?;
+//! for obj in state_at(reader.objects(), time) {
+//!     // the object as it was at the start of 2015
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! [`histories`] groups the versions of each object into an [`ObjectHistory`], and
+//! [`VersionDiff`] shows what changed between 2 versions.
+use crate::{NodeRead, OSMObj, OSMObjBase, OSMObjectType, ObjId, RelationRead, Timestamp, WayRead};
+
+/// Iterator returned by [`state_at`]
+pub struct StateAt<O, I> {
+    inner: I,
+    time: Timestamp,
+    last_key: Option<(OSMObjectType, ObjId)>,
+    /// Latest version of the current object at `time`
+    pending: Option<O>,
+}
+
+/// The objects as they were at `time`.
+///
+/// For each object, this is the latest version with a timestamp at or before `time`. Objects
+/// which were deleted then, or created after, are skipped. Versions without a timestamp are
+/// ignored. `objects` must be sorted.
+pub fn state_at<O, I>(objects: I, time: Timestamp) -> StateAt<O, I::IntoIter>
+where
+    O: OSMObjBase,
+    I: IntoIterator<Item = O>,
+{
+    StateAt {
+        inner: objects.into_iter(),
+        time,
+        last_key: None,
+        pending: None,
+    }
+}
+
+impl<O, I> Iterator for StateAt<O, I>
+where
+    O: OSMObjBase,
+    I: Iterator<Item = O>,
+{
+    type Item = O;
+
+    fn next(&mut self) -> Option<O> {
+        loop {
+            let Some(obj) = self.inner.next() else {
+                return self.pending.take().filter(|o| !o.deleted());
+            };
+            let key = (obj.object_type(), obj.id());
+            let in_time = obj.timestamp().is_some_and(|t| t <= self.time);
+            if self.last_key != Some(key) {
+                self.last_key = Some(key);
+                let previous = std::mem::replace(&mut self.pending, in_time.then_some(obj));
+                if let Some(previous) = previous
+                    && !previous.deleted()
+                {
+                    return Some(previous);
+                }
+            } else if in_time {
+                self.pending = Some(obj);
+            }
+        }
+    }
+}
+
+/// All the versions (including deletions) with a timestamp after `from`, and at or before
+/// `to`.
+///
+/// These are the changes which turn `state_at(from)` into `state_at(to)`. Versions without a
+/// timestamp are ignored.
+pub fn changes_between<O, I>(objects: I, from: Timestamp, to: Timestamp) -> impl Iterator<Item = O>
+where
+    O: OSMObjBase,
+    I: IntoIterator<Item = O>,
+{
+    objects
+        .into_iter()
+        .filter(move |o| o.timestamp().is_some_and(|t| from < t && t <= to))
+}
+
+/// All the versions of one object, oldest first. Never empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectHistory<O> {
+    versions: Vec<O>,
+}
+
+impl<O: OSMObj> ObjectHistory<O> {
+    pub fn object_type(&self) -> OSMObjectType {
+        self.versions[0].object_type()
+    }
+
+    pub fn id(&self) -> ObjId {
+        self.versions[0].id()
+    }
+
+    pub fn versions(&self) -> &[O] {
+        &self.versions
+    }
+
+    pub fn into_versions(self) -> Vec<O> {
+        self.versions
+    }
+
+    pub fn first(&self) -> &O {
+        &self.versions[0]
+    }
+
+    pub fn latest(&self) -> &O {
+        &self.versions[self.versions.len() - 1]
+    }
+
+    /// What changed in each version, compared to the version before it. The first version has
+    /// no diff.
+    pub fn diffs(&self) -> impl Iterator<Item = VersionDiff> + '_ {
+        self.versions
+            .windows(2)
+            .map(|w| VersionDiff::between(&w[0], &w[1]))
+    }
+}
+
+/// Iterator returned by [`histories`]
+pub struct Histories<O, I> {
+    inner: I,
+    /// First version of the next object
+    next_first: Option<O>,
+}
+
+/// Group consecutive versions of the same object. `objects` must be sorted.
+pub fn histories<O, I>(objects: I) -> Histories<O, I::IntoIter>
+where
+    O: OSMObj,
+    I: IntoIterator<Item = O>,
+{
+    Histories {
+        inner: objects.into_iter(),
+        next_first: None,
+    }
+}
+
+impl<O, I> Iterator for Histories<O, I>
+where
+    O: OSMObj,
+    I: Iterator<Item = O>,
+{
+    type Item = ObjectHistory<O>;
+
+    fn next(&mut self) -> Option<ObjectHistory<O>> {
+        let first = self.next_first.take().or_else(|| self.inner.next())?;
+        let key = (first.object_type(), first.id());
+        let mut versions = vec![first];
+        for obj in self.inner.by_ref() {
+            if (obj.object_type(), obj.id()) != key {
+                self.next_first = Some(obj);
+                break;
+            }
+            versions.push(obj);
+        }
+        Some(ObjectHistory { versions })
+    }
+}
+
+/// What changed between 2 versions of an object
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct VersionDiff {
+    pub old_version: Option<u32>,
+    pub new_version: Option<u32>,
+    /// The new version is a deletion
+    pub deleted: bool,
+    /// The old version was a deletion, and the new one isn't
+    pub undeleted: bool,
+    /// (key, value)
+    pub tags_added: Vec<(String, String)>,
+    /// (key, old value)
+    pub tags_removed: Vec<(String, String)>,
+    /// (key, old value, new value)
+    pub tags_changed: Vec<(String, String, String)>,
+    /// How many metres a node moved, if both versions have a location, and they differ.
+    pub moved: Option<f64>,
+    pub nodes_changed: bool,
+    pub members_changed: bool,
+    /// Metadata of the new version
+    pub changeset_id: Option<u32>,
+    pub uid: Option<u32>,
+    pub user: Option<String>,
+    pub timestamp: Option<Timestamp>,
+}
+
+impl VersionDiff {
+    /// Compare 2 versions of the same object
+    pub fn between<O: OSMObj>(old: &O, new: &O) -> Self {
+        let mut diff = VersionDiff {
+            old_version: old.version(),
+            new_version: new.version(),
+            deleted: new.deleted() && !old.deleted(),
+            undeleted: old.deleted() && !new.deleted(),
+            changeset_id: new.changeset_id(),
+            uid: new.uid(),
+            user: new.user().map(|u| u.to_string()),
+            timestamp: new.timestamp(),
+            ..Default::default()
+        };
+
+        for (k, v) in new.tags() {
+            match old.tag(k) {
+                None => diff.tags_added.push((k.to_string(), v.to_string())),
+                Some(old_v) if old_v != v => {
+                    diff.tags_changed
+                        .push((k.to_string(), old_v.to_string(), v.to_string()))
+                }
+                Some(_) => {}
+            }
+        }
+        for (k, v) in old.tags() {
+            if !new.has_tag(k) {
+                diff.tags_removed.push((k.to_string(), v.to_string()));
+            }
+        }
+
+        if let (Some(old), Some(new)) = (old.as_node(), new.as_node())
+            && let (Some(old_loc), Some(new_loc)) = (old.lat_lon(), new.lat_lon())
+            && old_loc != new_loc
+        {
+            diff.moved = Some(distance_m(
+                (old_loc.0.degrees(), old_loc.1.degrees()),
+                (new_loc.0.degrees(), new_loc.1.degrees()),
+            ));
+        }
+        if let (Some(old), Some(new)) = (old.as_way(), new.as_way()) {
+            diff.nodes_changed = old.nodes() != new.nodes();
+        }
+        if let (Some(old), Some(new)) = (old.as_relation(), new.as_relation()) {
+            diff.members_changed = !old.members().eq(new.members());
+        }
+
+        diff
+    }
+
+    /// True iff only the metadata changed, not the tags, location, nodes or members.
+    pub fn is_metadata_only(&self) -> bool {
+        !self.deleted
+            && !self.undeleted
+            && self.tags_added.is_empty()
+            && self.tags_removed.is_empty()
+            && self.tags_changed.is_empty()
+            && self.moved.is_none()
+            && !self.nodes_changed
+            && !self.members_changed
+    }
+}
+
+/// Great circle distance in metres, between 2 (lat, lon)s in degrees
+fn distance_m((lat1, lon1): (f64, f64), (lat2, lon2): (f64, f64)) -> f64 {
+    const EARTH_RADIUS_M: f64 = 6_371_008.8;
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let d_lat = lat2 - lat1;
+    let d_lon = (lon2 - lon1).to_radians();
+    let a = (d_lat / 2.).sin().powi(2) + lat1.cos() * lat2.cos() * (d_lon / 2.).sin().powi(2);
+    2. * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::StringOSMObj;
+    use crate::xml::XMLReader;
+    use crate::{OSMObjBaseRead, OSMReader};
+
+    const HISTORY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+ <node id="1" version="1" visible="true" timestamp="2010-01-01T00:00:00Z" lat="1" lon="1"/>
+ <node id="1" version="2" visible="true" timestamp="2012-01-01T00:00:00Z" lat="2" lon="1" user="bob" changeset="7">
+  <tag k="name" v="Shop"/>
+ </node>
+ <node id="1" version="3" visible="false" timestamp="2014-01-01T00:00:00Z"/>
+ <node id="2" version="1" visible="true" timestamp="2013-01-01T00:00:00Z" lat="1" lon="1"/>
+ <node id="3" version="1" visible="true" timestamp="2009-01-01T00:00:00Z" lat="1" lon="1"/>
+ <way id="1" version="1" visible="true" timestamp="2011-01-01T00:00:00Z">
+  <nd ref="1"/>
+  <nd ref="3"/>
+ </way>
+ <way id="1" version="2" visible="true" timestamp="2015-01-01T00:00:00Z">
+  <nd ref="3"/>
+  <nd ref="2"/>
+ </way>
+</osm>"#;
+
+    fn objects() -> Vec<StringOSMObj> {
+        XMLReader::new(HISTORY.as_bytes()).objects().collect()
+    }
+
+    fn versions(objs: impl Iterator<Item = StringOSMObj>) -> Vec<(OSMObjectType, ObjId, u32)> {
+        objs.map(|o| (o.object_type(), o.id(), o.version().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn state() {
+        use OSMObjectType::*;
+        let at = |time: &str| versions(state_at(objects(), time.parse().unwrap()));
+        assert_eq!(at("2000-01-01T00:00:00Z"), vec![]);
+        assert_eq!(
+            at("2012-01-01T00:00:00Z"),
+            vec![(Node, 1, 2), (Node, 3, 1), (Way, 1, 1)]
+        );
+        assert_eq!(
+            at("2014-06-01T00:00:00Z"),
+            vec![(Node, 2, 1), (Node, 3, 1), (Way, 1, 1)]
+        );
+        assert_eq!(
+            at("2020-01-01T00:00:00Z"),
+            vec![(Node, 2, 1), (Node, 3, 1), (Way, 1, 2)]
+        );
+    }
+
+    #[test]
+    fn changes() {
+        use OSMObjectType::*;
+        let changes = versions(changes_between(
+            objects(),
+            "2012-01-01T00:00:00Z".parse().unwrap(),
+            "2015-01-01T00:00:00Z".parse().unwrap(),
+        ));
+        assert_eq!(changes, vec![(Node, 1, 3), (Node, 2, 1), (Way, 1, 2)]);
+    }
+
+    #[test]
+    fn grouping_and_diffs() {
+        let histories: Vec<_> = histories(objects()).collect();
+        assert_eq!(histories.len(), 4);
+        assert_eq!(histories[0].id(), 1);
+        assert_eq!(histories[0].versions().len(), 3);
+        assert_eq!(histories[3].object_type(), OSMObjectType::Way);
+        assert_eq!(histories[3].latest().version(), Some(2));
+
+        let diffs: Vec<_> = histories[0].diffs().collect();
+        assert_eq!(diffs.len(), 2);
+        assert_eq!(diffs[0].new_version, Some(2));
+        assert_eq!(
+            diffs[0].tags_added,
+            vec![("name".to_string(), "Shop".to_string())]
+        );
+        assert_eq!(diffs[0].user.as_deref(), Some("bob"));
+        assert_eq!(diffs[0].changeset_id, Some(7));
+        // 1° of latitude
+        assert!((diffs[0].moved.unwrap() - 111_195.).abs() < 1.);
+        assert!(diffs[1].deleted);
+        assert_eq!(diffs[1].tags_removed.len(), 1);
+        assert!(!diffs[1].is_metadata_only());
+
+        let way_diff = histories[3].diffs().next().unwrap();
+        assert!(way_diff.nodes_changed);
+        assert!(!way_diff.members_changed);
+        assert!(histories[1].diffs().next().is_none());
+    }
+}
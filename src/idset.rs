@@ -0,0 +1,322 @@
+//! A compact set of object ids
+//!
+//! OSM ids are dense-ish, increasing numbers, which a `HashSet<ObjId>` stores very inefficiently.
+//! [`IdSet`] splits ids into chunks of 65,536. Chunks with few ids store a sorted list, and
+//! chunks with many are a bitmap (8 KiB), like a [roaring bitmap](https://roaringbitmap.org/).
+//!
+//! ```
+//! use osmio::idset::IdSet;
+//! let mut ids = IdSet::new();
+//! ids.insert(1);
+//! ids.insert(12_345_678_901);
+//! assert!(ids.contains(1));
+//! assert!(!ids.contains(2));
+//! assert_eq!(ids.iter().collect::<Vec<_>>(), vec![1, 12_345_678_901]);
+//! ```
+use crate::ObjId;
+use std::collections::BTreeMap;
+
+/// Number of bits in the part of the id stored in a chunk
+const CHUNK_BITS: u32 = 16;
+const CHUNK_MASK: ObjId = (1 << CHUNK_BITS) - 1;
+/// Number of u64's in a bitmap chunk
+const BITMAP_WORDS: usize = (1 << CHUNK_BITS) / 64;
+/// A list chunk with more ids than this is converted to a bitmap. A list of 4096 u16's is the
+/// same size as a bitmap.
+const MAX_LIST_LEN: usize = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Chunk {
+    /// Sorted
+    List(Vec<u16>),
+    Bitmap {
+        bits: Box<[u64; BITMAP_WORDS]>,
+        len: u32,
+    },
+}
+
+impl Chunk {
+    fn contains(&self, low: u16) -> bool {
+        match self {
+            Chunk::List(list) => list.binary_search(&low).is_ok(),
+            Chunk::Bitmap { bits, .. } => bits[low as usize / 64] & (1 << (low % 64)) != 0,
+        }
+    }
+
+    /// Returns true if it wasn't already present
+    fn insert(&mut self, low: u16) -> bool {
+        match self {
+            Chunk::List(list) => {
+                // Ids are often inserted in order
+                if list.last().is_none_or(|&last| last < low) {
+                    list.push(low);
+                } else {
+                    match list.binary_search(&low) {
+                        Ok(_) => return false,
+                        Err(idx) => list.insert(idx, low),
+                    }
+                }
+                if list.len() > MAX_LIST_LEN {
+                    self.convert_to_bitmap();
+                }
+                true
+            }
+            Chunk::Bitmap { bits, len } => {
+                let word = &mut bits[low as usize / 64];
+                let bit = 1 << (low % 64);
+                if *word & bit != 0 {
+                    false
+                } else {
+                    *word |= bit;
+                    *len += 1;
+                    true
+                }
+            }
+        }
+    }
+
+    /// Returns true if it was present
+    fn remove(&mut self, low: u16) -> bool {
+        match self {
+            Chunk::List(list) => match list.binary_search(&low) {
+                Ok(idx) => {
+                    list.remove(idx);
+                    true
+                }
+                Err(_) => false,
+            },
+            Chunk::Bitmap { bits, len } => {
+                let word = &mut bits[low as usize / 64];
+                let bit = 1 << (low % 64);
+                if *word & bit == 0 {
+                    false
+                } else {
+                    *word &= !bit;
+                    *len -= 1;
+                    true
+                }
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Chunk::List(list) => list.len(),
+            Chunk::Bitmap { len, .. } => *len as usize,
+        }
+    }
+
+    fn convert_to_bitmap(&mut self) {
+        if let Chunk::List(list) = self {
+            let mut bits = Box::new([0u64; BITMAP_WORDS]);
+            for &low in list.iter() {
+                bits[low as usize / 64] |= 1 << (low % 64);
+            }
+            *self = Chunk::Bitmap {
+                bits,
+                len: list.len() as u32,
+            };
+        }
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            Chunk::List(list) => Box::new(list.iter().copied()),
+            Chunk::Bitmap { bits, .. } => {
+                Box::new(bits.iter().enumerate().flat_map(|(word_idx, &word)| {
+                    (0..64)
+                        .filter(move |bit| word & (1 << bit) != 0)
+                        .map(move |bit| (word_idx * 64 + bit) as u16)
+                }))
+            }
+        }
+    }
+
+    fn union_with(&mut self, other: &Chunk) {
+        match (&mut *self, other) {
+            (
+                Chunk::Bitmap { bits, len },
+                Chunk::Bitmap {
+                    bits: other_bits, ..
+                },
+            ) => {
+                for (word, other_word) in bits.iter_mut().zip(other_bits.iter()) {
+                    *word |= other_word;
+                }
+                *len = bits.iter().map(|w| w.count_ones()).sum();
+            }
+            (Chunk::List(list), Chunk::List(other_list))
+                if list.len() + other_list.len() <= MAX_LIST_LEN =>
+            {
+                list.extend_from_slice(other_list);
+                list.sort_unstable();
+                list.dedup();
+            }
+            _ => {
+                for low in other.iter() {
+                    self.insert(low);
+                }
+            }
+        }
+    }
+}
+
+fn split(id: ObjId) -> (ObjId, u16) {
+    (id >> CHUNK_BITS, (id & CHUNK_MASK) as u16)
+}
+
+/// A set of object ids. Negative ids are supported.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdSet {
+    chunks: BTreeMap<ObjId, Chunk>,
+    len: usize,
+}
+
+impl IdSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add this id. Returns true if it wasn't already in the set.
+    pub fn insert(&mut self, id: ObjId) -> bool {
+        let (high, low) = split(id);
+        let added = self
+            .chunks
+            .entry(high)
+            .or_insert_with(|| Chunk::List(Vec::new()))
+            .insert(low);
+        if added {
+            self.len += 1;
+        }
+        added
+    }
+
+    /// Remove this id. Returns true if it was in the set.
+    pub fn remove(&mut self, id: ObjId) -> bool {
+        let (high, low) = split(id);
+        let Some(chunk) = self.chunks.get_mut(&high) else {
+            return false;
+        };
+        let removed = chunk.remove(low);
+        if removed {
+            self.len -= 1;
+            if chunk.len() == 0 {
+                self.chunks.remove(&high);
+            }
+        }
+        removed
+    }
+
+    pub fn contains(&self, id: ObjId) -> bool {
+        let (high, low) = split(id);
+        self.chunks
+            .get(&high)
+            .is_some_and(|chunk| chunk.contains(low))
+    }
+
+    /// Number of ids in this set
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// All the ids, in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = ObjId> + '_ {
+        self.chunks.iter().flat_map(|(&high, chunk)| {
+            chunk
+                .iter()
+                .map(move |low| (high << CHUNK_BITS) | low as ObjId)
+        })
+    }
+
+    /// Add all the ids in `other` to this set.
+    pub fn union_with(&mut self, other: &IdSet) {
+        for (high, other_chunk) in other.chunks.iter() {
+            match self.chunks.get_mut(high) {
+                Some(chunk) => chunk.union_with(other_chunk),
+                None => {
+                    self.chunks.insert(*high, other_chunk.clone());
+                }
+            }
+        }
+        self.len = self.chunks.values().map(|c| c.len()).sum();
+    }
+
+    /// Approximate number of bytes of memory used
+    pub fn memory_usage(&self) -> usize {
+        self.chunks
+            .values()
+            .map(|chunk| match chunk {
+                Chunk::List(list) => list.capacity() * 2,
+                Chunk::Bitmap { .. } => BITMAP_WORDS * 8,
+            } + std::mem::size_of::<(ObjId, Chunk)>())
+            .sum()
+    }
+}
+
+impl Extend<ObjId> for IdSet {
+    fn extend<T: IntoIterator<Item = ObjId>>(&mut self, iter: T) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl FromIterator<ObjId> for IdSet {
+    fn from_iter<T: IntoIterator<Item = ObjId>>(iter: T) -> Self {
+        let mut ids = IdSet::new();
+        ids.extend(iter);
+        ids
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_contains_iter() {
+        let mut ids = IdSet::new();
+        assert!(ids.is_empty());
+        // Enough in one chunk to become a bitmap
+        for id in (0..20_000).rev() {
+            assert!(ids.insert(id * 3));
+        }
+        assert!(!ids.insert(3));
+        assert!(ids.insert(-5));
+        assert!(ids.insert(10_000_000_000));
+        assert_eq!(ids.len(), 20_002);
+
+        assert!(ids.contains(0));
+        assert!(ids.contains(59_997));
+        assert!(!ids.contains(59_998));
+        assert!(ids.contains(-5));
+        assert!(!ids.contains(-6));
+
+        let all: Vec<_> = ids.iter().collect();
+        assert_eq!(all.len(), 20_002);
+        assert_eq!(&all[..3], &[-5, 0, 3]);
+        assert_eq!(all.last(), Some(&10_000_000_000));
+        assert!(all.windows(2).all(|w| w[0] < w[1]));
+
+        assert!(ids.remove(3));
+        assert!(!ids.remove(3));
+        assert!(!ids.contains(3));
+        assert_eq!(ids.len(), 20_001);
+    }
+
+    #[test]
+    fn union() {
+        let mut a: IdSet = (0..5000).map(|i| i * 2).collect();
+        let b: IdSet = (0..5000).map(|i| i * 2 + 1).chain([1_000_000]).collect();
+        a.union_with(&b);
+        assert_eq!(a.len(), 10_001);
+        assert_eq!(
+            a.iter().collect::<Vec<_>>(),
+            (0..10_000).chain([1_000_000]).collect::<Vec<_>>()
+        );
+    }
+}
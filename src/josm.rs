@@ -0,0 +1,199 @@
+//! JOSM XML files
+//!
+//! JOSM saves `.osm` files which are OSM XML, with some extras:
+//!
+//! * New objects have negative IDs.
+//! * Changed objects have an `action="modify"` or `action="delete"` attribute.
+//! * The root `<osm>` element can have `upload="false"` (or `never`) & `locked="true"`.
+//!
+//! Use [`XMLReader::next_with_info`](crate::xml::XMLReader::next_with_info) to get the action of
+//! each object, and [`XMLReader::header`](crate::xml::XMLReader::header) for the root flags.
+//! [`to_osmchange`] converts a JOSM file to an osmChange file for uploading.
+use crate::obj_types::StringOSMObj;
+use crate::osc::{OSCAction, OSCWriter};
+use crate::xml::XMLReader;
+use crate::{OSMObjBaseRead, OSMObjectType, OSMWriter};
+use anyhow::Result;
+use std::fmt;
+use std::io::{Read, Write};
+use std::str::FromStr;
+
+/// The `action` attribute JOSM puts on objects that have been changed locally.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JosmAction {
+    Modify,
+    Delete,
+}
+
+impl JosmAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JosmAction::Modify => "modify",
+            JosmAction::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for JosmAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for JosmAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "modify" => Ok(JosmAction::Modify),
+            "delete" => Ok(JosmAction::Delete),
+            _ => Err(format!("Unknown JOSM action {}", s)),
+        }
+    }
+}
+
+/// The `upload` attribute on the root element of a JOSM file, which says whether the data should
+/// be uploaded to OSM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadPolicy {
+    /// `upload="true"`, the default
+    Normal,
+    /// `upload="false"`, JOSM warns before uploading
+    Discouraged,
+    /// `upload="never"`, JOSM will not upload this
+    Blocked,
+}
+
+impl UploadPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UploadPolicy::Normal => "true",
+            UploadPolicy::Discouraged => "false",
+            UploadPolicy::Blocked => "never",
+        }
+    }
+}
+
+impl fmt::Display for UploadPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for UploadPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "true" => Ok(UploadPolicy::Normal),
+            "false" => Ok(UploadPolicy::Discouraged),
+            "never" => Ok(UploadPolicy::Blocked),
+            _ => Err(format!("Unknown upload policy {}", s)),
+        }
+    }
+}
+
+/// Which osmChange section this JOSM object belongs in, if any.
+///
+/// New objects (negative ID) are created, unless they were deleted again. Unchanged objects are
+/// not part of the change.
+pub fn change_action(obj: &impl OSMObjBaseRead, action: Option<JosmAction>) -> Option<OSCAction> {
+    match (obj.id() < 0, action) {
+        (true, Some(JosmAction::Delete)) => None,
+        (true, _) => Some(OSCAction::Create),
+        (false, Some(JosmAction::Modify)) => Some(OSCAction::Modify),
+        (false, Some(JosmAction::Delete)) => Some(OSCAction::Delete),
+        (false, None) => None,
+    }
+}
+
+/// Convert a JOSM file into an osmChange file, with `create`, `modify` & `delete` sections.
+///
+/// Created & modified objects are written in file order. Deleted objects are written at the end,
+/// relations first, then ways, then nodes, so that nothing is deleted while still in use. The
+/// writer is closed at the end.
+///
+/// An error is returned if the JOSM file says it should never be uploaded.
+pub fn to_osmchange<R: Read, W: Write>(
+    reader: &mut XMLReader<R>,
+    writer: &mut OSCWriter<W>,
+) -> Result<()> {
+    if reader.header()?.upload == Some(UploadPolicy::Blocked) {
+        anyhow::bail!("This JOSM file has upload=\"never\", and should not be uploaded");
+    }
+
+    let mut deletes: Vec<StringOSMObj> = Vec::new();
+    while let Some((obj, info)) = reader.next_with_info()? {
+        match change_action(&obj, info.action) {
+            None => {}
+            Some(OSCAction::Delete) => deletes.push(obj),
+            Some(action) => writer.write_change(action, &obj)?,
+        }
+    }
+
+    let delete_order = |t: OSMObjectType| match t {
+        OSMObjectType::Relation => 0,
+        OSMObjectType::Way => 1,
+        OSMObjectType::Node => 2,
+    };
+    deletes.sort_by_key(|o| delete_order(o.object_type()));
+    for obj in deletes.iter() {
+        writer.write_change(OSCAction::Delete, obj)?;
+    }
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OSMReader;
+    use crate::osc::OSCReader;
+
+    #[test]
+    fn josm_to_osmchange() {
+        let input = r#"<?xml version='1.0' encoding='UTF-8'?>
+<osm version='0.6' upload='false' generator='JOSM'>
+  <node id='-1' action='modify' lat='51.5' lon='-0.1'>
+    <tag k='amenity' v='bench' />
+  </node>
+  <node id='-2' action='delete' lat='51.5' lon='-0.1' />
+  <node id='10' version='3' lat='51.5' lon='-0.1' />
+  <node id='11' version='2' action='delete' lat='51.5' lon='-0.1' />
+  <way id='20' version='5' action='modify'>
+    <nd ref='10' />
+    <nd ref='-1' />
+  </way>
+  <way id='21' version='1' action='delete'>
+    <nd ref='10' />
+    <nd ref='11' />
+  </way>
+</osm>"#;
+        let mut reader = XMLReader::new(input.as_bytes());
+        assert_eq!(
+            reader.header().unwrap().upload,
+            Some(UploadPolicy::Discouraged)
+        );
+
+        let mut output = Vec::new();
+        let mut writer = OSCWriter::new(&mut output);
+        to_osmchange(&mut reader, &mut writer).unwrap();
+        drop(writer);
+
+        let mut reader = OSCReader::new(output.as_slice());
+        let mut changes = Vec::new();
+        while let Some((action, obj)) = reader.next_change().unwrap() {
+            changes.push((action, obj.object_type(), obj.id()));
+        }
+        assert_eq!(
+            changes,
+            vec![
+                (OSCAction::Create, OSMObjectType::Node, -1),
+                (OSCAction::Modify, OSMObjectType::Way, 20),
+                (OSCAction::Delete, OSMObjectType::Way, 21),
+                (OSCAction::Delete, OSMObjectType::Node, 11),
+            ]
+        );
+    }
+}
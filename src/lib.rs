@@ -16,16 +16,15 @@ extern crate chrono;
 extern crate flate2;
 extern crate protobuf;
 extern crate quick_xml;
-extern crate xml as xml_rs;
 #[macro_use]
 extern crate derive_builder;
 extern crate anyhow;
 extern crate bzip2;
-extern crate serde;
 extern crate serde_json;
 extern crate smallvec;
 extern crate smol_str;
 
+#[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
@@ -37,7 +36,6 @@ use std::io::{BufReader, Read, Write};
 use std::iter::{ExactSizeIterator, Iterator};
 use std::path::Path;
 use std::str::FromStr;
-use utils::{epoch_to_iso, iso_to_epoch};
 
 use anyhow::Result;
 
@@ -45,8 +43,22 @@ use anyhow::Result;
 pub mod utils;
 
 pub mod arcpbf;
+pub mod compression;
+pub mod extract;
+pub mod geometry;
+pub mod handler;
+pub mod history;
+pub mod idset;
+pub mod josm;
+pub mod multipolygon;
+pub mod nodestore;
 pub mod pbf;
+pub mod poly;
+pub mod progress;
+pub mod sort;
 pub mod stringpbf;
+pub mod tagfilter;
+pub mod timestamp;
 pub mod xml;
 //pub mod opl;
 pub mod osc;
@@ -58,6 +70,8 @@ mod tests;
 
 pub mod changesets;
 
+pub use timestamp::{ParseTimestampError, Timestamp};
+
 /// Type that stores the OSM Id
 pub type ObjId = i64;
 
@@ -73,10 +87,10 @@ pub const COORD_SCALE_FACTOR: f64 = (1_000_000_000 / COORD_PRECISION_NANOS) as f
 pub mod prelude {
     //! Useful things for osmio
     pub use crate::OSMObj;
-    pub use crate::OSMObjBase;
     pub use crate::OSMObjectType;
     pub use crate::OSMReader;
     pub use crate::{Node, Relation, Way};
+    pub use crate::{NodeRead, OSMObjBase, OSMObjBaseRead, RelationRead, WayRead};
 }
 
 pub fn lat_lon_inner_to_degrees(inner: i32) -> f64 {
@@ -97,7 +111,8 @@ macro_rules! lat_lon_impl {
         /// let float_lat: f64 = lat.into();
         /// assert_eq!(float_lat, 1.);
         /// ```
-        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+        #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         pub struct $lat_or_lon(i32);
 
         impl $lat_or_lon {
@@ -241,93 +256,82 @@ impl From<std::num::ParseFloatError> for ParseLatLonError {
     }
 }
 
-/// Timestamps can be stored as an ISO formatted string, or number of seconds since unix epoch
+/// A rectangular area, e.g. the `<bounds>` of an XML file.
 ///
-/// In XML files, timestamps are represented as ISO strings, and in PBF files, as integer seconds
-/// since the epoch
-#[derive(Debug, Clone, Eq, Serialize, Deserialize)]
-pub enum TimestampFormat {
-    ISOString(String),
-    EpochNumber(i64),
-}
-
-impl TimestampFormat {
-    pub fn to_iso_string(&self) -> String {
-        match self {
-            TimestampFormat::ISOString(s) => s.clone(),
-            TimestampFormat::EpochNumber(t) => epoch_to_iso(*t as i32),
+/// ```
+/// use osmio::{BBox, Lat, Lon};
+/// let bbox = BBox::new(Lat::from_inner(0), Lon::from_inner(0), Lat::from_inner(10), Lon::from_inner(10));
+/// assert!(bbox.contains(Lat::from_inner(5), Lon::from_inner(5)));
+/// assert!(!bbox.contains(Lat::from_inner(11), Lon::from_inner(5)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BBox {
+    pub min_lat: Lat,
+    pub min_lon: Lon,
+    pub max_lat: Lat,
+    pub max_lon: Lon,
+}
+
+impl BBox {
+    pub fn new(min_lat: Lat, min_lon: Lon, max_lat: Lat, max_lon: Lon) -> Self {
+        BBox {
+            min_lat,
+            min_lon,
+            max_lat,
+            max_lon,
         }
     }
 
-    pub fn to_epoch_number(&self) -> i64 {
-        match self {
-            TimestampFormat::ISOString(s) => iso_to_epoch(s) as i64,
-            &TimestampFormat::EpochNumber(t) => t,
-        }
+    /// True iff this location is inside (or on the edge of) this bbox.
+    pub fn contains(&self, lat: Lat, lon: Lon) -> bool {
+        self.min_lat <= lat && lat <= self.max_lat && self.min_lon <= lon && lon <= self.max_lon
     }
-}
 
-impl<T> From<T> for TimestampFormat
-where
-    T: Into<i64>,
-{
-    fn from(v: T) -> Self {
-        TimestampFormat::EpochNumber(v.into())
+    /// The smallest bbox containing all these locations, `None` if there are none.
+    pub fn from_locations(locations: impl IntoIterator<Item = (Lat, Lon)>) -> Option<Self> {
+        let mut locations = locations.into_iter();
+        let (lat, lon) = locations.next()?;
+        let mut bbox = BBox::new(lat, lon, lat, lon);
+        for (lat, lon) in locations {
+            bbox.extend(lat, lon);
+        }
+        Some(bbox)
     }
-}
 
-impl std::str::FromStr for TimestampFormat {
-    type Err = String;
-
-    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        let date: i64 = chrono::DateTime::parse_from_rfc3339(s)
-            .map_err(|_| "invalid date")?
-            .timestamp();
-        Ok(TimestampFormat::EpochNumber(date))
+    /// Grow this bbox to include this location.
+    pub fn extend(&mut self, lat: Lat, lon: Lon) {
+        self.min_lat = self.min_lat.min(lat);
+        self.max_lat = self.max_lat.max(lat);
+        self.min_lon = self.min_lon.min(lon);
+        self.max_lon = self.max_lon.max(lon);
     }
-}
 
-impl fmt::Display for TimestampFormat {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.to_iso_string())
+    /// True iff these 2 bboxes overlap (or touch).
+    pub fn intersects(&self, other: &BBox) -> bool {
+        self.min_lat <= other.max_lat
+            && other.min_lat <= self.max_lat
+            && self.min_lon <= other.max_lon
+            && other.min_lon <= self.max_lon
     }
 }
 
-impl std::cmp::PartialOrd for TimestampFormat {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match (self, other) {
-            (TimestampFormat::ISOString(a), TimestampFormat::ISOString(b)) => a.partial_cmp(b),
-            (TimestampFormat::EpochNumber(a), TimestampFormat::EpochNumber(b)) => a.partial_cmp(b),
-            (a, b) => a.to_epoch_number().partial_cmp(&b.to_epoch_number()),
-        }
-    }
-}
-impl std::cmp::PartialEq for TimestampFormat {
-    fn eq(&self, other: &Self) -> bool {
-        match (self, other) {
-            (TimestampFormat::ISOString(a), TimestampFormat::ISOString(b)) => a.eq(b),
-            (TimestampFormat::EpochNumber(a), TimestampFormat::EpochNumber(b)) => a.eq(b),
-            (a, b) => a.to_epoch_number().eq(&b.to_epoch_number()),
-        }
-    }
-}
+/// The old name of [`Timestamp`]
+#[deprecated(note = "Use Timestamp")]
+pub type TimestampFormat = Timestamp;
 
-/// The basic metadata fields all OSM objects share
-pub trait OSMObjBase: PartialEq + Debug + Clone {
+/// The basic metadata fields all OSM objects share, without setters.
+///
+/// Borrowed views, like the PBF [`NodeRef`](stringpbf::NodeRef), implement this & the other
+/// `*Read` traits. Owned objects implement [`OSMObjBase`] too.
+pub trait OSMObjBaseRead {
     fn id(&self) -> ObjId;
-    fn set_id(&mut self, val: impl Into<ObjId>);
     fn version(&self) -> Option<u32>;
-    fn set_version(&mut self, val: impl Into<Option<u32>>);
     fn deleted(&self) -> bool;
-    fn set_deleted(&mut self, val: bool);
     fn changeset_id(&self) -> Option<u32>;
-    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>);
-    fn timestamp(&self) -> &Option<TimestampFormat>;
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>);
+    fn timestamp(&self) -> Option<Timestamp>;
     fn uid(&self) -> Option<u32>;
-    fn set_uid(&mut self, val: impl Into<Option<u32>>);
     fn user(&self) -> Option<&str>;
-    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>);
 
     fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a>;
     fn tag(&self, key: impl AsRef<str>) -> Option<&str>;
@@ -358,6 +362,19 @@ pub trait OSMObjBase: PartialEq + Debug + Clone {
         self.num_tags() == 0
     }
 
+    fn object_type(&self) -> OSMObjectType;
+}
+
+/// The basic metadata fields all OSM objects share
+pub trait OSMObjBase: OSMObjBaseRead + PartialEq + Debug + Clone {
+    fn set_id(&mut self, val: impl Into<ObjId>);
+    fn set_version(&mut self, val: impl Into<Option<u32>>);
+    fn set_deleted(&mut self, val: bool);
+    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>);
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>);
+    fn set_uid(&mut self, val: impl Into<Option<u32>>);
+    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>);
+
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>);
     fn unset_tag(&mut self, key: impl AsRef<str>);
 
@@ -366,12 +383,10 @@ pub trait OSMObjBase: PartialEq + Debug + Clone {
         self.set_user(None);
         self.set_changeset_id(None);
     }
-
-    fn object_type(&self) -> OSMObjectType;
 }
 
-/// A Node
-pub trait Node: OSMObjBase {
+/// A Node, without setters
+pub trait NodeRead: OSMObjBaseRead {
     /// Latitude & Longitude of the node (if it's set)
     fn lat_lon(&self) -> Option<(Lat, Lon)>;
 
@@ -383,7 +398,10 @@ pub trait Node: OSMObjBase {
     fn has_lat_lon(&self) -> bool {
         self.lat_lon().is_some()
     }
+}
 
+/// A Node
+pub trait Node: OSMObjBase + NodeRead {
     /// Remove the lat & lon for this node
     fn unset_lat_lon(&mut self) {
         self.set_lat_lon_direct(None);
@@ -396,7 +414,7 @@ pub trait Node: OSMObjBase {
     ///
     /// The type signature is complicated so you can convert from f64
     /// ```rust
-    /// use osmio::Node;
+    /// use osmio::{Node, NodeRead};
     /// # use osmio::obj_types::StringNodeBuilder;
     /// # let mut node = StringNodeBuilder::default()._id(1).build().unwrap();
     ///
@@ -433,8 +451,8 @@ pub trait Node: OSMObjBase {
     }
 }
 
-/// A Way
-pub trait Way: OSMObjBase {
+/// A Way, without setters
+pub trait WayRead: OSMObjBaseRead {
     /// List of node ids in this way
     fn nodes(&self) -> &[ObjId];
 
@@ -446,7 +464,12 @@ pub trait Way: OSMObjBase {
     fn num_nodes(&self) -> usize;
     /// Return node id at this position
     fn node(&self, idx: usize) -> Option<ObjId>;
-    fn set_nodes(&mut self, nodes: impl IntoIterator<Item = impl Into<ObjId>>);
+
+    /// The location of each node, in the same order as [`nodes()`](WayRead::nodes), if the source
+    /// included them (e.g. a PBF file with `LocationsOnWays`).
+    fn node_locations(&self) -> Option<&[(Lat, Lon)]> {
+        None
+    }
 
     /// A Way which begins and ends with the same Node is considered "closed".
     ///
@@ -472,11 +495,20 @@ pub trait Way: OSMObjBase {
     }
 }
 
-/// A Relation
-pub trait Relation: OSMObjBase {
+/// A Way
+pub trait Way: OSMObjBase + WayRead {
+    fn set_nodes(&mut self, nodes: impl IntoIterator<Item = impl Into<ObjId>>);
+}
+
+/// A Relation, without setters
+pub trait RelationRead: OSMObjBaseRead {
     fn members<'a>(
         &'a self,
     ) -> Box<dyn ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)> + 'a>;
+}
+
+/// A Relation
+pub trait Relation: OSMObjBase + RelationRead {
     fn set_members(
         &mut self,
         members: impl IntoIterator<Item = (OSMObjectType, ObjId, impl Into<String>)>,
@@ -484,7 +516,8 @@ pub trait Relation: OSMObjBase {
 }
 
 /// A Node, Way or Relation
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum OSMObjectType {
     Node,
     Way,
@@ -499,7 +532,7 @@ impl OSMObjectType {
             OSMObjectType::Relation => 'r',
         }
     }
-    pub fn name_long(&self) -> &str {
+    pub fn name_long(&self) -> &'static str {
         match self {
             OSMObjectType::Node => "node",
             OSMObjectType::Way => "way",
@@ -588,12 +621,25 @@ pub trait OSMReader {
     /// Create this reader from a `std::io::Read`.
     fn new(_: Self::R) -> Self;
 
+    /// Assume the objects are sorted (by type, then id, then version).
+    ///
+    /// When on, [`nodes`](OSMReader::nodes) etc. stop at the first object of a later type. That
+    /// object isn't lost, it's returned by the next call. The order is checked as objects are
+    /// read: reading stops before the first object which is out of order, and
+    /// [`unsorted_error`](OSMReader::unsorted_error) returns the error. Readers which don't
+    /// support it ignore this.
     #[allow(unused_variables)]
     fn set_sorted_assumption(&mut self, sorted_assumption: bool) {}
     fn get_sorted_assumption(&mut self) -> bool {
         false
     }
 
+    /// With the sorted assumption, the first object which was out of order. Nothing is read after
+    /// this.
+    fn unsorted_error(&self) -> Option<&sort::UnsortedError> {
+        None
+    }
+
     fn assume_sorted(&mut self) {
         self.set_sorted_assumption(true);
     }
@@ -601,6 +647,16 @@ pub trait OSMReader {
         self.set_sorted_assumption(false);
     }
 
+    /// Only objects of these types (nodes, ways, relations) are wanted. Readers which can skip
+    /// the others cheaply (e.g. PBF, which doesn't decode those blocks) may return only those,
+    /// others ignore this.
+    #[allow(unused_variables)]
+    fn set_object_filter(&mut self, nodes_ways_relations: (bool, bool, bool)) {}
+    /// The current object filter. All types, unless the reader supports filtering.
+    fn get_object_filter(&self) -> (bool, bool, bool) {
+        (true, true, true)
+    }
+
     /// Convert to the underlying reader
     fn into_inner(self) -> Self::R;
 
@@ -610,6 +666,28 @@ pub trait OSMReader {
     /// Returns the next OSM Object in this reader
     fn next(&mut self) -> Option<Self::Obj>;
 
+    /// Put back `obj`, which was just returned by [`next`](OSMReader::next), so the next call
+    /// returns it again.
+    ///
+    /// Readers which support the sorted assumption must implement this. By default `obj` is
+    /// dropped.
+    #[allow(unused_variables)]
+    fn unread(&mut self, obj: Self::Obj) {}
+
+    /// Overwrite `obj` with the next object, returning `false` at the end.
+    ///
+    /// Readers may reuse `obj`'s buffers (tags, nodes, members) rather than allocating new ones.
+    /// By default `obj` is replaced by [`next`](OSMReader::next).
+    fn next_into(&mut self, obj: &mut Self::Obj) -> Result<bool> {
+        match self.next() {
+            Some(next) => {
+                *obj = next;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     /// Returns an iterator over the objects in this reader.
     fn objects(&mut self) -> OSMObjectIterator<'_, Self>
     where
@@ -618,18 +696,40 @@ pub trait OSMReader {
         OSMObjectIterator { inner: self }
     }
 
+    /// Returns an iterator over the objects in this reader, checking they are sorted (by type,
+    /// then id, then version). It stops after the first error.
+    fn checked_objects(&mut self) -> CheckedOSMObjectIterator<'_, Self>
+    where
+        Self: Sized,
+    {
+        CheckedOSMObjectIterator {
+            inner: self,
+            checker: Some(sort::SortChecker::new()),
+        }
+    }
+
     fn nodes(&mut self) -> Box<dyn Iterator<Item = <<Self as OSMReader>::Obj as OSMObj>::Node> + '_>
     where
         Self: Sized,
     {
-        Box::new(self.objects().filter_map(|o| o.into_node()))
+        if self.get_sorted_assumption() {
+            Box::new(
+                sorted_objects_of_type(self, OSMObjectType::Node).filter_map(|o| o.into_node()),
+            )
+        } else {
+            Box::new(self.objects().filter_map(|o| o.into_node()))
+        }
     }
 
     fn ways(&mut self) -> Box<dyn Iterator<Item = <<Self as OSMReader>::Obj as OSMObj>::Way> + '_>
     where
         Self: Sized,
     {
-        Box::new(self.objects().filter_map(|o| o.into_way()))
+        if self.get_sorted_assumption() {
+            Box::new(sorted_objects_of_type(self, OSMObjectType::Way).filter_map(|o| o.into_way()))
+        } else {
+            Box::new(self.objects().filter_map(|o| o.into_way()))
+        }
     }
 
     fn relations(
@@ -638,24 +738,40 @@ pub trait OSMReader {
     where
         Self: Sized,
     {
-        Box::new(self.objects().filter_map(|o| o.into_relation()))
+        if self.get_sorted_assumption() {
+            Box::new(
+                sorted_objects_of_type(self, OSMObjectType::Relation)
+                    .filter_map(|o| o.into_relation()),
+            )
+        } else {
+            Box::new(self.objects().filter_map(|o| o.into_relation()))
+        }
     }
 
     //fn nodes_locations<'a>(&'a mut self) -> Box<Iterator<Item=(ObjId, Lat, Lon)>+'a> where Self:Sized {
     //    Box::new(self.nodes().filter_map(|n| if n.deleted || n.lat.is_none() { None } else { Some((n.id, n.lat.unwrap(), n.lon.unwrap())) } ))
     //}
+}
 
-    //fn ways<'a>(&'a mut self) -> Box<Iterator<Item=Way>+'a> where Self:Sized {
-    //    if self.get_sorted_assumption() {
-    //        Box::new(self.objects().take_while(|o| (o.is_node() || o.is_way())).filter_map(|o| o.into_way()))
-    //    } else {
-    //        Box::new(self.objects().filter_map(|o| o.into_way()))
-    //    }
-    //}
-
-    //fn relations<'a>(&'a mut self) -> Box<Iterator<Item=Relation>+'a> where Self:Sized {
-    //    Box::new(self.objects().filter_map(|o| o.into_relation()))
-    //}
+/// The objects of this type from a sorted reader. Objects of earlier types are skipped, and it
+/// stops at the first object of a later type, which is put back.
+fn sorted_objects_of_type<R: OSMReader>(
+    reader: &mut R,
+    object_type: OSMObjectType,
+) -> impl Iterator<Item = R::Obj> + '_ {
+    std::iter::from_fn(move || {
+        loop {
+            let obj = reader.next()?;
+            match obj.object_type().cmp(&object_type) {
+                std::cmp::Ordering::Less => continue,
+                std::cmp::Ordering::Equal => return Some(obj),
+                std::cmp::Ordering::Greater => {
+                    reader.unread(obj);
+                    return None;
+                }
+            }
+        }
+    })
 }
 
 /// Something that produces OSMObjects
@@ -688,6 +804,37 @@ where
     }
 }
 
+/// Something that produces OSMObjects, checking they are sorted
+///
+/// Created by `OSMReader::checked_objects`
+pub struct CheckedOSMObjectIterator<'a, R>
+where
+    R: OSMReader + 'a,
+{
+    inner: &'a mut R,
+    /// `None` after an error
+    checker: Option<sort::SortChecker>,
+}
+
+impl<'a, R> Iterator for CheckedOSMObjectIterator<'a, R>
+where
+    R: OSMReader,
+{
+    type Item = Result<R::Obj, sort::UnsortedError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let checker = self.checker.as_mut()?;
+        let obj = self.inner.next()?;
+        match checker.check(&obj) {
+            Ok(()) => Some(Ok(obj)),
+            Err(err) => {
+                self.checker = None;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 /// An error when trying to write from an OSMWriter
 #[derive(Debug)]
 pub enum OSMWriteError {
@@ -697,6 +844,9 @@ pub enum OSMWriteError {
     OPLWrite(::std::io::Error),
     XMLWriteXMLError(quick_xml::Error),
     XMLWriteIOError(::std::io::Error),
+    /// Node locations were given, but the writer isn't writing them
+    LocationsOnWaysDisabled,
+    WrongNumberOfLocations(WrongNumberOfLocations),
 }
 impl std::fmt::Display for OSMWriteError {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -704,6 +854,39 @@ impl std::fmt::Display for OSMWriteError {
     }
 }
 impl std::error::Error for OSMWriteError {}
+impl From<WrongNumberOfLocations> for OSMWriteError {
+    fn from(err: WrongNumberOfLocations) -> OSMWriteError {
+        OSMWriteError::WrongNumberOfLocations(err)
+    }
+}
+
+/// A way's node locations don't have one location per node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WrongNumberOfLocations {
+    pub nodes: usize,
+    pub locations: usize,
+}
+impl std::fmt::Display for WrongNumberOfLocations {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "way has {} nodes, but {} locations",
+            self.nodes, self.locations
+        )
+    }
+}
+impl std::error::Error for WrongNumberOfLocations {}
+
+impl WrongNumberOfLocations {
+    /// Error unless there is one location per node
+    pub(crate) fn check(nodes: usize, locations: usize) -> Result<(), WrongNumberOfLocations> {
+        if nodes == locations {
+            Ok(())
+        } else {
+            Err(WrongNumberOfLocations { nodes, locations })
+        }
+    }
+}
 
 /// A generic writer for OSM objects.
 pub trait OSMWriter<W: Write> {
@@ -0,0 +1,500 @@
+//! Build areas from `type=multipolygon` & `type=boundary` relations
+//!
+//! [`assemble`] joins the member ways into closed rings (reversing them, and joining split ways,
+//! as needed), then works out which rings are outers & which are inners from the geometry. Roles
+//! are not trusted. Anything wrong with the relation is returned as a list of [`Problem`]s.
+//!
+//! Outer rings are anti-clockwise, and inner rings clockwise, as in GeoJSON.
+use crate::geometry::{MissingNodes, way_coords};
+use crate::nodestore::NodeLocationStore;
+use crate::{Lat, Lon, OSMObjectType, ObjId, RelationRead, WayRead};
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A closed ring of locations. The first & last location are the same.
+pub type Ring = Vec<(Lat, Lon)>;
+
+/// One outer ring, and the inner rings (holes) in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub outer: Ring,
+    pub inners: Vec<Ring>,
+}
+
+/// The area of one relation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiPolygon {
+    pub relation_id: ObjId,
+    pub polygons: Vec<Polygon>,
+}
+
+impl Polygon {
+    /// Is this location inside the outer ring, and not in any hole?
+    pub fn contains(&self, lat: Lat, lon: Lon) -> bool {
+        let p = point((lat, lon));
+        point_in_ring(p, &self.outer) && !self.inners.iter().any(|inner| point_in_ring(p, inner))
+    }
+}
+
+impl MultiPolygon {
+    /// Is this location inside any of the polygons?
+    pub fn contains(&self, lat: Lat, lon: Lon) -> bool {
+        self.polygons.iter().any(|p| p.contains(lat, lon))
+    }
+}
+
+/// Something wrong with a relation, which means it can't be made into a valid area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The relation isn't `type=multipolygon` or `type=boundary`
+    NotAnArea,
+    /// The relation has no `outer`/`inner` ways
+    NoWays,
+    /// A member way wasn't given
+    MissingWay(ObjId),
+    /// Some nodes of a member way have no location
+    MissingNodes(MissingNodes),
+    /// The ways can't be joined into a closed ring. The ring starts & ends at these nodes.
+    UnclosedRing {
+        start_node: ObjId,
+        end_node: ObjId,
+        way_ids: Vec<ObjId>,
+    },
+    /// A ring has fewer than 3 distinct points
+    DegenerateRing { way_ids: Vec<ObjId> },
+    /// A ring crosses itself. These are the segments which cross.
+    SelfIntersection {
+        way_ids: Vec<ObjId>,
+        segments: [((Lat, Lon), (Lat, Lon)); 2],
+    },
+    /// Two rings cross each other. These are the segments which cross.
+    RingIntersection {
+        segments: [((Lat, Lon), (Lat, Lon)); 2],
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::NotAnArea => write!(f, "relation is not a multipolygon or boundary"),
+            Problem::NoWays => write!(f, "relation has no outer or inner ways"),
+            Problem::MissingWay(way_id) => write!(f, "member way {} is missing", way_id),
+            Problem::MissingNodes(missing) => missing.fmt(f),
+            Problem::UnclosedRing {
+                start_node,
+                end_node,
+                way_ids,
+            } => write!(
+                f,
+                "ring of ways {:?} is not closed, it goes from node {} to node {}",
+                way_ids, start_node, end_node
+            ),
+            Problem::DegenerateRing { way_ids } => {
+                write!(f, "ring of ways {:?} has fewer than 3 points", way_ids)
+            }
+            Problem::SelfIntersection { way_ids, segments } => write!(
+                f,
+                "ring of ways {:?} crosses itself at {:?}",
+                way_ids, segments
+            ),
+            Problem::RingIntersection { segments } => {
+                write!(f, "rings cross each other at {:?}", segments)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Problem {}
+
+/// A ring built from ways
+struct RawRing {
+    way_ids: Vec<ObjId>,
+    node_ids: Vec<ObjId>,
+    coords: Ring,
+}
+
+/// Build the area of this relation, from its member ways & node locations.
+///
+/// Only way members with role `outer`, `inner` or no role are used.
+pub fn assemble<W: WayRead>(
+    relation: &impl RelationRead,
+    ways: &HashMap<ObjId, W>,
+    store: &(impl NodeLocationStore + ?Sized),
+) -> Result<MultiPolygon, Vec<Problem>> {
+    if !matches!(
+        relation.tag("type"),
+        Some("multipolygon") | Some("boundary")
+    ) {
+        return Err(vec![Problem::NotAnArea]);
+    }
+
+    let mut problems = Vec::new();
+    let mut seen = HashSet::new();
+    // (way id, node ids, locations)
+    let mut segments = Vec::new();
+    for (member_type, way_id, role) in relation.members() {
+        if member_type != OSMObjectType::Way || !matches!(role, "outer" | "inner" | "") {
+            continue;
+        }
+        if !seen.insert(way_id) {
+            continue;
+        }
+        let Some(way) = ways.get(&way_id) else {
+            problems.push(Problem::MissingWay(way_id));
+            continue;
+        };
+        match way_coords(way, store) {
+            Ok(coords) if !coords.is_empty() => {
+                segments.push((way_id, way.nodes().to_vec(), coords));
+            }
+            Ok(_) => {}
+            Err(missing) => problems.push(Problem::MissingNodes(missing)),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+    if segments.is_empty() {
+        return Err(vec![Problem::NoWays]);
+    }
+
+    let rings = join_rings(segments, &mut problems);
+    check_intersections(&rings, &mut problems);
+    if !problems.is_empty() {
+        return Err(problems);
+    }
+
+    Ok(MultiPolygon {
+        relation_id: relation.id(),
+        polygons: nest_rings(rings),
+    })
+}
+
+/// Join the ways into closed rings, by their end nodes
+fn join_rings(
+    mut segments: Vec<(ObjId, Vec<ObjId>, Ring)>,
+    problems: &mut Vec<Problem>,
+) -> Vec<RawRing> {
+    let mut rings = Vec::new();
+    while let Some((way_id, node_ids, coords)) = segments.pop() {
+        let mut ring = RawRing {
+            way_ids: vec![way_id],
+            node_ids,
+            coords,
+        };
+
+        while ring.node_ids.first() != ring.node_ids.last() {
+            let start = ring.node_ids[0];
+            let end = *ring.node_ids.last().unwrap();
+            let Some(idx) = segments.iter().position(|(_, nids, _)| {
+                let (first, last) = (nids[0], *nids.last().unwrap());
+                first == end || last == end || first == start || last == start
+            }) else {
+                break;
+            };
+            let (way_id, mut nids, mut coords) = segments.swap_remove(idx);
+            ring.way_ids.push(way_id);
+            if nids[0] == end || *nids.last().unwrap() == end {
+                // Append to the end
+                if nids[0] != end {
+                    nids.reverse();
+                    coords.reverse();
+                }
+                ring.node_ids.extend_from_slice(&nids[1..]);
+                ring.coords.extend_from_slice(&coords[1..]);
+            } else {
+                // Prepend to the start
+                if *nids.last().unwrap() != start {
+                    nids.reverse();
+                    coords.reverse();
+                }
+                nids.pop();
+                coords.pop();
+                nids.append(&mut ring.node_ids);
+                coords.append(&mut ring.coords);
+                ring.node_ids = nids;
+                ring.coords = coords;
+            }
+        }
+
+        if ring.node_ids.first() != ring.node_ids.last() {
+            problems.push(Problem::UnclosedRing {
+                start_node: ring.node_ids[0],
+                end_node: *ring.node_ids.last().unwrap(),
+                way_ids: ring.way_ids,
+            });
+        } else if ring.coords.len() < 4 {
+            problems.push(Problem::DegenerateRing {
+                way_ids: ring.way_ids,
+            });
+        } else {
+            rings.push(ring);
+        }
+    }
+    rings
+}
+
+type Point = (i64, i64);
+
+fn point((lat, lon): (Lat, Lon)) -> Point {
+    (lon.inner() as i64, lat.inner() as i64)
+}
+
+/// >0 if a, b, c turn anti-clockwise, <0 if clockwise, 0 if collinear
+fn orientation(a: Point, b: Point, c: Point) -> i128 {
+    (b.0 - a.0) as i128 * (c.1 - a.1) as i128 - (b.1 - a.1) as i128 * (c.0 - a.0) as i128
+}
+
+/// Do these 2 segments properly cross (i.e. not just touch at an end)?
+fn segments_cross(a: (Point, Point), b: (Point, Point)) -> bool {
+    let o1 = orientation(a.0, a.1, b.0).signum();
+    let o2 = orientation(a.0, a.1, b.1).signum();
+    let o3 = orientation(b.0, b.1, a.0).signum();
+    let o4 = orientation(b.0, b.1, a.1).signum();
+    o1 * o2 < 0 && o3 * o4 < 0
+}
+
+/// Look for rings which cross themselves or each other.
+///
+/// A sweep along the x axis, so only segments which overlap in x are compared.
+fn check_intersections(rings: &[RawRing], problems: &mut Vec<Problem>) {
+    // (min x, max x, ring idx, segment idx)
+    let mut segments: Vec<(i64, i64, usize, usize)> = Vec::new();
+    for (ring_idx, ring) in rings.iter().enumerate() {
+        for (seg_idx, w) in ring.coords.windows(2).enumerate() {
+            let (a, b) = (point(w[0]), point(w[1]));
+            segments.push((a.0.min(b.0), a.0.max(b.0), ring_idx, seg_idx));
+        }
+    }
+    segments.sort_unstable();
+
+    let segment = |ring_idx: usize, seg_idx: usize| {
+        let coords = &rings[ring_idx].coords;
+        (coords[seg_idx], coords[seg_idx + 1])
+    };
+
+    for (i, &(_, max_x, ring_a, seg_a)) in segments.iter().enumerate() {
+        for &(min_x, _, ring_b, seg_b) in segments[i + 1..].iter() {
+            if min_x > max_x {
+                break;
+            }
+            let (a, b) = (segment(ring_a, seg_a), segment(ring_b, seg_b));
+            if !segments_cross((point(a.0), point(a.1)), (point(b.0), point(b.1))) {
+                continue;
+            }
+            if ring_a == ring_b {
+                problems.push(Problem::SelfIntersection {
+                    way_ids: rings[ring_a].way_ids.clone(),
+                    segments: [a, b],
+                });
+            } else {
+                problems.push(Problem::RingIntersection { segments: [a, b] });
+            }
+        }
+    }
+}
+
+/// Twice the signed area. Positive if anti-clockwise.
+fn signed_area(ring: &Ring) -> i128 {
+    ring.windows(2)
+        .map(|w| {
+            let (a, b) = (point(w[0]), point(w[1]));
+            a.0 as i128 * b.1 as i128 - b.0 as i128 * a.1 as i128
+        })
+        .sum()
+}
+
+/// Is this point inside the ring? (even-odd rule)
+fn point_in_ring(p: Point, ring: &Ring) -> bool {
+    let mut inside = false;
+    for w in ring.windows(2) {
+        let (a, b) = (point(w[0]), point(w[1]));
+        if (a.1 > p.1) != (b.1 > p.1) {
+            // x of the edge at y = p.1, compared without division
+            let lhs = (p.0 - a.0) as i128 * (b.1 - a.1) as i128;
+            let rhs = (b.0 - a.0) as i128 * (p.1 - a.1) as i128;
+            if (b.1 > a.1 && lhs < rhs) || (b.1 < a.1 && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Is this location inside the ring? (even-odd rule)
+pub(crate) fn ring_contains(ring: &Ring, lat: Lat, lon: Lon) -> bool {
+    point_in_ring(point((lat, lon)), ring)
+}
+
+/// Is ring `a` inside ring `b`? Rings don't cross, so one vertex (not shared with `b`) is enough.
+fn ring_in_ring(a: &RawRing, b: &RawRing) -> bool {
+    let b_nodes: HashSet<ObjId> = b.node_ids.iter().copied().collect();
+    a.node_ids
+        .iter()
+        .zip(a.coords.iter())
+        .find(|(nid, _)| !b_nodes.contains(nid))
+        .is_some_and(|(_, &loc)| point_in_ring(point(loc), &b.coords))
+}
+
+/// Work out which rings are outers, and which inners belong to them
+fn nest_rings(rings: Vec<RawRing>) -> Vec<Polygon> {
+    // containers[i] = rings which ring i is inside
+    let containers: Vec<Vec<usize>> = (0..rings.len())
+        .map(|i| {
+            (0..rings.len())
+                .filter(|&j| i != j && ring_in_ring(&rings[i], &rings[j]))
+                .collect()
+        })
+        .collect();
+
+    let mut polygons = Vec::new();
+    let mut polygon_idx = HashMap::new();
+    for (i, ring) in rings.iter().enumerate() {
+        if containers[i].len().is_multiple_of(2) {
+            let mut outer = ring.coords.clone();
+            if signed_area(&outer) < 0 {
+                outer.reverse();
+            }
+            polygon_idx.insert(i, polygons.len());
+            polygons.push(Polygon {
+                outer,
+                inners: Vec::new(),
+            });
+        }
+    }
+    for (i, ring) in rings.iter().enumerate() {
+        if !containers[i].len().is_multiple_of(2) {
+            // The parent is the container which is inside the most other rings
+            let parent = *containers[i]
+                .iter()
+                .max_by_key(|&&j| containers[j].len())
+                .unwrap();
+            let mut inner = ring.coords.clone();
+            if signed_area(&inner) > 0 {
+                inner.reverse();
+            }
+            polygons[polygon_idx[&parent]].inners.push(inner);
+        }
+    }
+    polygons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodestore::HashMapStore;
+    use crate::obj_types::{StringRelation, StringRelationBuilder, StringWay, StringWayBuilder};
+
+    fn deg(x: i32) -> i32 {
+        x * 10_000_000
+    }
+
+    /// Nodes on a 10×10 grid, node id = 100*x + y
+    fn grid() -> HashMapStore {
+        let mut store = HashMapStore::new();
+        for x in 0..10 {
+            for y in 0..10 {
+                store
+                    .set(100 * x + y, (Lat(deg(y as i32)), Lon(deg(x as i32))))
+                    .unwrap();
+            }
+        }
+        store
+    }
+
+    fn way(id: ObjId, nodes: &[ObjId]) -> (ObjId, StringWay) {
+        (
+            id,
+            StringWayBuilder::default()
+                ._id(id)
+                ._nodes(nodes.iter().copied().collect())
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn relation(members: &[(ObjId, &str)]) -> StringRelation {
+        StringRelationBuilder::default()
+            ._id(1)
+            ._tags(smallvec::smallvec![("type".into(), "multipolygon".into())])
+            ._members(
+                members
+                    .iter()
+                    .map(|(id, role)| (OSMObjectType::Way, *id, (*role).into()))
+                    .collect(),
+            )
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn split_reversed_outer_with_inner() {
+        let store = grid();
+        let ways: HashMap<_, _> = [
+            // outer square (0,0)-(8,8), in 2 halves, one reversed
+            way(10, &[0, 800, 808]),
+            way(11, &[0, 8, 808]),
+            // inner square (2,2)-(4,4), clockwise, role not set
+            way(12, &[202, 204, 404, 402, 202]),
+            // separate outer (9,0)-(9,2)
+            way(13, &[900, 902, 901, 900]),
+        ]
+        .into_iter()
+        .collect();
+        let rel = relation(&[(10, "outer"), (11, "outer"), (12, ""), (13, "outer")]);
+
+        let mp = assemble(&rel, &ways, &store).unwrap();
+        assert_eq!(mp.relation_id, 1);
+        assert_eq!(mp.polygons.len(), 2);
+        let big = mp.polygons.iter().find(|p| p.outer.len() == 5).unwrap();
+        assert!(signed_area(&big.outer) > 0);
+        assert_eq!(big.inners.len(), 1);
+        assert!(signed_area(&big.inners[0]) < 0);
+        assert_eq!(big.outer.first(), big.outer.last());
+
+        assert!(mp.contains(Lat(deg(1)), Lon(deg(1))));
+        assert!(!mp.contains(Lat(deg(3)), Lon(deg(3))));
+        assert!(mp.contains(Lat(deg(5)), Lon(deg(5))));
+        assert!(!mp.contains(Lat(deg(10)), Lon(deg(1))));
+    }
+
+    #[test]
+    fn problems() {
+        let store = grid();
+        let ways: HashMap<_, _> = [
+            way(10, &[0, 800, 808]),
+            way(11, &[0, 8]),
+            // bow tie
+            way(12, &[202, 404, 204, 402, 202]),
+            way(13, &[0, 12345]),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            assemble(&relation(&[(10, "outer"), (11, "outer")]), &ways, &store),
+            Err(vec![Problem::UnclosedRing {
+                start_node: 808,
+                end_node: 8,
+                way_ids: vec![11, 10],
+            }])
+        );
+        assert!(matches!(
+            assemble(&relation(&[(12, "outer")]), &ways, &store).unwrap_err()[..],
+            [Problem::SelfIntersection { .. }]
+        ));
+        assert_eq!(
+            assemble(&relation(&[(99, "outer")]), &ways, &store),
+            Err(vec![Problem::MissingWay(99)])
+        );
+        assert!(matches!(
+            assemble(&relation(&[(13, "outer")]), &ways, &store).unwrap_err()[..],
+            [Problem::MissingNodes(_)]
+        ));
+        assert_eq!(
+            assemble(&relation(&[(10, "label")]), &ways, &store),
+            Err(vec![Problem::NoWays])
+        );
+    }
+}
@@ -1,65 +1,302 @@
-//! Currently unused local experimentation
-use crate::{Lat, Lon};
+//! Stores of node locations, for looking up where a way's nodes are.
+//!
+//! There are several [`NodeLocationStore`] backends, depending on how many nodes there are:
+//!
+//! * [`DenseFileStore`]: a memory mapped file, indexed by node id. Best for the planet or large
+//!   extracts. It takes 8 bytes for every possible node id up to the largest one stored.
+//! * [`SparseStore`]: a sorted array. Good for small extracts, which have few nodes spread over the
+//!   whole id range.
+//! * [`HashMapStore`]: an in memory hash map, which doesn't need sorted input.
+//!
+//! All backends can be read from many threads at once.
+//!
+//! ```no_run
+//! use osmio::nodestore::{NodeLocationStore, SparseStore};
+//! let mut store = SparseStore::new();
+//! store.load_pbf("region-latest.osm.pbf")?;
+//! let lat_lon = store.get(1234);
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::stringpbf::PBFNodePositionReader;
+use crate::{Lat, Lon, ObjId};
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
-use std::fs;
-use std::io::{BufReader, BufWriter};
-use std::io::{Seek, SeekFrom};
+use anyhow::{Context, Result, bail};
+use memmap2::{Mmap, MmapMut};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
 
-pub struct NodeStoreWriter {
-    max_node_id: u64,
-    fp: BufWriter<fs::File>,
+/// Somewhere to store, & look up, node locations.
+pub trait NodeLocationStore: Send + Sync {
+    /// The location of this node, if it's been stored
+    fn get(&self, node_id: ObjId) -> Option<(Lat, Lon)>;
+
+    /// Store the location of this node, replacing any previous location.
+    fn set(&mut self, node_id: ObjId, lat_lon: (Lat, Lon)) -> Result<()>;
+
+    /// Store many node locations. Returns how many were stored.
+    fn load(&mut self, locations: impl IntoIterator<Item = (ObjId, (Lat, Lon))>) -> Result<usize>
+    where
+        Self: Sized,
+    {
+        let mut num = 0;
+        for (node_id, lat_lon) in locations {
+            self.set(node_id, lat_lon)?;
+            num += 1;
+        }
+        Ok(num)
+    }
+
+    /// Store the location of every node in this PBF file. Returns how many were stored.
+    fn load_pbf(&mut self, filename: impl AsRef<Path>) -> Result<usize>
+    where
+        Self: Sized,
+    {
+        self.load(PBFNodePositionReader::from_filename(filename)?)
+    }
+}
+
+/// Size of one location in a [`DenseFileStore`]
+const DENSE_ENTRY_SIZE: usize = 8;
+
+/// Latitudes are stored XOR this. An unset (zeroed) entry has a latitude of `i32::MIN`, which
+/// isn't valid, so we know it's empty. (0, 0) is a valid location.
+const DENSE_LAT_MASK: i32 = i32::MIN;
+
+enum DenseMap {
+    ReadOnly(Mmap),
+    Writable { file: File, mmap: MmapMut },
 }
 
-pub struct NodeStoreReader {
-    fp: BufReader<fs::File>,
+/// Node locations in a memory mapped file, indexed by node id.
+///
+/// The file is sparse on filesystems which support it, so unused id ranges don't take up disk
+/// space. Negative node ids can't be stored.
+pub struct DenseFileStore {
+    map: DenseMap,
 }
 
-impl NodeStoreWriter {
-    pub fn create(filename: &str) -> Self {
-        let fp = BufWriter::with_capacity(1_000_000_000, fs::File::create(filename).unwrap());
-        NodeStoreWriter {
-            max_node_id: 0,
-            fp: fp,
-        }
+impl DenseFileStore {
+    /// Create a new, empty, store in this file. An existing file is truncated.
+    pub fn create(filename: impl AsRef<Path>) -> Result<Self> {
+        let filename = filename.as_ref();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(filename)
+            .with_context(|| format!("Creating node store {}", filename.display()))?;
+        // Can't map an empty file
+        file.set_len(DENSE_ENTRY_SIZE as u64 * 1024)?;
+        // SAFETY: We have just created this file. Other processes must not change it while we
+        // have it open.
+        let mmap = unsafe { MmapMut::map_mut(&file)? };
+        Ok(DenseFileStore {
+            map: DenseMap::Writable { file, mmap },
+        })
     }
 
-    pub fn set(&mut self, node_id: u64, lat: Lat, lon: Lon) {
-        if self.max_node_id < node_id {
-            //self.fp.set_len(node_id*8);
-            self.fp.seek(SeekFrom::End(0)).unwrap();
-            for _ in self.max_node_id..node_id {
-                self.fp
-                    .write_i32::<BigEndian>(SENTINAL_VALUE_FOR_EMPTY)
-                    .unwrap();
-                self.fp
-                    .write_i32::<BigEndian>(SENTINAL_VALUE_FOR_EMPTY)
-                    .unwrap();
-            }
-            self.max_node_id = node_id;
+    /// Open an existing store for reading.
+    pub fn open(filename: impl AsRef<Path>) -> Result<Self> {
+        let filename = filename.as_ref();
+        let file = File::open(filename)
+            .with_context(|| format!("Opening node store {}", filename.display()))?;
+        if file.metadata()?.len() % DENSE_ENTRY_SIZE as u64 != 0 {
+            bail!("{} is not a node store", filename.display());
         }
-        self.fp.seek(SeekFrom::Start(node_id * 8)).unwrap();
-        self.fp.write_i32::<BigEndian>(lat.inner()).unwrap();
-        self.fp.write_i32::<BigEndian>(lon.inner()).unwrap();
+        // SAFETY: Other processes must not change this file while we have it open.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(DenseFileStore {
+            map: DenseMap::ReadOnly(mmap),
+        })
     }
-}
 
-const SENTINAL_VALUE_FOR_EMPTY: i32 = i32::MAX;
+    /// Write all changes to disk.
+    pub fn flush(&self) -> Result<()> {
+        if let DenseMap::Writable { mmap, .. } = &self.map {
+            mmap.flush()?;
+        }
+        Ok(())
+    }
 
-impl NodeStoreReader {
-    pub fn open(filename: &str) -> Self {
-        let fp = BufReader::new(fs::File::open(filename).unwrap());
-        NodeStoreReader { fp: fp }
+    fn bytes(&self) -> &[u8] {
+        match &self.map {
+            DenseMap::ReadOnly(mmap) => mmap,
+            DenseMap::Writable { mmap, .. } => mmap,
+        }
     }
+}
 
-    pub fn get(&mut self, node_id: &u64) -> Option<(Lat, Lon)> {
-        self.fp.seek(SeekFrom::Start(node_id * 8)).unwrap();
-        let lat = self.fp.read_i32::<BigEndian>().unwrap();
-        let lon = self.fp.read_i32::<BigEndian>().unwrap();
-        if lat == SENTINAL_VALUE_FOR_EMPTY || lon == SENTINAL_VALUE_FOR_EMPTY {
+impl NodeLocationStore for DenseFileStore {
+    fn get(&self, node_id: ObjId) -> Option<(Lat, Lon)> {
+        let offset = usize::try_from(node_id)
+            .ok()?
+            .checked_mul(DENSE_ENTRY_SIZE)?;
+        let entry = self.bytes().get(offset..offset + DENSE_ENTRY_SIZE)?;
+        let lat = i32::from_le_bytes(entry[0..4].try_into().unwrap()) ^ DENSE_LAT_MASK;
+        let lon = i32::from_le_bytes(entry[4..8].try_into().unwrap());
+        if lat == i32::MIN {
             None
         } else {
             Some((Lat::from_inner(lat), Lon::from_inner(lon)))
         }
     }
+
+    fn set(&mut self, node_id: ObjId, (lat, lon): (Lat, Lon)) -> Result<()> {
+        let DenseMap::Writable { file, mmap } = &mut self.map else {
+            bail!("This node store was opened read only");
+        };
+        let Ok(index) = usize::try_from(node_id) else {
+            bail!(
+                "Negative node id {} can't be stored in a DenseFileStore",
+                node_id
+            );
+        };
+        let offset = index * DENSE_ENTRY_SIZE;
+        if offset + DENSE_ENTRY_SIZE > mmap.len() {
+            // Grow by at least double, so there are few remaps
+            let new_len = (offset + DENSE_ENTRY_SIZE).max(mmap.len() * 2);
+            mmap.flush()?;
+            file.set_len(new_len as u64)?;
+            // SAFETY: as in `create`
+            *mmap = unsafe { MmapMut::map_mut(&*file)? };
+        }
+        mmap[offset..offset + 4].copy_from_slice(&(lat.inner() ^ DENSE_LAT_MASK).to_le_bytes());
+        mmap[offset + 4..offset + 8].copy_from_slice(&lon.inner().to_le_bytes());
+        Ok(())
+    }
+}
+
+/// Node locations in a sorted array.
+///
+/// Storing nodes in increasing id order (as in a sorted PBF) is fast. Out of order nodes are
+/// supported, but slow.
+#[derive(Debug, Clone, Default)]
+pub struct SparseStore {
+    locations: Vec<(ObjId, (Lat, Lon))>,
+}
+
+impl SparseStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nodes stored
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+}
+
+impl NodeLocationStore for SparseStore {
+    fn get(&self, node_id: ObjId) -> Option<(Lat, Lon)> {
+        self.locations
+            .binary_search_by_key(&node_id, |(nid, _)| *nid)
+            .ok()
+            .map(|idx| self.locations[idx].1)
+    }
+
+    fn set(&mut self, node_id: ObjId, lat_lon: (Lat, Lon)) -> Result<()> {
+        match self.locations.last() {
+            Some((last_id, _)) if *last_id >= node_id => {
+                match self
+                    .locations
+                    .binary_search_by_key(&node_id, |(nid, _)| *nid)
+                {
+                    Ok(idx) => self.locations[idx].1 = lat_lon,
+                    Err(idx) => self.locations.insert(idx, (node_id, lat_lon)),
+                }
+            }
+            _ => self.locations.push((node_id, lat_lon)),
+        }
+        Ok(())
+    }
+}
+
+/// Node locations in an in memory `HashMap`.
+#[derive(Debug, Clone, Default)]
+pub struct HashMapStore {
+    locations: HashMap<ObjId, (Lat, Lon)>,
+}
+
+impl HashMapStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of nodes stored
+    pub fn len(&self) -> usize {
+        self.locations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty()
+    }
+}
+
+impl NodeLocationStore for HashMapStore {
+    fn get(&self, node_id: ObjId) -> Option<(Lat, Lon)> {
+        self.locations.get(&node_id).copied()
+    }
+
+    fn set(&mut self, node_id: ObjId, lat_lon: (Lat, Lon)) -> Result<()> {
+        self.locations.insert(node_id, lat_lon);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_store(store: &mut impl NodeLocationStore) {
+        let locations = vec![
+            (1, (Lat(0), Lon(0))),
+            (5, (Lat(-900000000), Lon(-1800000000))),
+            (3, (Lat(515000000), Lon(-1000000))),
+            (100_000, (Lat(1), Lon(2))),
+        ];
+        assert_eq!(store.load(locations.clone()).unwrap(), 4);
+        for (nid, lat_lon) in locations {
+            assert_eq!(store.get(nid), Some(lat_lon));
+        }
+        assert_eq!(store.get(2), None);
+        assert_eq!(store.get(100_001), None);
+        assert_eq!(store.get(10_000_000), None);
+
+        store.set(3, (Lat(10), Lon(10))).unwrap();
+        assert_eq!(store.get(3), Some((Lat(10), Lon(10))));
+    }
+
+    #[test]
+    fn sparse() {
+        let mut store = SparseStore::new();
+        check_store(&mut store);
+        assert_eq!(store.len(), 4);
+    }
+
+    #[test]
+    fn hashmap() {
+        check_store(&mut HashMapStore::new());
+    }
+
+    #[test]
+    fn dense() {
+        let path = std::env::temp_dir().join(format!("osmio-nodestore-{}", std::process::id()));
+        let mut store = DenseFileStore::create(&path).unwrap();
+        check_store(&mut store);
+        assert!(store.set(-1, (Lat(0), Lon(0))).is_err());
+        store.flush().unwrap();
+        drop(store);
+
+        let store = DenseFileStore::open(&path).unwrap();
+        assert_eq!(store.get(1), Some((Lat(0), Lon(0))));
+        assert_eq!(store.get(3), Some((Lat(10), Lon(10))));
+        assert_eq!(store.get(2), None);
+        std::fs::remove_file(&path).unwrap();
+    }
 }
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, TimestampFormat};
+use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, Timestamp};
 use std::sync::Arc;
 
 macro_rules! func_call_inner_get {
@@ -28,7 +28,7 @@ pub struct ArcNode {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Arc<str>>,
     pub(crate) _tags: Option<Vec<(Arc<str>, Arc<str>)>>,
@@ -42,12 +42,13 @@ pub struct ArcWay {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Arc<str>>,
     pub(crate) _tags: Vec<(Arc<str>, Arc<str>)>,
 
     pub(crate) _nodes: Vec<ObjId>,
+    pub(crate) _node_locations: Option<Vec<(Lat, Lon)>>,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -56,7 +57,7 @@ pub struct ArcRelation {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Arc<str>>,
     pub(crate) _tags: Vec<(Arc<str>, Arc<str>)>,
@@ -71,7 +72,7 @@ pub enum ArcOSMObj {
     Relation(ArcRelation),
 }
 
-impl OSMObjBase for ArcOSMObj {
+impl OSMObjBaseRead for ArcOSMObj {
     fn id(&self) -> ObjId {
         func_call_inner_get!(self, id)
     }
@@ -84,7 +85,7 @@ impl OSMObjBase for ArcOSMObj {
     fn changeset_id(&self) -> Option<u32> {
         func_call_inner_get!(self, changeset_id)
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
+    fn timestamp(&self) -> Option<Timestamp> {
         func_call_inner_get!(self, timestamp)
     }
     fn uid(&self) -> Option<u32> {
@@ -94,28 +95,6 @@ impl OSMObjBase for ArcOSMObj {
         func_call_inner_get!(self, user)
     }
 
-    fn set_id(&mut self, val: impl Into<ObjId>) {
-        func_call_inner_set!(self, set_id, val);
-    }
-    fn set_version(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_version, val);
-    }
-    fn set_deleted(&mut self, val: bool) {
-        func_call_inner_set!(self, set_deleted, val);
-    }
-    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_changeset_id, val);
-    }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
-        func_call_inner_set!(self, set_timestamp, val);
-    }
-    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_uid, val);
-    }
-    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
-        func_call_inner_set!(self, set_user, val);
-    }
-
     fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
         match self {
             ArcOSMObj::Node(x) => x.tags(),
@@ -147,6 +126,38 @@ impl OSMObjBase for ArcOSMObj {
         }
     }
 
+    fn object_type(&self) -> OSMObjectType {
+        match self {
+            ArcOSMObj::Node(_) => OSMObjectType::Node,
+            ArcOSMObj::Way(_) => OSMObjectType::Way,
+            ArcOSMObj::Relation(_) => OSMObjectType::Relation,
+        }
+    }
+}
+
+impl OSMObjBase for ArcOSMObj {
+    fn set_id(&mut self, val: impl Into<ObjId>) {
+        func_call_inner_set!(self, set_id, val);
+    }
+    fn set_version(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_version, val);
+    }
+    fn set_deleted(&mut self, val: bool) {
+        func_call_inner_set!(self, set_deleted, val);
+    }
+    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_changeset_id, val);
+    }
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
+        func_call_inner_set!(self, set_timestamp, val);
+    }
+    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_uid, val);
+    }
+    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
+        func_call_inner_set!(self, set_user, val);
+    }
+
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         match self {
             ArcOSMObj::Node(x) => x.set_tag(key, value),
@@ -162,14 +173,6 @@ impl OSMObjBase for ArcOSMObj {
             ArcOSMObj::Relation(x) => x.unset_tag(key),
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        match self {
-            ArcOSMObj::Node(_) => OSMObjectType::Node,
-            ArcOSMObj::Way(_) => OSMObjectType::Way,
-            ArcOSMObj::Relation(_) => OSMObjectType::Relation,
-        }
-    }
 }
 
 impl OSMObj for ArcOSMObj {
@@ -250,7 +253,7 @@ impl OSMObj for ArcOSMObj {
     }
 }
 
-impl OSMObjBase for ArcNode {
+impl OSMObjBaseRead for ArcNode {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -263,8 +266,8 @@ impl OSMObjBase for ArcNode {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -273,28 +276,6 @@ impl OSMObjBase for ArcNode {
         self._user.as_ref().map(|x| x as _)
     }
 
-    fn set_id(&mut self, val: impl Into<ObjId>) {
-        self._id = val.into();
-    }
-    fn set_version(&mut self, val: impl Into<Option<u32>>) {
-        self._version = val.into();
-    }
-    fn set_deleted(&mut self, val: bool) {
-        self._deleted = val;
-    }
-    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
-        self._changeset_id = val.into();
-    }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
-        self._timestamp = val.into();
-    }
-    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
-        self._uid = val.into();
-    }
-    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
-        self._user = val.into().map(Arc::from);
-    }
-
     fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
         match self._tags {
             None => Box::new(std::iter::empty()),
@@ -317,6 +298,34 @@ impl OSMObjBase for ArcNode {
         })
     }
 
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Node
+    }
+}
+
+impl OSMObjBase for ArcNode {
+    fn set_id(&mut self, val: impl Into<ObjId>) {
+        self._id = val.into();
+    }
+    fn set_version(&mut self, val: impl Into<Option<u32>>) {
+        self._version = val.into();
+    }
+    fn set_deleted(&mut self, val: bool) {
+        self._deleted = val;
+    }
+    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
+        self._changeset_id = val.into();
+    }
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
+        self._timestamp = val.into();
+    }
+    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
+        self._uid = val.into();
+    }
+    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
+        self._user = val.into().map(Arc::from);
+    }
+
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -351,23 +360,21 @@ impl OSMObjBase for ArcNode {
             }
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Node
-    }
 }
 
-impl Node for ArcNode {
+impl NodeRead for ArcNode {
     fn lat_lon(&self) -> Option<(Lat, Lon)> {
         self._lat_lon
     }
+}
 
+impl Node for ArcNode {
     fn set_lat_lon_direct(&mut self, loc: Option<(Lat, Lon)>) {
         self._lat_lon = loc;
     }
 }
 
-impl OSMObjBase for ArcWay {
+impl OSMObjBaseRead for ArcWay {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -380,8 +387,8 @@ impl OSMObjBase for ArcWay {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -390,6 +397,30 @@ impl OSMObjBase for ArcWay {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.as_ref() == key {
+                    Some(v.as_ref())
+                } else {
+                    None
+                }
+            })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Way
+    }
+}
+
+impl OSMObjBase for ArcWay {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -402,7 +433,7 @@ impl OSMObjBase for ArcWay {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -412,24 +443,6 @@ impl OSMObjBase for ArcWay {
         self._user = val.into().map(Arc::from);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| {
-                if k.as_ref() == key {
-                    Some(v.as_ref())
-                } else {
-                    None
-                }
-            })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -457,13 +470,9 @@ impl OSMObjBase for ArcWay {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Way
-    }
 }
 
-impl Way for ArcWay {
+impl WayRead for ArcWay {
     fn nodes(&self) -> &[ObjId] {
         &self._nodes
     }
@@ -475,13 +484,20 @@ impl Way for ArcWay {
     fn node(&self, idx: usize) -> Option<ObjId> {
         self._nodes.get(idx).cloned()
     }
+    fn node_locations(&self) -> Option<&[(Lat, Lon)]> {
+        self._node_locations.as_deref()
+    }
+}
+
+impl Way for ArcWay {
     fn set_nodes(&mut self, nodes: impl IntoIterator<Item = impl Into<ObjId>>) {
         self._nodes.clear();
         self._nodes.extend(nodes.into_iter().map(|i| i.into()));
+        self._node_locations = None;
     }
 }
 
-impl OSMObjBase for ArcRelation {
+impl OSMObjBaseRead for ArcRelation {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -494,8 +510,8 @@ impl OSMObjBase for ArcRelation {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -504,6 +520,30 @@ impl OSMObjBase for ArcRelation {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.as_ref() == key {
+                    Some(v.as_ref())
+                } else {
+                    None
+                }
+            })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Relation
+    }
+}
+
+impl OSMObjBase for ArcRelation {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -516,7 +556,7 @@ impl OSMObjBase for ArcRelation {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -526,24 +566,6 @@ impl OSMObjBase for ArcRelation {
         self._user = val.into().map(Arc::from);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| {
-                if k.as_ref() == key {
-                    Some(v.as_ref())
-                } else {
-                    None
-                }
-            })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -571,19 +593,17 @@ impl OSMObjBase for ArcRelation {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Relation
-    }
 }
 
-impl Relation for ArcRelation {
+impl RelationRead for ArcRelation {
     fn members<'a>(
         &'a self,
     ) -> Box<dyn ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)> + 'a> {
         Box::new(self._members.iter().map(|(t, o, r)| (*t, *o, r.as_ref())))
     }
+}
 
+impl Relation for ArcRelation {
     fn set_members(
         &mut self,
         members: impl IntoIterator<Item = (OSMObjectType, ObjId, impl Into<String>)>,
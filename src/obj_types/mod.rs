@@ -1,9 +1,29 @@
 //! Base OpenStreetMap object types
+//!
+//! # Serde
+//!
+//! With the `serde` feature (on by default), all the object types implement `Serialize` &
+//! `Deserialize`, with the [OSM JSON](https://wiki.openstreetmap.org/wiki/OSM_JSON) field names:
+//!
+//! ```json
+//! {"type":"node","id":1,"lat":51.5,"lon":-0.1,"timestamp":"2020-01-01T00:00:00Z","version":2,
+//!  "changeset":3,"user":"Jane","uid":4,"tags":{"amenity":"cafe"}}
+//! {"type":"way","id":10,"nodes":[1,2,1]}
+//! {"type":"relation","id":100,"visible":false,"members":[{"type":"way","ref":10,"role":"outer"}]}
+//! ```
+//!
+//! `visible` is only included for deleted objects, and a way's node locations (if any) are in
+//! `geometry`, as a list of `{"lat":…,"lon":…}`. In human readable formats, missing values are
+//! left out. In other formats (e.g. bincode), every field is always included.
 mod arc_types;
+#[cfg(feature = "serde")]
+mod osm_json;
 mod rc_types;
 mod string_types;
 
-use crate::{Node, OSMObjBase, Relation, Way};
+use crate::{Node, NodeRead, OSMObjBase, OSMObjBaseRead, Relation, RelationRead, Way, WayRead};
+use std::rc::Rc;
+use std::sync::Arc;
 
 pub use self::arc_types::*;
 pub use self::rc_types::*;
@@ -16,7 +36,7 @@ impl From<RcNode> for StringNode {
         n.set_version(obj.version());
         n.set_deleted(obj.deleted());
         n.set_changeset_id(obj.changeset_id());
-        n.set_timestamp(obj.timestamp().clone());
+        n.set_timestamp(obj.timestamp());
         n.set_uid(obj.uid());
         n.set_user(obj.user());
         n.set_lat_lon_direct(obj.lat_lon());
@@ -36,7 +56,7 @@ impl From<RcWay> for StringWay {
         w.set_version(obj.version());
         w.set_deleted(obj.deleted());
         w.set_changeset_id(obj.changeset_id());
-        w.set_timestamp(obj.timestamp().clone());
+        w.set_timestamp(obj.timestamp());
         w.set_uid(obj.uid());
         w.set_user(obj.user());
 
@@ -45,6 +65,7 @@ impl From<RcWay> for StringWay {
         }
 
         w.set_nodes(obj.nodes().iter().copied());
+        w._node_locations = obj._node_locations;
 
         w
     }
@@ -60,7 +81,7 @@ impl From<RcRelation> for StringRelation {
         r.set_version(obj.version());
         r.set_deleted(obj.deleted());
         r.set_changeset_id(obj.changeset_id());
-        r.set_timestamp(obj.timestamp().clone());
+        r.set_timestamp(obj.timestamp());
         r.set_uid(obj.uid());
         r.set_user(obj.user());
 
@@ -91,7 +112,7 @@ impl From<ArcNode> for StringNode {
         n.set_version(obj.version());
         n.set_deleted(obj.deleted());
         n.set_changeset_id(obj.changeset_id());
-        n.set_timestamp(obj.timestamp().clone());
+        n.set_timestamp(obj.timestamp());
         n.set_uid(obj.uid());
         n.set_user(obj.user());
         n.set_lat_lon_direct(obj.lat_lon());
@@ -111,7 +132,7 @@ impl From<ArcWay> for StringWay {
         w.set_version(obj.version());
         w.set_deleted(obj.deleted());
         w.set_changeset_id(obj.changeset_id());
-        w.set_timestamp(obj.timestamp().clone());
+        w.set_timestamp(obj.timestamp());
         w.set_uid(obj.uid());
         w.set_user(obj.user());
 
@@ -120,6 +141,7 @@ impl From<ArcWay> for StringWay {
         }
 
         w.set_nodes(obj.nodes().iter().copied());
+        w._node_locations = obj._node_locations;
 
         w
     }
@@ -135,7 +157,7 @@ impl From<ArcRelation> for StringRelation {
         r.set_version(obj.version());
         r.set_deleted(obj.deleted());
         r.set_changeset_id(obj.changeset_id());
-        r.set_timestamp(obj.timestamp().clone());
+        r.set_timestamp(obj.timestamp());
         r.set_uid(obj.uid());
         r.set_user(obj.user());
 
@@ -158,3 +180,86 @@ impl From<ArcOSMObj> for StringOSMObj {
         }
     }
 }
+
+macro_rules! from_string_types {
+    ($ptr:ident, $node:ident, $way:ident, $relation:ident, $obj:ident) => {
+        impl From<StringNode> for $node {
+            fn from(obj: StringNode) -> Self {
+                $node {
+                    _id: obj._id,
+                    _version: obj._version,
+                    _deleted: obj._deleted,
+                    _changeset_id: obj._changeset_id,
+                    _timestamp: obj._timestamp,
+                    _uid: obj._uid,
+                    _user: obj._user.map(|u| $ptr::from(u.as_str())),
+                    _tags: (!obj._tags.is_empty()).then(|| {
+                        obj._tags
+                            .iter()
+                            .map(|(k, v)| ($ptr::from(k.as_str()), $ptr::from(v.as_str())))
+                            .collect()
+                    }),
+                    _lat_lon: obj._lat_lon,
+                }
+            }
+        }
+
+        impl From<StringWay> for $way {
+            fn from(obj: StringWay) -> Self {
+                $way {
+                    _id: obj._id,
+                    _version: obj._version,
+                    _deleted: obj._deleted,
+                    _changeset_id: obj._changeset_id,
+                    _timestamp: obj._timestamp,
+                    _uid: obj._uid,
+                    _user: obj._user.map(|u| $ptr::from(u.as_str())),
+                    _tags: obj
+                        ._tags
+                        .iter()
+                        .map(|(k, v)| ($ptr::from(k.as_str()), $ptr::from(v.as_str())))
+                        .collect(),
+                    _nodes: obj._nodes.into_vec(),
+                    _node_locations: obj._node_locations,
+                }
+            }
+        }
+
+        impl From<StringRelation> for $relation {
+            fn from(obj: StringRelation) -> Self {
+                $relation {
+                    _id: obj._id,
+                    _version: obj._version,
+                    _deleted: obj._deleted,
+                    _changeset_id: obj._changeset_id,
+                    _timestamp: obj._timestamp,
+                    _uid: obj._uid,
+                    _user: obj._user.map(|u| $ptr::from(u.as_str())),
+                    _tags: obj
+                        ._tags
+                        .iter()
+                        .map(|(k, v)| ($ptr::from(k.as_str()), $ptr::from(v.as_str())))
+                        .collect(),
+                    _members: obj
+                        ._members
+                        .into_iter()
+                        .map(|(t, id, role)| (t, id, $ptr::from(role.as_str())))
+                        .collect(),
+                }
+            }
+        }
+
+        impl From<StringOSMObj> for $obj {
+            fn from(obj: StringOSMObj) -> Self {
+                match obj {
+                    StringOSMObj::Node(n) => $obj::Node(n.into()),
+                    StringOSMObj::Way(w) => $obj::Way(w.into()),
+                    StringOSMObj::Relation(r) => $obj::Relation(r.into()),
+                }
+            }
+        }
+    };
+}
+
+from_string_types!(Arc, ArcNode, ArcWay, ArcRelation, ArcOSMObj);
+from_string_types!(Rc, RcNode, RcWay, RcRelation, RcOSMObj);
@@ -0,0 +1,492 @@
+//! Serde support for the object types, in the OSM JSON layout. See the [`obj_types`](super)
+//! docs.
+use super::*;
+use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, Timestamp};
+use serde::de::{self, Deserializer, MapAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq, SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// `"node"`, `"way"` or `"relation"`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TypeName {
+    Node,
+    Way,
+    Relation,
+}
+
+impl From<OSMObjectType> for TypeName {
+    fn from(t: OSMObjectType) -> Self {
+        match t {
+            OSMObjectType::Node => TypeName::Node,
+            OSMObjectType::Way => TypeName::Way,
+            OSMObjectType::Relation => TypeName::Relation,
+        }
+    }
+}
+
+impl From<TypeName> for OSMObjectType {
+    fn from(t: TypeName) -> Self {
+        match t {
+            TypeName::Node => OSMObjectType::Node,
+            TypeName::Way => OSMObjectType::Way,
+            TypeName::Relation => OSMObjectType::Relation,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MemberRef<'a> {
+    #[serde(rename = "type")]
+    object_type: TypeName,
+    #[serde(rename = "ref")]
+    id: ObjId,
+    role: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Member {
+    #[serde(rename = "type")]
+    object_type: TypeName,
+    #[serde(rename = "ref")]
+    id: ObjId,
+    #[serde(default)]
+    role: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LatLon {
+    lat: f64,
+    lon: f64,
+}
+
+/// The type specific parts of an object
+trait JsonParts: OSMObjBase {
+    fn json_lat_lon(&self) -> Option<(Lat, Lon)> {
+        None
+    }
+    fn json_nodes(&self) -> Option<&[ObjId]> {
+        None
+    }
+    fn json_geometry(&self) -> Option<&[(Lat, Lon)]> {
+        None
+    }
+    fn json_members(&self) -> Option<Vec<MemberRef<'_>>> {
+        None
+    }
+}
+
+/// Tags as a map, in the object's order
+struct Tags<'a, O>(&'a O);
+
+impl<O: OSMObjBase> Serialize for Tags<'_, O> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let tags = self.0.tags();
+        let mut map = serializer.serialize_map(Some(tags.len()))?;
+        for (k, v) in tags {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+struct Geometry<'a>(&'a [(Lat, Lon)]);
+
+impl Serialize for Geometry<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for (lat, lon) in self.0 {
+            seq.serialize_element(&LatLon {
+                lat: lat.degrees(),
+                lon: lon.degrees(),
+            })?;
+        }
+        seq.end()
+    }
+}
+
+/// For human readable formats (e.g. JSON), missing & empty fields are left out. Otherwise
+/// (e.g. bincode) every field is always written, so the layout doesn't change.
+fn serialize_obj<O: JsonParts, S: Serializer>(obj: &O, serializer: S) -> Result<S::Ok, S::Error> {
+    let all_fields = !serializer.is_human_readable();
+    let mut st = serializer.serialize_struct("OSMObj", 14)?;
+    macro_rules! field {
+        ($name:literal, $value:expr, $present:expr) => {
+            if all_fields || $present {
+                st.serialize_field($name, &$value)?;
+            } else {
+                st.skip_field($name)?;
+            }
+        };
+    }
+
+    st.serialize_field("type", &TypeName::from(obj.object_type()))?;
+    st.serialize_field("id", &obj.id())?;
+    let lat_lon = obj.json_lat_lon();
+    field!(
+        "lat",
+        lat_lon.map(|(lat, _)| lat.degrees()),
+        lat_lon.is_some()
+    );
+    field!(
+        "lon",
+        lat_lon.map(|(_, lon)| lon.degrees()),
+        lat_lon.is_some()
+    );
+    field!("timestamp", obj.timestamp(), obj.timestamp().is_some());
+    field!("version", obj.version(), obj.version().is_some());
+    field!(
+        "changeset",
+        obj.changeset_id(),
+        obj.changeset_id().is_some()
+    );
+    field!("user", obj.user(), obj.user().is_some());
+    field!("uid", obj.uid(), obj.uid().is_some());
+    field!("visible", !obj.deleted(), obj.deleted());
+    field!("tags", Tags(obj), obj.tagged());
+    let nodes = obj.json_nodes();
+    field!("nodes", nodes.unwrap_or_default(), nodes.is_some());
+    let geometry = obj.json_geometry();
+    field!("geometry", geometry.map(Geometry), geometry.is_some());
+    let members = obj.json_members();
+    field!(
+        "members",
+        members.as_deref().unwrap_or_default(),
+        members.is_some()
+    );
+    st.end()
+}
+
+/// Tags, keeping the order
+#[derive(Default)]
+struct TagList(Vec<(String, String)>);
+
+impl<'de> Deserialize<'de> for TagList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagsVisitor;
+        impl<'de> Visitor<'de> for TagsVisitor {
+            type Value = TagList;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a map of tags")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TagList, A::Error> {
+                let mut tags = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(tag) = map.next_entry()? {
+                    tags.push(tag);
+                }
+                Ok(TagList(tags))
+            }
+        }
+        deserializer.deserialize_map(TagsVisitor)
+    }
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Every field, in the order they're serialized
+#[derive(Deserialize)]
+struct ObjFields {
+    #[serde(rename = "type")]
+    object_type: TypeName,
+    id: ObjId,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    timestamp: Option<Timestamp>,
+    version: Option<u32>,
+    changeset: Option<u32>,
+    user: Option<String>,
+    uid: Option<u32>,
+    #[serde(default = "default_visible")]
+    visible: bool,
+    #[serde(default)]
+    tags: TagList,
+    #[serde(default)]
+    nodes: Vec<ObjId>,
+    geometry: Option<Vec<LatLon>>,
+    #[serde(default)]
+    members: Vec<Member>,
+}
+
+fn lat_lon<E: de::Error>(lat: f64, lon: f64) -> Result<(Lat, Lon), E> {
+    Ok((
+        Lat::try_from(lat).map_err(E::custom)?,
+        Lon::try_from(lon).map_err(E::custom)?,
+    ))
+}
+
+impl ObjFields {
+    fn expect_type<E: de::Error>(&self, expected: OSMObjectType) -> Result<(), E> {
+        let actual = OSMObjectType::from(self.object_type);
+        if actual != expected {
+            return Err(E::custom(format!(
+                "expected a {}, not a {}",
+                expected, actual
+            )));
+        }
+        Ok(())
+    }
+
+    fn into_node<E: de::Error>(self) -> Result<StringNode, E> {
+        self.expect_type(OSMObjectType::Node)?;
+        Ok(StringNode {
+            _id: self.id,
+            _version: self.version,
+            _deleted: !self.visible,
+            _changeset_id: self.changeset,
+            _timestamp: self.timestamp,
+            _uid: self.uid,
+            _user: self.user.map(Into::into),
+            _tags: tags(self.tags),
+            _lat_lon: match (self.lat, self.lon) {
+                (Some(lat), Some(lon)) => Some(lat_lon(lat, lon)?),
+                _ => None,
+            },
+        })
+    }
+
+    fn into_way<E: de::Error>(self) -> Result<StringWay, E> {
+        self.expect_type(OSMObjectType::Way)?;
+        Ok(StringWay {
+            _id: self.id,
+            _version: self.version,
+            _deleted: !self.visible,
+            _changeset_id: self.changeset,
+            _timestamp: self.timestamp,
+            _uid: self.uid,
+            _user: self.user.map(Into::into),
+            _tags: tags(self.tags),
+            _nodes: self.nodes.into(),
+            _node_locations: self
+                .geometry
+                .map(|g| g.into_iter().map(|ll| lat_lon(ll.lat, ll.lon)).collect())
+                .transpose()?,
+        })
+    }
+
+    fn into_relation<E: de::Error>(self) -> Result<StringRelation, E> {
+        self.expect_type(OSMObjectType::Relation)?;
+        Ok(StringRelation {
+            _id: self.id,
+            _version: self.version,
+            _deleted: !self.visible,
+            _changeset_id: self.changeset,
+            _timestamp: self.timestamp,
+            _uid: self.uid,
+            _user: self.user.map(Into::into),
+            _tags: tags(self.tags),
+            _members: self
+                .members
+                .into_iter()
+                .map(|m| (m.object_type.into(), m.id, m.role.into()))
+                .collect(),
+        })
+    }
+
+    fn into_obj<E: de::Error>(self) -> Result<StringOSMObj, E> {
+        Ok(match self.object_type {
+            TypeName::Node => self.into_node()?.into(),
+            TypeName::Way => self.into_way()?.into(),
+            TypeName::Relation => self.into_relation()?.into(),
+        })
+    }
+}
+
+fn tags(tags: TagList) -> smallvec::SmallVec<[(smol_str::SmolStr, smol_str::SmolStr); 1]> {
+    tags.0
+        .into_iter()
+        .map(|(k, v)| (k.into(), v.into()))
+        .collect()
+}
+
+macro_rules! impl_json_parts {
+    ($obj:ident, $node:ident, $way:ident, $relation:ident) => {
+        impl JsonParts for $node {
+            fn json_lat_lon(&self) -> Option<(Lat, Lon)> {
+                self.lat_lon()
+            }
+        }
+
+        impl JsonParts for $way {
+            fn json_nodes(&self) -> Option<&[ObjId]> {
+                Some(self.nodes())
+            }
+            fn json_geometry(&self) -> Option<&[(Lat, Lon)]> {
+                self.node_locations()
+            }
+        }
+
+        impl JsonParts for $relation {
+            fn json_members(&self) -> Option<Vec<MemberRef<'_>>> {
+                Some(
+                    self.members()
+                        .map(|(object_type, id, role)| MemberRef {
+                            object_type: object_type.into(),
+                            id,
+                            role,
+                        })
+                        .collect(),
+                )
+            }
+        }
+
+        impl JsonParts for $obj {
+            fn json_lat_lon(&self) -> Option<(Lat, Lon)> {
+                self.as_node().and_then(|n| n.json_lat_lon())
+            }
+            fn json_nodes(&self) -> Option<&[ObjId]> {
+                self.as_way().and_then(|w| w.json_nodes())
+            }
+            fn json_geometry(&self) -> Option<&[(Lat, Lon)]> {
+                self.as_way().and_then(|w| w.json_geometry())
+            }
+            fn json_members(&self) -> Option<Vec<MemberRef<'_>>> {
+                self.as_relation().and_then(|r| r.json_members())
+            }
+        }
+
+        impl_json_parts!(@serialize $obj, $node, $way, $relation);
+    };
+    (@serialize $($t:ident),*) => {
+        $(
+            impl Serialize for $t {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serialize_obj(self, serializer)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_parts!(StringOSMObj, StringNode, StringWay, StringRelation);
+impl_json_parts!(ArcOSMObj, ArcNode, ArcWay, ArcRelation);
+impl_json_parts!(RcOSMObj, RcNode, RcWay, RcRelation);
+
+macro_rules! impl_deserialize {
+    ($t:ident, $into:ident) => {
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                ObjFields::deserialize(deserializer)?.$into()
+            }
+        }
+    };
+}
+
+impl_deserialize!(StringNode, into_node);
+impl_deserialize!(StringWay, into_way);
+impl_deserialize!(StringRelation, into_relation);
+impl_deserialize!(StringOSMObj, into_obj);
+
+/// Deserialized as the `String*` type, then converted
+macro_rules! impl_deserialize_via {
+    ($($t:ident <- $via:ident),*) => {
+        $(
+            impl<'de> Deserialize<'de> for $t {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    $via::deserialize(deserializer).map(Into::into)
+                }
+            }
+        )*
+    };
+}
+
+impl_deserialize_via!(
+    ArcNode <- StringNode,
+    ArcWay <- StringWay,
+    ArcRelation <- StringRelation,
+    ArcOSMObj <- StringOSMObj,
+    RcNode <- StringNode,
+    RcWay <- StringWay,
+    RcRelation <- StringRelation,
+    RcOSMObj <- StringOSMObj
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn objects() -> Vec<StringOSMObj> {
+        vec![
+            StringNodeBuilder::default()
+                ._id(1)
+                ._version(2)
+                ._changeset_id(3)
+                ._timestamp(1577836800.into())
+                ._uid(4)
+                ._user("Jane".into())
+                ._tags(smallvec::smallvec![
+                    ("name".into(), "Café".into()),
+                    ("amenity".into(), "cafe".into())
+                ])
+                ._lat_lon((Lat::from_inner(515000000), Lon::from_inner(-1000000)))
+                .build()
+                .unwrap()
+                .into(),
+            StringWayBuilder::default()
+                ._id(10)
+                ._nodes(smallvec::smallvec![1, 2, 1])
+                ._node_locations(vec![
+                    (Lat::from_inner(1), Lon::from_inner(2)),
+                    (Lat::from_inner(3), Lon::from_inner(4)),
+                    (Lat::from_inner(1), Lon::from_inner(2)),
+                ])
+                .build()
+                .unwrap()
+                .into(),
+            StringRelationBuilder::default()
+                ._id(100)
+                ._deleted(true)
+                ._members(vec![
+                    (OSMObjectType::Way, 10, "outer".into()),
+                    (OSMObjectType::Node, 1, "".into()),
+                ])
+                .build()
+                .unwrap()
+                .into(),
+        ]
+    }
+
+    #[test]
+    fn json() {
+        let objs = objects();
+        let json = serde_json::to_string(&objs).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"[{"type":"node","id":1,"lat":51.5,"lon":-0.1,"timestamp":"2020-01-01T00:00:00Z","version":2,"changeset":3,"user":"Jane","uid":4,"tags":{"name":"Café","amenity":"cafe"}},"#,
+                r#"{"type":"way","id":10,"nodes":[1,2,1],"geometry":[{"lat":1e-7,"lon":2e-7},{"lat":3e-7,"lon":4e-7},{"lat":1e-7,"lon":2e-7}]},"#,
+                r#"{"type":"relation","id":100,"visible":false,"members":[{"type":"way","ref":10,"role":"outer"},{"type":"node","ref":1,"role":""}]}]"#
+            )
+        );
+        assert_eq!(
+            serde_json::from_str::<Vec<StringOSMObj>>(&json).unwrap(),
+            objs
+        );
+
+        let arc: Vec<ArcOSMObj> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&arc).unwrap(), json);
+        assert_eq!(
+            serde_json::from_str::<StringWay>(r#"{"type":"way","id":1,"extra":[]}"#)
+                .unwrap()
+                .id(),
+            1
+        );
+        assert!(serde_json::from_str::<StringNode>(r#"{"type":"way","id":1}"#).is_err());
+    }
+
+    #[test]
+    fn bincode() {
+        let objs = objects();
+        let bytes = bincode::serialize(&objs).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Vec<StringOSMObj>>(&bytes).unwrap(),
+            objs
+        );
+        let rc: Vec<RcOSMObj> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(rc[2].id(), 100);
+    }
+}
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, TimestampFormat};
+use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, Timestamp};
 use std::rc::Rc;
 
 macro_rules! func_call_inner_get {
@@ -28,7 +28,7 @@ pub struct RcNode {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Rc<str>>,
     pub(crate) _tags: Option<Vec<(Rc<str>, Rc<str>)>>,
@@ -42,12 +42,13 @@ pub struct RcWay {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Rc<str>>,
     pub(crate) _tags: Vec<(Rc<str>, Rc<str>)>,
 
     pub(crate) _nodes: Vec<ObjId>,
+    pub(crate) _node_locations: Option<Vec<(Lat, Lon)>>,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -56,7 +57,7 @@ pub struct RcRelation {
     pub(crate) _version: Option<u32>,
     pub(crate) _deleted: bool,
     pub(crate) _changeset_id: Option<u32>,
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     pub(crate) _uid: Option<u32>,
     pub(crate) _user: Option<Rc<str>>,
     pub(crate) _tags: Vec<(Rc<str>, Rc<str>)>,
@@ -71,7 +72,7 @@ pub enum RcOSMObj {
     Relation(RcRelation),
 }
 
-impl OSMObjBase for RcOSMObj {
+impl OSMObjBaseRead for RcOSMObj {
     fn id(&self) -> ObjId {
         func_call_inner_get!(self, id)
     }
@@ -84,7 +85,7 @@ impl OSMObjBase for RcOSMObj {
     fn changeset_id(&self) -> Option<u32> {
         func_call_inner_get!(self, changeset_id)
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
+    fn timestamp(&self) -> Option<Timestamp> {
         func_call_inner_get!(self, timestamp)
     }
     fn uid(&self) -> Option<u32> {
@@ -94,28 +95,6 @@ impl OSMObjBase for RcOSMObj {
         func_call_inner_get!(self, user)
     }
 
-    fn set_id(&mut self, val: impl Into<ObjId>) {
-        func_call_inner_set!(self, set_id, val);
-    }
-    fn set_version(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_version, val);
-    }
-    fn set_deleted(&mut self, val: bool) {
-        func_call_inner_set!(self, set_deleted, val);
-    }
-    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_changeset_id, val);
-    }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
-        func_call_inner_set!(self, set_timestamp, val);
-    }
-    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
-        func_call_inner_set!(self, set_uid, val);
-    }
-    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
-        func_call_inner_set!(self, set_user, val);
-    }
-
     fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
         match self {
             RcOSMObj::Node(x) => x.tags(),
@@ -147,6 +126,38 @@ impl OSMObjBase for RcOSMObj {
         }
     }
 
+    fn object_type(&self) -> OSMObjectType {
+        match self {
+            RcOSMObj::Node(_) => OSMObjectType::Node,
+            RcOSMObj::Way(_) => OSMObjectType::Way,
+            RcOSMObj::Relation(_) => OSMObjectType::Relation,
+        }
+    }
+}
+
+impl OSMObjBase for RcOSMObj {
+    fn set_id(&mut self, val: impl Into<ObjId>) {
+        func_call_inner_set!(self, set_id, val);
+    }
+    fn set_version(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_version, val);
+    }
+    fn set_deleted(&mut self, val: bool) {
+        func_call_inner_set!(self, set_deleted, val);
+    }
+    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_changeset_id, val);
+    }
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
+        func_call_inner_set!(self, set_timestamp, val);
+    }
+    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
+        func_call_inner_set!(self, set_uid, val);
+    }
+    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
+        func_call_inner_set!(self, set_user, val);
+    }
+
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         match self {
             RcOSMObj::Node(x) => x.set_tag(key, value),
@@ -162,14 +173,6 @@ impl OSMObjBase for RcOSMObj {
             RcOSMObj::Relation(x) => x.unset_tag(key),
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        match self {
-            RcOSMObj::Node(_) => OSMObjectType::Node,
-            RcOSMObj::Way(_) => OSMObjectType::Way,
-            RcOSMObj::Relation(_) => OSMObjectType::Relation,
-        }
-    }
 }
 
 impl OSMObj for RcOSMObj {
@@ -250,7 +253,7 @@ impl OSMObj for RcOSMObj {
     }
 }
 
-impl OSMObjBase for RcNode {
+impl OSMObjBaseRead for RcNode {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -263,8 +266,8 @@ impl OSMObjBase for RcNode {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -273,28 +276,6 @@ impl OSMObjBase for RcNode {
         self._user.as_ref().map(|x| x as _)
     }
 
-    fn set_id(&mut self, val: impl Into<ObjId>) {
-        self._id = val.into();
-    }
-    fn set_version(&mut self, val: impl Into<Option<u32>>) {
-        self._version = val.into();
-    }
-    fn set_deleted(&mut self, val: bool) {
-        self._deleted = val;
-    }
-    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
-        self._changeset_id = val.into();
-    }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
-        self._timestamp = val.into();
-    }
-    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
-        self._uid = val.into();
-    }
-    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
-        self._user = val.into().map(Rc::from);
-    }
-
     fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
         match self._tags {
             None => Box::new(std::iter::empty()),
@@ -320,6 +301,34 @@ impl OSMObjBase for RcNode {
         }
     }
 
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Node
+    }
+}
+
+impl OSMObjBase for RcNode {
+    fn set_id(&mut self, val: impl Into<ObjId>) {
+        self._id = val.into();
+    }
+    fn set_version(&mut self, val: impl Into<Option<u32>>) {
+        self._version = val.into();
+    }
+    fn set_deleted(&mut self, val: bool) {
+        self._deleted = val;
+    }
+    fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
+        self._changeset_id = val.into();
+    }
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
+        self._timestamp = val.into();
+    }
+    fn set_uid(&mut self, val: impl Into<Option<u32>>) {
+        self._uid = val.into();
+    }
+    fn set_user<'a>(&mut self, val: impl Into<Option<&'a str>>) {
+        self._user = val.into().map(Rc::from);
+    }
+
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -354,23 +363,21 @@ impl OSMObjBase for RcNode {
             }
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Node
-    }
 }
 
-impl Node for RcNode {
+impl NodeRead for RcNode {
     fn lat_lon(&self) -> Option<(Lat, Lon)> {
         self._lat_lon
     }
+}
 
+impl Node for RcNode {
     fn set_lat_lon_direct(&mut self, loc: Option<(Lat, Lon)>) {
         self._lat_lon = loc;
     }
 }
 
-impl OSMObjBase for RcWay {
+impl OSMObjBaseRead for RcWay {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -383,8 +390,8 @@ impl OSMObjBase for RcWay {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -393,6 +400,30 @@ impl OSMObjBase for RcWay {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.as_ref() == key {
+                    Some(v.as_ref())
+                } else {
+                    None
+                }
+            })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Way
+    }
+}
+
+impl OSMObjBase for RcWay {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -405,7 +436,7 @@ impl OSMObjBase for RcWay {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -415,24 +446,6 @@ impl OSMObjBase for RcWay {
         self._user = val.into().map(Rc::from);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| {
-                if k.as_ref() == key {
-                    Some(v.as_ref())
-                } else {
-                    None
-                }
-            })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -460,13 +473,9 @@ impl OSMObjBase for RcWay {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Way
-    }
 }
 
-impl Way for RcWay {
+impl WayRead for RcWay {
     fn nodes(&self) -> &[ObjId] {
         &self._nodes
     }
@@ -478,13 +487,20 @@ impl Way for RcWay {
     fn node(&self, idx: usize) -> Option<ObjId> {
         self._nodes.get(idx).cloned()
     }
+    fn node_locations(&self) -> Option<&[(Lat, Lon)]> {
+        self._node_locations.as_deref()
+    }
+}
+
+impl Way for RcWay {
     fn set_nodes(&mut self, nodes: impl IntoIterator<Item = impl Into<ObjId>>) {
         self._nodes.clear();
         self._nodes.extend(nodes.into_iter().map(|i| i.into()));
+        self._node_locations = None;
     }
 }
 
-impl OSMObjBase for RcRelation {
+impl OSMObjBaseRead for RcRelation {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -497,8 +513,8 @@ impl OSMObjBase for RcRelation {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -507,6 +523,30 @@ impl OSMObjBase for RcRelation {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| {
+                if k.as_ref() == key {
+                    Some(v.as_ref())
+                } else {
+                    None
+                }
+            })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Relation
+    }
+}
+
+impl OSMObjBase for RcRelation {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -519,7 +559,7 @@ impl OSMObjBase for RcRelation {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -529,24 +569,6 @@ impl OSMObjBase for RcRelation {
         self._user = val.into().map(Rc::from);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| {
-                if k.as_ref() == key {
-                    Some(v.as_ref())
-                } else {
-                    None
-                }
-            })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -574,19 +596,17 @@ impl OSMObjBase for RcRelation {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Relation
-    }
 }
 
-impl Relation for RcRelation {
+impl RelationRead for RcRelation {
     fn members<'a>(
         &'a self,
     ) -> Box<dyn ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)> + 'a> {
         Box::new(self._members.iter().map(|(t, o, r)| (*t, *o, r.as_ref())))
     }
+}
 
+impl Relation for RcRelation {
     fn set_members(
         &mut self,
         members: impl IntoIterator<Item = (OSMObjectType, ObjId, impl Into<String>)>,
@@ -1,5 +1,5 @@
 use super::*;
-use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, TimestampFormat};
+use crate::{Lat, Lon, OSMObj, OSMObjectType, ObjId, Timestamp, WrongNumberOfLocations};
 use smallvec::SmallVec;
 use smol_str::SmolStr;
 
@@ -36,7 +36,7 @@ pub struct StringNode {
     #[builder(default = "None")]
     pub(crate) _changeset_id: Option<u32>,
     #[builder(default = "None")]
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     #[builder(default = "None")]
     pub(crate) _uid: Option<u32>,
     #[builder(default = "None")]
@@ -60,7 +60,7 @@ pub struct StringWay {
     #[builder(default = "None")]
     pub(crate) _changeset_id: Option<u32>,
     #[builder(default = "None")]
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     #[builder(default = "None")]
     pub(crate) _uid: Option<u32>,
     #[builder(default = "None")]
@@ -71,6 +71,9 @@ pub struct StringWay {
 
     #[builder(default = "smallvec::smallvec![]")]
     pub(crate) _nodes: SmallVec<[ObjId; 6]>,
+
+    #[builder(default = "None")]
+    pub(crate) _node_locations: Option<Vec<(Lat, Lon)>>,
 }
 
 #[derive(PartialEq, Debug, Builder, Clone)]
@@ -84,7 +87,7 @@ pub struct StringRelation {
     #[builder(default = "None")]
     pub(crate) _changeset_id: Option<u32>,
     #[builder(default = "None")]
-    pub(crate) _timestamp: Option<TimestampFormat>,
+    pub(crate) _timestamp: Option<Timestamp>,
     #[builder(default = "None")]
     pub(crate) _uid: Option<u32>,
     #[builder(default = "None")]
@@ -120,7 +123,7 @@ impl From<StringRelation> for StringOSMObj {
     }
 }
 
-impl OSMObjBase for StringOSMObj {
+impl OSMObjBaseRead for StringOSMObj {
     fn id(&self) -> ObjId {
         func_call_inner_get!(self, id)
     }
@@ -133,7 +136,7 @@ impl OSMObjBase for StringOSMObj {
     fn changeset_id(&self) -> Option<u32> {
         func_call_inner_get!(self, changeset_id)
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
+    fn timestamp(&self) -> Option<Timestamp> {
         func_call_inner_get!(self, timestamp)
     }
     fn uid(&self) -> Option<u32> {
@@ -143,6 +146,28 @@ impl OSMObjBase for StringOSMObj {
         func_call_inner_get!(self, user)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        func_call_inner_get!(self, tags)
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        match self {
+            StringOSMObj::Node(x) => x.tag(key),
+            StringOSMObj::Way(x) => x.tag(key),
+            StringOSMObj::Relation(x) => x.tag(key),
+        }
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        match self {
+            StringOSMObj::Node(_) => OSMObjectType::Node,
+            StringOSMObj::Way(_) => OSMObjectType::Way,
+            StringOSMObj::Relation(_) => OSMObjectType::Relation,
+        }
+    }
+}
+
+impl OSMObjBase for StringOSMObj {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         func_call_inner_set!(self, set_id, val);
     }
@@ -155,7 +180,7 @@ impl OSMObjBase for StringOSMObj {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         func_call_inner_set!(self, set_changeset_id, val);
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         func_call_inner_set!(self, set_timestamp, val);
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -165,18 +190,6 @@ impl OSMObjBase for StringOSMObj {
         func_call_inner_set!(self, set_user, val);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        func_call_inner_get!(self, tags)
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        match self {
-            StringOSMObj::Node(x) => x.tag(key),
-            StringOSMObj::Way(x) => x.tag(key),
-            StringOSMObj::Relation(x) => x.tag(key),
-        }
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         match self {
             StringOSMObj::Node(x) => x.set_tag(key, value),
@@ -192,14 +205,6 @@ impl OSMObjBase for StringOSMObj {
             StringOSMObj::Relation(x) => x.unset_tag(key),
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        match self {
-            StringOSMObj::Node(_) => OSMObjectType::Node,
-            StringOSMObj::Way(_) => OSMObjectType::Way,
-            StringOSMObj::Relation(_) => OSMObjectType::Relation,
-        }
-    }
 }
 
 impl OSMObj for StringOSMObj {
@@ -280,7 +285,7 @@ impl OSMObj for StringOSMObj {
     }
 }
 
-impl OSMObjBase for StringNode {
+impl OSMObjBaseRead for StringNode {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -293,8 +298,8 @@ impl OSMObjBase for StringNode {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -303,6 +308,24 @@ impl OSMObjBase for StringNode {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Node
+    }
+}
+
+impl OSMObjBase for StringNode {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -315,7 +338,7 @@ impl OSMObjBase for StringNode {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -325,18 +348,6 @@ impl OSMObjBase for StringNode {
         self._user = val.into().map(SmolStr::new);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key: &str = key.as_ref();
         let value: String = value.into();
@@ -364,23 +375,21 @@ impl OSMObjBase for StringNode {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Node
-    }
 }
 
-impl Node for StringNode {
+impl NodeRead for StringNode {
     fn lat_lon(&self) -> Option<(Lat, Lon)> {
         self._lat_lon
     }
+}
 
+impl Node for StringNode {
     fn set_lat_lon_direct(&mut self, loc: Option<(Lat, Lon)>) {
         self._lat_lon = loc;
     }
 }
 
-impl OSMObjBase for StringWay {
+impl OSMObjBaseRead for StringWay {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -393,8 +402,8 @@ impl OSMObjBase for StringWay {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -403,6 +412,24 @@ impl OSMObjBase for StringWay {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Way
+    }
+}
+
+impl OSMObjBase for StringWay {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -415,7 +442,7 @@ impl OSMObjBase for StringWay {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -425,18 +452,6 @@ impl OSMObjBase for StringWay {
         self._user = val.into().map(SmolStr::new);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -464,13 +479,9 @@ impl OSMObjBase for StringWay {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Way
-    }
 }
 
-impl Way for StringWay {
+impl WayRead for StringWay {
     fn num_nodes(&self) -> usize {
         self._nodes.len()
     }
@@ -480,13 +491,34 @@ impl Way for StringWay {
     fn node(&self, idx: usize) -> Option<ObjId> {
         self._nodes.get(idx).cloned()
     }
+    fn node_locations(&self) -> Option<&[(Lat, Lon)]> {
+        self._node_locations.as_deref()
+    }
+}
+
+impl Way for StringWay {
     fn set_nodes(&mut self, nodes: impl IntoIterator<Item = impl Into<ObjId>>) {
         self._nodes.truncate(0);
         self._nodes.extend(nodes.into_iter().map(|i| i.into()));
+        self._node_locations = None;
     }
 }
 
-impl OSMObjBase for StringRelation {
+impl StringWay {
+    /// Set (or remove) the location of each node. There must be one location per node.
+    pub fn set_node_locations(
+        &mut self,
+        locations: Option<Vec<(Lat, Lon)>>,
+    ) -> Result<(), WrongNumberOfLocations> {
+        if let Some(locations) = &locations {
+            WrongNumberOfLocations::check(self._nodes.len(), locations.len())?;
+        }
+        self._node_locations = locations;
+        Ok(())
+    }
+}
+
+impl OSMObjBaseRead for StringRelation {
     fn id(&self) -> ObjId {
         self._id
     }
@@ -499,8 +531,8 @@ impl OSMObjBase for StringRelation {
     fn changeset_id(&self) -> Option<u32> {
         self._changeset_id
     }
-    fn timestamp(&self) -> &Option<TimestampFormat> {
-        &self._timestamp
+    fn timestamp(&self) -> Option<Timestamp> {
+        self._timestamp
     }
     fn uid(&self) -> Option<u32> {
         self._uid
@@ -509,6 +541,24 @@ impl OSMObjBase for StringRelation {
         self._user.as_ref().map(|x| x as _)
     }
 
+    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
+        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
+    }
+
+    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+        let key = key.as_ref();
+        self._tags
+            .iter()
+            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
+            .next()
+    }
+
+    fn object_type(&self) -> OSMObjectType {
+        OSMObjectType::Relation
+    }
+}
+
+impl OSMObjBase for StringRelation {
     fn set_id(&mut self, val: impl Into<ObjId>) {
         self._id = val.into();
     }
@@ -521,7 +571,7 @@ impl OSMObjBase for StringRelation {
     fn set_changeset_id(&mut self, val: impl Into<Option<u32>>) {
         self._changeset_id = val.into();
     }
-    fn set_timestamp(&mut self, val: impl Into<Option<TimestampFormat>>) {
+    fn set_timestamp(&mut self, val: impl Into<Option<Timestamp>>) {
         self._timestamp = val.into();
     }
     fn set_uid(&mut self, val: impl Into<Option<u32>>) {
@@ -531,18 +581,6 @@ impl OSMObjBase for StringRelation {
         self._user = val.into().map(SmolStr::new);
     }
 
-    fn tags<'a>(&'a self) -> Box<dyn ExactSizeIterator<Item = (&'a str, &'a str)> + 'a> {
-        Box::new(self._tags.iter().map(|(k, v)| (k.as_ref(), v.as_ref())))
-    }
-
-    fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
-        let key = key.as_ref();
-        self._tags
-            .iter()
-            .filter_map(|(k, v)| if k == key { Some(v.as_ref()) } else { None })
-            .next()
-    }
-
     fn set_tag(&mut self, key: impl AsRef<str>, value: impl Into<String>) {
         let key = key.as_ref();
         let value = value.into();
@@ -570,19 +608,17 @@ impl OSMObjBase for StringRelation {
             self._tags.remove(i);
         }
     }
-
-    fn object_type(&self) -> OSMObjectType {
-        OSMObjectType::Relation
-    }
 }
 
-impl Relation for StringRelation {
+impl RelationRead for StringRelation {
     fn members<'a>(
         &'a self,
     ) -> Box<dyn ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)> + 'a> {
         Box::new(self._members.iter().map(|(t, i, r)| (*t, *i, r.as_str())))
     }
+}
 
+impl Relation for StringRelation {
     fn set_members(
         &mut self,
         members: impl IntoIterator<Item = (OSMObjectType, ObjId, impl Into<String>)>,
@@ -1,18 +1,73 @@
 //! OSC File format
 
+use super::BBox;
 use super::version;
-use super::{Node, OSMObj, OSMObjectType, Relation, Way};
+use super::{OSMObj, OSMObjBase};
 use super::{OSMReader, OSMWriteError, OSMWriter};
+use crate::compression::{CompressedReader, CompressedWriter, Compression};
 use crate::obj_types::StringOSMObj;
+use crate::progress::ReadProgress;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
 use std::io::{BufReader, Read, Write};
-use std::iter::Iterator;
+use std::path::Path;
+use std::str::FromStr;
 
-use crate::xml::{write_xml_escaped, xml_elements_to_osm_obj};
+use anyhow::Result;
 
-use xml_rs::reader::{EventReader, Events, XmlEvent};
+use crate::xml::{
+    XMLHeader, XMLObjInfo, obj_from_start, read_header_element, read_obj_children, write_bounds,
+    write_obj_element, write_xml_escaped,
+};
 
+use quick_xml::events::Event;
+
+/// Reads OSM Change (`.osc`) files
 pub struct OSCReader<R: Read> {
-    parser: Events<BufReader<R>>,
+    reader: quick_xml::Reader<BufReader<R>>,
+    buf: Vec<u8>,
+    header: XMLHeader,
+    header_complete: bool,
+    section: OSCAction,
+    pending: Option<(OSCAction, StringOSMObj)>,
+}
+
+/// The sections of an osmChange file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OSCAction {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl OSCAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OSCAction::Create => "create",
+            OSCAction::Modify => "modify",
+            OSCAction::Delete => "delete",
+        }
+    }
+}
+
+impl fmt::Display for OSCAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OSCAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "create" => Ok(OSCAction::Create),
+            "modify" => Ok(OSCAction::Modify),
+            "delete" => Ok(OSCAction::Delete),
+            _ => Err(format!("Unknown osmChange action {}", s)),
+        }
+    }
 }
 
 #[derive(PartialEq)]
@@ -24,69 +79,126 @@ enum State {
 
 pub struct OSCWriter<W: Write> {
     writer: W,
-    //headers: HashMap<String, String>,
+    headers: HashMap<String, String>,
+    bounds: Option<BBox>,
+    finish: fn(&mut W) -> std::io::Result<()>,
+    section: Option<OSCAction>,
     _state: State,
 }
 
+/// Opens an OSC file, decompressing it based on the filename extension (`.gz`, `.bz2` or
+/// `.zst`).
+pub fn from_filename(filename: impl AsRef<Path>) -> Result<OSCReader<CompressedReader<File>>> {
+    Ok(OSCReader::new(CompressedReader::open(filename)?))
+}
+
+/// Creates an OSC file, compressing it based on the filename extension (`.gz`, `.bz2` or
+/// `.zst`).
+///
+/// The compression is finished when the writer is closed.
+pub fn to_filename(filename: impl AsRef<Path>) -> Result<OSCWriter<CompressedWriter<File>>> {
+    Ok(OSCWriter::from_compressed(CompressedWriter::create(
+        filename,
+    )?))
+}
+
+impl<R: Read> OSCReader<R> {
+    /// Returns the next OSM object, or an error if the XML is invalid.
+    ///
+    /// Objects in a `<delete>` section are marked as deleted.
+    pub fn next_obj(&mut self) -> Result<Option<StringOSMObj>> {
+        Ok(self.next_change()?.map(|(_action, obj)| obj))
+    }
+
+    /// Returns the next OSM object, and which section (`create`, `modify` or `delete`) it is in.
+    pub fn next_change(&mut self) -> Result<Option<(OSCAction, StringOSMObj)>> {
+        if let Some(change) = self.pending.take() {
+            return Ok(Some(change));
+        }
+        self.read_next_obj()
+    }
+
+    /// The root element attributes (& bounds, if any) of this file.
+    ///
+    /// This can be called at any time. If no objects have been read yet, the file is read up to
+    /// the first object.
+    pub fn header(&mut self) -> Result<&XMLHeader> {
+        if !self.header_complete {
+            self.pending = self.read_next_obj()?;
+        }
+        Ok(&self.header)
+    }
+
+    fn read_next_obj(&mut self) -> Result<Option<(OSCAction, StringOSMObj)>> {
+        loop {
+            self.buf.clear();
+            let (e, is_empty) = match self.reader.read_event_into(&mut self.buf)? {
+                Event::Eof => {
+                    self.header_complete = true;
+                    return Ok(None);
+                }
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                _ => continue,
+            };
+            if !self.header_complete && read_header_element(&mut self.header, &e)? {
+                continue;
+            }
+            if let Ok(section) = std::str::from_utf8(e.local_name().as_ref())?.parse() {
+                self.section = section;
+                continue;
+            }
+            let mut info = XMLObjInfo::default();
+            let Some(mut obj) = obj_from_start(&e, &mut info)? else {
+                continue;
+            };
+            self.header_complete = true;
+            if !is_empty {
+                read_obj_children(&mut obj, &mut info, &mut self.reader, &mut self.buf)?;
+            }
+            if self.section == OSCAction::Delete {
+                obj.set_deleted(true);
+            }
+            return Ok(Some((self.section, obj)));
+        }
+    }
+}
+
 impl<R: Read> OSMReader for OSCReader<R> {
     type R = R;
     type Obj = StringOSMObj;
 
     fn new(reader: R) -> Self {
         OSCReader {
-            parser: EventReader::new(BufReader::new(reader)).into_iter(),
+            reader: quick_xml::Reader::from_reader(BufReader::new(reader)),
+            buf: Vec::new(),
+            header: XMLHeader::default(),
+            header_complete: false,
+            section: OSCAction::Modify,
+            pending: None,
         }
     }
 
     fn into_inner(self) -> R {
-        self.parser.into_inner().into_inner().into_inner()
+        self.reader.into_inner().into_inner()
     }
 
     fn inner(&self) -> &R {
-        todo!("{} {} OSCReader inner()", file!(), line!());
+        self.reader.get_ref().get_ref()
     }
 
     fn next(&mut self) -> Option<StringOSMObj> {
-        let mut elements = Vec::new();
-
-        // Pull xml/sax elements from the xml parser into a vector so we know what to work with.
-        let mut should_push = false;
-        loop {
-            let el = match self.parser.next() {
-                None => {
-                    break;
-                }
-                Some(e) => e,
-            };
-
-            let el = el.unwrap();
-
-            let mut should_break = false;
-            match el {
-                XmlEvent::StartElement { ref name, .. } => match name.local_name.as_str() {
-                    "node" | "way" | "relation" => {
-                        should_push = true;
-                    }
-                    _ => {}
-                },
-                XmlEvent::EndElement { ref name, .. } => match name.local_name.as_str() {
-                    "node" | "way" | "relation" => {
-                        should_break = true;
-                    }
-                    _ => {}
-                },
-                _ => {}
-            }
+        self.next_obj().expect("Invalid OSC XML")
+    }
+}
 
-            if should_push {
-                elements.push(el);
-            }
-            if should_break {
-                break;
-            }
-        }
+impl<R: Read + ReadProgress> ReadProgress for OSCReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.reader.get_ref().bytes_read()
+    }
 
-        xml_elements_to_osm_obj(&mut elements)
+    fn total_bytes(&self) -> Option<u64> {
+        self.reader.get_ref().total_bytes()
     }
 }
 
@@ -100,29 +212,101 @@ impl<W: Write> OSCWriter<W> {
                 version()
             )?;
 
-            //for (k, v) in self.headers.iter() {
-            //    write!(self.writer, " {}=\"", k)?;
-            //    write_xml_escaped(&mut self.writer, v)?;
-            //    write!(self.writer, "\"")?;
-            //}
+            for (k, v) in self.headers.iter() {
+                write!(self.writer, " {}=\"", k)?;
+                write_xml_escaped(&mut self.writer, v)?;
+                write!(self.writer, "\"")?;
+            }
             write!(self.writer, ">")?;
-            write!(self.writer, "\n<modify>")?;
+            if let Some(bounds) = &self.bounds {
+                write_bounds(&mut self.writer, bounds)?;
+            }
 
             self._state = State::WritingObjects;
         }
         Ok(())
     }
+
+    /// Write an object in the `action` section, starting a new section if needed.
+    ///
+    /// Consecutive objects with the same action share one section.
+    pub fn write_change(
+        &mut self,
+        action: OSCAction,
+        obj: &impl OSMObj,
+    ) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => self.ensure_header()?, // This will update self._state
+            State::WritingObjects => {}
+            State::Closed => return Err(OSMWriteError::AlreadyClosed),
+        }
+
+        if self.section != Some(action) {
+            if let Some(section) = self.section {
+                write!(self.writer, "\n</{}>", section)?;
+            }
+            write!(self.writer, "\n<{}>", action)?;
+            self.section = Some(action);
+        }
+        write_obj_element(&mut self.writer, obj, None)?;
+
+        Ok(())
+    }
+
+    /// Write a `<bounds>` element with this extent. Must be called before any objects are
+    /// written.
+    pub fn set_bounds(&mut self, bounds: BBox) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => {
+                self.bounds = Some(bounds);
+                Ok(())
+            }
+            State::Closed => Err(OSMWriteError::AlreadyClosed),
+            _ => Err(OSMWriteError::AlreadyStarted),
+        }
+    }
+}
+
+impl<W: Write> OSCWriter<CompressedWriter<W>> {
+    /// Write an OSC file to `writer`, compressed with `compression`.
+    pub fn new_compressed(writer: W, compression: Compression) -> Result<Self> {
+        Ok(Self::from_compressed(CompressedWriter::new(
+            writer,
+            compression,
+        )?))
+    }
+
+    /// Write an OSC file to this compressed writer. Closing this will finish the compression.
+    pub fn from_compressed(writer: CompressedWriter<W>) -> Self {
+        let mut osc_writer = OSCWriter::new(writer);
+        osc_writer.finish = CompressedWriter::finish;
+        osc_writer
+    }
 }
 
 impl<W: Write> OSMWriter<W> for OSCWriter<W> {
     fn new(writer: W) -> Self {
         OSCWriter {
             writer,
-            //headers: HashMap::new(),
+            headers: HashMap::new(),
+            bounds: None,
+            finish: W::flush,
+            section: None,
             _state: State::Initial,
         }
     }
 
+    fn set_header(&mut self, (key, value): (&str, &str)) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => {
+                self.headers.insert(key.into(), value.into());
+                Ok(())
+            }
+            State::Closed => Err(OSMWriteError::AlreadyClosed),
+            _ => Err(OSMWriteError::AlreadyStarted),
+        }
+    }
+
     fn is_open(&self) -> bool {
         self._state != State::Closed
     }
@@ -131,8 +315,11 @@ impl<W: Write> OSMWriter<W> for OSCWriter<W> {
         self.ensure_header()?;
 
         if self._state != State::Closed {
-            write!(self.writer, "\n</modify>")?;
+            if let Some(section) = self.section.take() {
+                write!(self.writer, "\n</{}>", section)?;
+            }
             write!(self.writer, "\n</osmChange>")?;
+            (self.finish)(&mut self.writer)?;
 
             self._state = State::Closed;
         }
@@ -140,96 +327,10 @@ impl<W: Write> OSMWriter<W> for OSCWriter<W> {
         Ok(())
     }
 
+    /// Objects are written in a `<modify>` section. Use
+    /// [`write_change`](OSCWriter::write_change) for other sections.
     fn write_obj(&mut self, obj: &impl OSMObj) -> Result<(), OSMWriteError> {
-        match self._state {
-            State::Initial => self.ensure_header()?, // This will update self._state
-            State::WritingObjects => {}
-            State::Closed => return Err(OSMWriteError::AlreadyClosed),
-        }
-
-        write!(
-            self.writer,
-            "{}",
-            match obj.object_type() {
-                OSMObjectType::Node => "\n\t<node",
-                OSMObjectType::Way => "\n\t<way",
-                OSMObjectType::Relation => "\n\t<relation",
-            }
-        )?;
-        write!(self.writer, " id=\"{}\"", obj.id())?;
-        write!(
-            self.writer,
-            " visible=\"{}\"",
-            if obj.deleted() { "false" } else { "true" }
-        )?;
-        write!(self.writer, " version=\"{}\"", obj.version().unwrap())?;
-        if let Some(user) = obj.user() {
-            write!(self.writer, " user=\"")?;
-            write_xml_escaped(&mut self.writer, user)?;
-            write!(self.writer, "\"")?;
-        }
-        if let Some(uid) = obj.uid() {
-            write!(self.writer, " uid=\"{}\"", uid)?;
-        }
-        if let Some(changeset_id) = obj.changeset_id() {
-            write!(self.writer, " changeset=\"{}\"", changeset_id)?;
-        }
-        if let Some(timestamp) = obj.timestamp() {
-            write!(self.writer, " timestamp=\"{}\"", timestamp)?;
-        }
-
-        if let Some(node) = obj.as_node() {
-            if let Some((lat, lon)) = node.lat_lon() {
-                write!(self.writer, " lat=\"{}\"", lat)?;
-                write!(self.writer, " lon=\"{}\"", lon)?;
-            }
-        }
-
-        if obj.is_node() && obj.untagged() {
-            write!(self.writer, " />")?;
-            return Ok(());
-        }
-        write!(self.writer, ">")?;
-
-        if let Some(way) = obj.as_way() {
-            for nid in way.nodes() {
-                write!(self.writer, "\n\t\t<nd ref=\"{}\" />", nid)?;
-            }
-        }
-
-        if let Some(relation) = obj.as_relation() {
-            for member in relation.members() {
-                write!(
-                    self.writer,
-                    "\n\t\t<member type=\"{}\" ref=\"{}\" role=\"",
-                    member.0, member.1
-                )?;
-                if !member.2.is_empty() {
-                    write_xml_escaped(&mut self.writer, member.2)?;
-                }
-                write!(self.writer, "\"/>")?;
-            }
-        }
-
-        for (k, v) in obj.tags() {
-            write!(self.writer, "\n\t\t<tag k=\"")?;
-            write_xml_escaped(&mut self.writer, k)?;
-            write!(self.writer, "\" v=\"")?;
-            write_xml_escaped(&mut self.writer, v)?;
-            write!(self.writer, "\" />")?;
-        }
-
-        write!(
-            self.writer,
-            "{}",
-            match obj.object_type() {
-                OSMObjectType::Node => "\n\t</node>",
-                OSMObjectType::Way => "\n\t</way>",
-                OSMObjectType::Relation => "\n\t</relation>",
-            }
-        )?;
-
-        Ok(())
+        self.write_change(OSCAction::Modify, obj)
     }
 
     fn into_inner(self) -> W {
@@ -0,0 +1,298 @@
+//! Osmosis [`.poly` files](https://wiki.openstreetmap.org/wiki/Osmosis/Polygon_Filter_File_Format)
+//!
+//! A `.poly` file has a name, then sections, each a ring of `lon lat` lines ending with `END`.
+//! Sections whose name starts with `!` are holes. A location is in the poly if it's in any
+//! outer section, and not in any hole.
+//!
+//! ```
+//! use osmio::poly::Poly;
+//! use osmio::{Lat, Lon};
+//! let poly: Poly = "square\n1\n 0 0\n 2 0\n 2 2\n 0 2\n 0 0\nEND\nEND\n".parse()?;
+//! assert!(poly.contains(Lat::from_inner(10_000_000), Lon::from_inner(10_000_000)));
+//! assert!(!poly.contains(Lat::from_inner(30_000_000), Lon::from_inner(10_000_000)));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::multipolygon::{MultiPolygon, Polygon, Ring, ring_contains};
+use crate::{Lat, Lon};
+
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// One ring in a poly file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolySection {
+    /// Without any leading `!`
+    pub name: String,
+    pub hole: bool,
+    /// First & last locations are the same
+    pub ring: Ring,
+}
+
+/// A `.poly` file
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Poly {
+    pub name: String,
+    pub sections: Vec<PolySection>,
+}
+
+impl Poly {
+    /// Read a `.poly` file
+    pub fn from_filename(filename: impl AsRef<Path>) -> Result<Self> {
+        let filename = filename.as_ref();
+        Self::read(BufReader::new(File::open(filename)?))
+            .with_context(|| format!("Reading {}", filename.display()))
+    }
+
+    /// Parse a `.poly` file from this reader
+    pub fn read(reader: impl Read) -> Result<Self> {
+        let mut lines = BufReader::new(reader)
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line));
+        let mut next_line = || -> Result<Option<(usize, String)>> {
+            for (lineno, line) in lines.by_ref() {
+                let line = line?;
+                let line = line.trim();
+                if !line.is_empty() {
+                    return Ok(Some((lineno, line.to_string())));
+                }
+            }
+            Ok(None)
+        };
+
+        let Some((_, name)) = next_line()? else {
+            bail!("Empty poly file");
+        };
+        let mut poly = Poly {
+            name,
+            sections: Vec::new(),
+        };
+
+        loop {
+            let Some((lineno, section_name)) = next_line()? else {
+                bail!("Unexpected end of file, expected END");
+            };
+            if section_name == "END" {
+                break;
+            }
+            let (hole, section_name) = match section_name.strip_prefix('!') {
+                Some(name) => (true, name.to_string()),
+                None => (false, section_name),
+            };
+
+            let mut ring = Ring::new();
+            loop {
+                let Some((lineno, line)) = next_line()? else {
+                    bail!("Unexpected end of file in section on line {}", lineno);
+                };
+                if line == "END" {
+                    break;
+                }
+                let mut parts = line.split_whitespace();
+                let (Some(lon), Some(lat), None) = (parts.next(), parts.next(), parts.next())
+                else {
+                    bail!("Line {}: expected 'lon lat', got {:?}", lineno, line);
+                };
+                let lon = Lon::from_str(lon)
+                    .map_err(|e| anyhow::anyhow!("Line {}: invalid longitude: {}", lineno, e))?;
+                let lat = Lat::from_str(lat)
+                    .map_err(|e| anyhow::anyhow!("Line {}: invalid latitude: {}", lineno, e))?;
+                ring.push((lat, lon));
+            }
+            if ring.first() != ring.last() {
+                ring.push(ring[0]);
+            }
+            poly.sections.push(PolySection {
+                name: section_name,
+                hole,
+                ring,
+            });
+        }
+
+        Ok(poly)
+    }
+
+    /// Write this in `.poly` format
+    pub fn write(&self, mut writer: impl Write) -> Result<()> {
+        write!(writer, "{}", self)?;
+        Ok(())
+    }
+
+    /// Is this location in an outer section, and not in a hole?
+    pub fn contains(&self, lat: Lat, lon: Lon) -> bool {
+        let in_sections = |hole: bool| {
+            self.sections
+                .iter()
+                .filter(|s| s.hole == hole)
+                .any(|s| ring_contains(&s.ring, lat, lon))
+        };
+        in_sections(false) && !in_sections(true)
+    }
+
+    /// Convert to polygons, by putting each hole in the outer section which contains it.
+    ///
+    /// Holes which aren't in any outer are dropped.
+    pub fn to_polygons(&self) -> Vec<Polygon> {
+        let mut polygons: Vec<Polygon> = self
+            .sections
+            .iter()
+            .filter(|s| !s.hole)
+            .map(|s| Polygon {
+                outer: s.ring.clone(),
+                inners: Vec::new(),
+            })
+            .collect();
+        for hole in self.sections.iter().filter(|s| s.hole) {
+            let Some(&(lat, lon)) = hole.ring.first() else {
+                continue;
+            };
+            if let Some(polygon) = polygons.iter_mut().find(|p| p.contains(lat, lon)) {
+                polygon.inners.push(hole.ring.clone());
+            }
+        }
+        polygons
+    }
+}
+
+impl FromStr for Poly {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Poly::read(s.as_bytes())
+    }
+}
+
+impl fmt::Display for Poly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.name)?;
+        for section in self.sections.iter() {
+            writeln!(f, "{}{}", if section.hole { "!" } else { "" }, section.name)?;
+            for (lat, lon) in section.ring.iter() {
+                writeln!(f, "   {:.7}   {:.7}", lon.degrees(), lat.degrees())?;
+            }
+            writeln!(f, "END")?;
+        }
+        writeln!(f, "END")
+    }
+}
+
+/// Each outer & inner ring becomes a section. Sections are numbered from 1.
+impl From<&MultiPolygon> for Poly {
+    fn from(multipolygon: &MultiPolygon) -> Self {
+        let mut sections = Vec::new();
+        for polygon in multipolygon.polygons.iter() {
+            sections.push(PolySection {
+                name: (sections.len() + 1).to_string(),
+                hole: false,
+                ring: polygon.outer.clone(),
+            });
+            for inner in polygon.inners.iter() {
+                sections.push(PolySection {
+                    name: (sections.len() + 1).to_string(),
+                    hole: true,
+                    ring: inner.clone(),
+                });
+            }
+        }
+        Poly {
+            name: format!("relation_{}", multipolygon.relation_id),
+            sections,
+        }
+    }
+}
+
+impl From<&Poly> for crate::extract::Region {
+    fn from(poly: &Poly) -> Self {
+        poly.to_polygons().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "australia_v
+first_area
+     0.1446693E+03    -0.3826255E+02
+     0.1446627E+03    -0.3825661E+02
+     0.1446763E+03    -0.3824465E+02
+     0.1446693E+03    -0.3826255E+02
+END
+second_area
+     0.1446693E+03    -0.3826255E+02
+     0.1446627E+03    -0.3825661E+02
+     0.1446763E+03    -0.3824465E+02
+END
+!hole
+     0.1446700E+03    -0.3825800E+02
+     0.1446690E+03    -0.3825700E+02
+     0.1446710E+03    -0.3825700E+02
+END
+END
+";
+
+    #[test]
+    fn parse_and_write() {
+        let poly: Poly = INPUT.parse().unwrap();
+        assert_eq!(poly.name, "australia_v");
+        assert_eq!(poly.sections.len(), 3);
+        assert_eq!(poly.sections[0].name, "first_area");
+        assert_eq!(poly.sections[0].ring[0], (Lat(-382625500), Lon(1446693000)));
+        // closed
+        assert_eq!(poly.sections[1].ring.len(), 4);
+        assert!(poly.sections[2].hole);
+        assert_eq!(poly.sections[2].name, "hole");
+
+        let output = poly.to_string();
+        assert!(output.starts_with("australia_v\nfirst_area\n   144.6693000   -38.2625500\n"));
+        assert!(output.contains("\n!hole\n"));
+        assert!(output.ends_with("END\nEND\n"));
+        assert_eq!(output.parse::<Poly>().unwrap(), poly);
+
+        assert!("name\n1\n 0 0\n".parse::<Poly>().is_err());
+        assert!("name\n1\n 0 x\nEND\nEND\n".parse::<Poly>().is_err());
+    }
+
+    #[test]
+    fn contains() {
+        let poly: Poly = INPUT.parse().unwrap();
+        // inside the triangle
+        assert!(poly.contains(Lat(-382560000), Lon(1446700000)));
+        // in the hole
+        assert!(!poly.contains(Lat(-382573000), Lon(1446700000)));
+        assert!(!poly.contains(Lat(-382560000), Lon(1446000000)));
+
+        let polygons = poly.to_polygons();
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].inners.len(), 1);
+    }
+
+    #[test]
+    fn from_multipolygon() {
+        let square = |min: i32, max: i32| {
+            vec![
+                (Lat(min), Lon(min)),
+                (Lat(min), Lon(max)),
+                (Lat(max), Lon(max)),
+                (Lat(max), Lon(min)),
+                (Lat(min), Lon(min)),
+            ]
+        };
+        let mp = MultiPolygon {
+            relation_id: 42,
+            polygons: vec![Polygon {
+                outer: square(0, 100),
+                inners: vec![square(10, 20)],
+            }],
+        };
+        let poly = Poly::from(&mp);
+        assert_eq!(poly.name, "relation_42");
+        assert_eq!(poly.sections[1].name, "2");
+        assert!(poly.sections[1].hole);
+        assert_eq!(poly.to_polygons(), mp.polygons);
+    }
+}
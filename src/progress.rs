@@ -0,0 +1,210 @@
+//! How far through their input readers are, in bytes
+//!
+//! All the readers implement [`ReadProgress`] when the source they read from does. Files do (the
+//! total is the file size), as do the decompressors & `BufReader`s around them. Progress is of
+//! the underlying source, so for compressed files it's the compressed bytes, and the fraction
+//! done is accurate. Wrap other sources (e.g. stdin) in a [`CountingReader`].
+//!
+//! ```no_run
+//! use iter_progress::ProgressableIter;
+//! use osmio::progress::ReadProgress;
+//! use osmio::OSMReader;
+//!
+//! let mut reader = osmio::xml::from_filename("planet.osm.bz2")?;
+//! let mut objects = reader.objects().progress();
+//! while let Some((mut state, _obj)) = objects.next() {
+//!     if let Some(fraction) = objects.inner().inner().fraction_done() {
+//!         state.assume_fraction(fraction);
+//!     }
+//!     state.do_every_n_sec(2., |state| {
+//!         println!("{:.1}% done, eta {:?}", state.percent().unwrap(), state.eta());
+//!     });
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::compression::CompressedReader;
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+
+/// Reports how many bytes have been read from the underlying source
+pub trait ReadProgress {
+    /// Bytes read from the underlying source so far. Includes any read ahead into buffers.
+    fn bytes_read(&self) -> u64;
+
+    /// The size of the underlying source, if known, e.g. when it's a file
+    fn total_bytes(&self) -> Option<u64> {
+        None
+    }
+
+    /// How far through the source (from 0 to 1), if the total is known
+    fn fraction_done(&self) -> Option<f64> {
+        self.total_bytes()
+            .filter(|&total| total > 0)
+            .map(|total| self.bytes_read() as f64 / total as f64)
+    }
+}
+
+/// The file's current position
+impl ReadProgress for File {
+    fn bytes_read(&self) -> u64 {
+        let mut file: &File = self;
+        file.stream_position().unwrap_or(0)
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.metadata().ok().map(|m| m.len())
+    }
+}
+
+/// Bytes in the buffer aren't counted as read
+impl<R: ReadProgress> ReadProgress for BufReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.get_ref()
+            .bytes_read()
+            .saturating_sub(self.buffer().len() as u64)
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.get_ref().total_bytes()
+    }
+}
+
+/// Progress through the compressed source
+macro_rules! delegate_read_progress {
+    ($type:ident) => {
+        impl<R: Read + ReadProgress> ReadProgress for $type<R> {
+            fn bytes_read(&self) -> u64 {
+                self.get_ref().bytes_read()
+            }
+
+            fn total_bytes(&self) -> Option<u64> {
+                self.get_ref().total_bytes()
+            }
+        }
+    };
+}
+
+delegate_read_progress!(MultiBzDecoder);
+delegate_read_progress!(MultiGzDecoder);
+
+/// The zstd decoder's read ahead buffer isn't counted
+impl<R: Read + ReadProgress> ReadProgress for CompressedReader<R> {
+    fn bytes_read(&self) -> u64 {
+        match self {
+            CompressedReader::Plain(r) => r.bytes_read(),
+            CompressedReader::Gzip(r) => r.bytes_read(),
+            CompressedReader::Bzip2(r) => r.bytes_read(),
+            CompressedReader::Zstd(r) => r.get_ref().bytes_read(),
+        }
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.get_ref().total_bytes()
+    }
+}
+
+/// A `Read` which counts the bytes read through it, for sources which can't report it
+/// themselves
+#[derive(Debug)]
+pub struct CountingReader<R: Read> {
+    inner: R,
+    bytes_read: u64,
+    total_bytes: Option<u64>,
+}
+
+impl<R: Read> CountingReader<R> {
+    pub fn new(inner: R) -> Self {
+        CountingReader {
+            inner,
+            bytes_read: 0,
+            total_bytes: None,
+        }
+    }
+
+    /// When the total size is known some other way, e.g. from a `Content-Length` header
+    pub fn with_total_bytes(inner: R, total_bytes: u64) -> Self {
+        CountingReader {
+            total_bytes: Some(total_bytes),
+            ..CountingReader::new(inner)
+        }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let num_read = self.inner.read(buf)?;
+        self.bytes_read += num_read as u64;
+        Ok(num_read)
+    }
+}
+
+impl<R: Read> ReadProgress for CountingReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.total_bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OSMReader;
+    use crate::compression::{CompressedWriter, Compression};
+    use crate::xml::XMLReader;
+    use std::io::Write;
+
+    const XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="1" lon="1"/>
+  <node id="2" lat="2" lon="2"/>
+</osm>"#;
+
+    #[test]
+    fn counting() {
+        let mut reader = CountingReader::with_total_bytes(XML.as_bytes(), XML.len() as u64);
+        let mut buf = [0; 10];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(reader.bytes_read(), 10);
+        assert_eq!(reader.fraction_done(), Some(10. / XML.len() as f64));
+
+        let mut reader = XMLReader::new(CountingReader::with_total_bytes(
+            XML.as_bytes(),
+            XML.len() as u64,
+        ));
+        assert_eq!(reader.bytes_read(), 0);
+        assert_eq!(reader.objects().count(), 2);
+        assert_eq!(reader.fraction_done(), Some(1.));
+        assert_eq!(CountingReader::new(XML.as_bytes()).fraction_done(), None);
+    }
+
+    #[test]
+    fn compressed_file() {
+        let dir = std::env::temp_dir().join(format!("osmio-progress-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.osm.gz");
+        let mut writer =
+            CompressedWriter::new(File::create(&path).unwrap(), Compression::Gzip).unwrap();
+        writer.write_all(XML.as_bytes()).unwrap();
+        writer.finish().unwrap();
+
+        let compressed_size = std::fs::metadata(&path).unwrap().len();
+        let mut reader = crate::xml::from_filename(&path).unwrap();
+        assert_eq!(reader.total_bytes(), Some(compressed_size));
+        assert_eq!(reader.objects().count(), 2);
+        assert_eq!(reader.bytes_read(), compressed_size);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+//! Sort objects which don't fit in memory
+//!
+//! OSM files should be sorted by type (nodes, then ways, then relations), then id, then version.
+//! Files from JOSM, Overpass, or merging files often aren't. [`ExternalSort`] reads objects
+//! into memory until the memory budget is reached, then writes that sorted run to a temporary
+//! file. At the end the runs are merged.
+//!
+//! ```no_run
+//! use osmio::sort::ExternalSort;
+//! use osmio::OSMReader;
+//! let mut reader = osmio::xml::from_filename("unsorted.osm")?;
+//! let mut writer = osmio::xml::to_filename("sorted.osm.gz")?;
+//! ExternalSort::new()
+//!     .memory_budget(512 * 1024 * 1024)
+//!     .sort(reader.objects(), &mut writer)?;
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::obj_types::StringOSMObj;
+use crate::xml::{XMLReader, XMLWriter};
+use crate::{OSMObjBaseRead, OSMObjectType, OSMReader, OSMWriter, ObjId, RelationRead, WayRead};
+
+use anyhow::Result;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The order objects are sorted in
+pub type SortKey = (OSMObjectType, ObjId, Option<u32>);
+
+/// The sort key of this object: type, id, version
+pub fn sort_key(obj: &impl OSMObjBaseRead) -> SortKey {
+    (obj.object_type(), obj.id(), obj.version())
+}
+
+/// Objects are not in sorted order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsortedError {
+    pub previous: SortKey,
+    pub current: SortKey,
+}
+
+impl fmt::Display for UnsortedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let describe = |(object_type, id, version): SortKey| match version {
+            Some(version) => format!("{} {} v{}", object_type, id, version),
+            None => format!("{} {}", object_type, id),
+        };
+        write!(
+            f,
+            "objects are not sorted: {} is after {}",
+            describe(self.current),
+            describe(self.previous)
+        )
+    }
+}
+
+impl std::error::Error for UnsortedError {}
+
+/// Checks that objects are in sorted order (type, id, version), one at a time.
+#[derive(Debug, Clone, Default)]
+pub struct SortChecker {
+    last: Option<SortKey>,
+}
+
+impl SortChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Is this object after (or the same as) the last object?
+    pub fn check(&mut self, obj: &impl OSMObjBaseRead) -> Result<(), UnsortedError> {
+        let key = sort_key(obj);
+        if let Some(previous) = self.last
+            && key < previous
+        {
+            return Err(UnsortedError {
+                previous,
+                current: key,
+            });
+        }
+        self.last = Some(key);
+        Ok(())
+    }
+}
+
+/// The check readers do as they read, when they assume the objects are sorted. Nothing is read
+/// after the first object which is out of order.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AssumedSortedCheck {
+    checker: SortChecker,
+    error: Option<UnsortedError>,
+}
+
+impl AssumedSortedCheck {
+    /// The first object which was out of order
+    pub(crate) fn error(&self) -> Option<&UnsortedError> {
+        self.error.as_ref()
+    }
+
+    /// Should the reader stop, because an earlier object was out of order?
+    pub(crate) fn stopped(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Is this object in order? If not, the error is kept.
+    pub(crate) fn check(&mut self, obj: &impl OSMObjBaseRead) -> bool {
+        match self.checker.check(obj) {
+            Ok(()) => true,
+            Err(err) => {
+                self.error = Some(err);
+                false
+            }
+        }
+    }
+}
+
+/// Approximately how much memory this object uses
+fn approx_size(obj: &StringOSMObj) -> usize {
+    let tags: usize = obj.tags().map(|(k, v)| k.len() + v.len() + 48).sum();
+    let rest = match obj {
+        StringOSMObj::Node(_) => 0,
+        StringOSMObj::Way(way) => way.nodes().len() * 8,
+        StringOSMObj::Relation(relation) => {
+            relation.members().map(|(_, _, role)| role.len() + 40).sum()
+        }
+    };
+    std::mem::size_of::<StringOSMObj>() + obj.user().map_or(0, |u| u.len()) + tags + rest
+}
+
+/// Number of temp files made by this process
+static NUM_TEMP_FILES: AtomicUsize = AtomicUsize::new(0);
+
+/// A sorted run in a temporary file, which is deleted when dropped
+struct Run {
+    path: PathBuf,
+}
+
+impl Run {
+    fn write(temp_dir: &Path, objs: &[StringOSMObj]) -> Result<Self> {
+        let path = temp_dir.join(format!(
+            "osmio-sort-{}-{}.osm",
+            std::process::id(),
+            NUM_TEMP_FILES.fetch_add(1, Ordering::Relaxed)
+        ));
+        // Create this first so the file is removed if writing fails
+        let run = Run { path };
+        let mut writer = XMLWriter::new(BufWriter::new(File::create(&run.path)?));
+        for obj in objs {
+            writer.write_obj(obj)?;
+        }
+        writer.close()?;
+        Ok(run)
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Sorts objects with a limited amount of memory, using temporary files.
+#[derive(Debug, Clone)]
+pub struct ExternalSort {
+    memory_budget: usize,
+    temp_dir: PathBuf,
+}
+
+impl Default for ExternalSort {
+    fn default() -> Self {
+        ExternalSort {
+            memory_budget: 1024 * 1024 * 1024,
+            temp_dir: std::env::temp_dir(),
+        }
+    }
+}
+
+impl ExternalSort {
+    /// 1 GiB memory budget, in the system temporary directory
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Roughly how many bytes of objects to keep in memory, before writing a run to disk.
+    pub fn memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = bytes;
+        self
+    }
+
+    /// Where to store the sorted runs.
+    pub fn temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+
+    /// Sort these objects, and write them to `writer`, in type, id, version order.
+    ///
+    /// Objects with the same type, id & version stay in the order they were read. Returns the
+    /// number of objects written. The writer is not closed.
+    pub fn sort<W: Write>(
+        &self,
+        objects: impl IntoIterator<Item = StringOSMObj>,
+        writer: &mut impl OSMWriter<W>,
+    ) -> Result<usize> {
+        let mut runs = Vec::new();
+        let mut buffer = Vec::new();
+        let mut buffer_size = 0;
+        for obj in objects {
+            buffer_size += approx_size(&obj);
+            buffer.push(obj);
+            if buffer_size >= self.memory_budget {
+                buffer.sort_by_key(sort_key);
+                runs.push(Run::write(&self.temp_dir, &buffer)?);
+                buffer.clear();
+                buffer_size = 0;
+            }
+        }
+        buffer.sort_by_key(sort_key);
+
+        if runs.is_empty() {
+            // Everything fit in memory
+            for obj in buffer.iter() {
+                writer.write_obj(obj)?;
+            }
+            return Ok(buffer.len());
+        }
+        if !buffer.is_empty() {
+            runs.push(Run::write(&self.temp_dir, &buffer)?);
+        }
+        drop(buffer);
+
+        merge_runs(&runs, writer)
+    }
+}
+
+/// k-way merge of the sorted runs
+fn merge_runs<W: Write>(runs: &[Run], writer: &mut impl OSMWriter<W>) -> Result<usize> {
+    let mut readers = runs
+        .iter()
+        .map(|run| Ok(XMLReader::new(BufReader::new(File::open(&run.path)?))))
+        .collect::<Result<Vec<_>>>()?;
+    let mut heads: Vec<Option<StringOSMObj>> = Vec::with_capacity(readers.len());
+    // The run index is included so equal keys keep the input order
+    let mut heap = BinaryHeap::with_capacity(readers.len());
+    for (idx, reader) in readers.iter_mut().enumerate() {
+        let head = reader.next_obj()?;
+        if let Some(obj) = &head {
+            heap.push(Reverse((sort_key(obj), idx)));
+        }
+        heads.push(head);
+    }
+
+    let mut num_written = 0;
+    while let Some(Reverse((_, idx))) = heap.pop() {
+        let obj = heads[idx].take().expect("run in heap has a head");
+        writer.write_obj(&obj)?;
+        num_written += 1;
+        if let Some(next) = readers[idx].next_obj()? {
+            heap.push(Reverse((sort_key(&next), idx)));
+            heads[idx] = Some(next);
+        }
+    }
+    Ok(num_written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::{StringNodeBuilder, StringRelationBuilder, StringWayBuilder};
+    use crate::{Lat, Lon, OSMObj};
+
+    fn objects() -> Vec<StringOSMObj> {
+        let mut objs: Vec<StringOSMObj> = Vec::new();
+        for i in 0..50 {
+            // A simple shuffle
+            let id = (i * 37) % 50 + 1;
+            objs.push(
+                StringRelationBuilder::default()
+                    ._id(id)
+                    ._version(1)
+                    ._members(vec![(OSMObjectType::Node, id, "stop".into())])
+                    .build()
+                    .unwrap()
+                    .into(),
+            );
+            objs.push(
+                StringNodeBuilder::default()
+                    ._id(id)
+                    ._version((i % 3 + 1) as u32)
+                    ._lat_lon((Lat(id as i32), Lon(id as i32)))
+                    ._tags(smallvec::smallvec![(
+                        "name".into(),
+                        format!("n{}", i).into()
+                    )])
+                    .build()
+                    .unwrap()
+                    .into(),
+            );
+            objs.push(
+                StringWayBuilder::default()
+                    ._id(-id)
+                    ._nodes(smallvec::smallvec![id, id + 1])
+                    .build()
+                    .unwrap()
+                    .into(),
+            );
+        }
+        objs
+    }
+
+    fn sort(budget: usize) -> Vec<StringOSMObj> {
+        let mut output = Vec::new();
+        let mut writer = XMLWriter::new(&mut output);
+        let num = ExternalSort::new()
+            .memory_budget(budget)
+            .sort(objects(), &mut writer)
+            .unwrap();
+        writer.close().unwrap();
+        drop(writer);
+        assert_eq!(num, 150);
+        XMLReader::new(output.as_slice()).objects().collect()
+    }
+
+    #[test]
+    fn checker() {
+        let mut checker = SortChecker::new();
+        let objs = objects();
+        // node 1, node 38, way -1
+        for obj in [&objs[1], &objs[4], &objs[2]] {
+            assert!(checker.check(obj).is_ok());
+        }
+        let err = checker.check(&objs[1]).unwrap_err();
+        assert_eq!(err.previous, (OSMObjectType::Way, -1, None));
+        assert_eq!(err.current, (OSMObjectType::Node, 1, Some(1)));
+        assert_eq!(
+            err.to_string(),
+            "objects are not sorted: node 1 v1 is after way -1"
+        );
+        // relation 1
+        assert!(checker.check(&objs[0]).is_ok());
+    }
+
+    #[test]
+    fn in_memory_and_external() {
+        let mut expected = objects();
+        expected.sort_by_key(sort_key);
+
+        let in_memory = sort(usize::MAX);
+        assert_eq!(in_memory, expected);
+        assert!(in_memory[0].is_node());
+        assert_eq!(in_memory[50].id(), -50);
+        assert!(in_memory[149].is_relation());
+
+        // A run for every few objects
+        let external = sort(1000);
+        assert_eq!(external, expected);
+    }
+}
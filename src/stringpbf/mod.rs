@@ -1,32 +1,41 @@
 //! PBF/Protobuf file format and return StringOSMObj's
 //!
-//! Reading PBF files. Writing/creating PBF files is not currently supported or implemented
+//! Reading & writing PBF files. Node locations on ways (`LocationsOnWays`) are supported.
 use super::OSMReader;
 use super::ObjId;
-use super::TimestampFormat;
+use super::Timestamp;
 use byteorder;
 use byteorder::ReadBytesExt;
 use protobuf::Message;
-use smallvec::SmallVec;
-use smol_str::SmolStr;
 use std::collections::VecDeque;
-use std::io::{Cursor, Read};
+use std::io::{Cursor, Read, Seek, SeekFrom};
 use std::iter::Iterator;
 
 use super::*;
 use crate::COORD_PRECISION_NANOS;
+use crate::idset::IdSet;
+use crate::progress::ReadProgress;
+use crate::sort::{AssumedSortedCheck, UnsortedError};
 
 use flate2::read::ZlibDecoder;
 
-use obj_types::{StringNode, StringOSMObj, StringRelation, StringWay};
-
 use protobuf;
 mod fileformat;
 mod node_id_pos;
+mod objects;
 mod osmformat;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod view;
+mod writer;
 pub use self::node_id_pos::PBFNodePositionReader;
+pub use self::objects::*;
+pub use self::view::{NodeRef, PrimitiveBlockView, RelationRef, WayRef};
+pub use self::writer::PBFWriter;
 
 type ObjectFilter = (bool, bool, bool);
+/// Only return objects with these ids. One for each of nodes, ways, relations. `None` means all.
+type IdFilter = [Option<IdSet>; 3];
 
 struct FileReader<R: Read> {
     reader: R,
@@ -87,26 +96,46 @@ impl<R: Read> FileReader<R> {
     }
 }
 
-fn decode_nodes(
+/// Block wide settings needed to decode the objects
+struct BlockInfo {
+    /// Nanodegrees per unit
+    granularity: i32,
+    lat_offset: i64,
+    lon_offset: i64,
+    /// Seconds per unit
+    date_granularity: i64,
+}
+
+impl BlockInfo {
+    /// The location of a dense node, from its (delta decoded) raw lat & lon
+    fn dense_lat_lon(&self, raw_lat: i64, raw_lon: i64) -> (Lat, Lon) {
+        // granularity is in nanodegrees
+        let scale_factor = (self.granularity / COORD_PRECISION_NANOS) as i64;
+        // Offsets from pbf are in nanodegrees
+        let lat = raw_lat * scale_factor + self.lat_offset / COORD_PRECISION_NANOS as i64;
+        let lon = raw_lon * scale_factor + self.lon_offset / COORD_PRECISION_NANOS as i64;
+        (Lat(lat as i32), Lon(lon as i32))
+    }
+}
+
+fn decode_nodes<B: PBFObjects>(
     _primitive_group: &osmformat::PrimitiveGroup,
-    _granularity: i32,
-    _lat_offset: i64,
-    _lon_offset: i64,
-    _date_granularity: i32,
-    _stringtable: &[SmolStr],
-    _sink: &mut VecDeque<StringOSMObj>,
+    _block: &BlockInfo,
+    _stringtable: &[B::Str],
+    _wanted_ids: Option<&IdSet>,
+    _objects: &mut B,
+    _sink: &mut VecDeque<B::Obj>,
 ) -> usize {
     unimplemented!("Dense node");
 }
 
-fn decode_dense_nodes(
+fn decode_dense_nodes<B: PBFObjects>(
     primitive_group: &osmformat::PrimitiveGroup,
-    granularity: i32,
-    lat_offset: i64,
-    lon_offset: i64,
-    date_granularity: i32,
-    stringtable: &[SmolStr],
-    results: &mut VecDeque<StringOSMObj>,
+    block: &BlockInfo,
+    stringtable: &[B::Str],
+    wanted_ids: Option<&IdSet>,
+    objects: &mut B,
+    results: &mut VecDeque<B::Obj>,
 ) -> usize {
     let mut num_objects_written = 0;
     let dense = &primitive_group.dense;
@@ -114,6 +143,8 @@ fn decode_dense_nodes(
     let lats = &dense.lat;
     let lons = &dense.lon;
     let denseinfo = &dense.denseinfo;
+    // Files can be written without any metadata
+    let has_info = denseinfo.version.len() == ids.len();
 
     let uids = &denseinfo.uid;
     let changesets = &denseinfo.changeset;
@@ -133,7 +164,7 @@ fn decode_dense_nodes(
     let mut last_id: i64 = 0;
     let mut last_raw_lat: i32 = 0;
     let mut last_raw_lon: i32 = 0;
-    let mut last_timestamp = 0;
+    let mut last_timestamp: i64 = 0;
     let mut last_changset = 0;
     let mut last_uid = 0;
     let mut last_user_sid = 0;
@@ -142,6 +173,8 @@ fn decode_dense_nodes(
         // last_* start off 0
         let id = ids[index] + last_id;
         last_id = id;
+        // The other fields are delta coded, so they need to be read even for unwanted nodes
+        let wanted = wanted_ids.is_none_or(|w| w.contains(id as ObjId));
 
         let raw_lat = i32::try_from(lats[index] + last_raw_lat as i64)
             .expect("raw_lat was larger than the OSM precision allows");
@@ -151,174 +184,182 @@ fn decode_dense_nodes(
             .expect("raw_lon was larger than OSM precision allows");
         last_raw_lon = raw_lon;
 
-        // granularity is in nanodegrees
-        let scale_factor = granularity / COORD_PRECISION_NANOS;
-        let mut internal_lat = raw_lat * scale_factor;
-        let mut internal_lon = raw_lon * scale_factor;
-
-        // Offsets from pbf are in nanodegrees
-        let internal_lat_offset = lat_offset / COORD_PRECISION_NANOS as i64;
-        let internal_lon_offset = lon_offset / COORD_PRECISION_NANOS as i64;
-        internal_lat += internal_lat_offset as i32;
-        internal_lon += internal_lon_offset as i32;
+        let lat_lon = block.dense_lat_lon(raw_lat as i64, raw_lon as i64);
 
-        let mut tags: SmallVec<[(SmolStr, SmolStr); 1]> = SmallVec::new();
-        loop {
-            //assert!(keys_vals_index <= keys_vals.len());
-            let next = keys_vals[keys_vals_index];
-            keys_vals_index += 1;
-            if next == 0 {
-                break;
-            } else {
-                let key = next;
-                let val = keys_vals[keys_vals_index];
-                keys_vals_index += 1;
-                tags.push((
-                    stringtable[key as usize].clone(),
-                    stringtable[val as usize].clone(),
-                ));
+        // This node's tags are (key, value) string ids, ending with a 0. keys_vals is empty if
+        // no node in the block has tags.
+        let tags_start = keys_vals_index;
+        while keys_vals.get(keys_vals_index).is_some_and(|&k| k != 0) {
+            keys_vals_index += 2;
+        }
+        let tags = keys_vals
+            .get(tags_start..keys_vals_index)
+            .unwrap_or_default();
+        keys_vals_index += 1;
+
+        let meta = if has_info {
+            let changeset_id = changesets[index] + last_changset;
+            last_changset = changeset_id;
+            let uid_id = uids[index] + last_uid;
+            last_uid = uid_id;
+            let user_sid = user_sids[index] + last_user_sid;
+            last_user_sid = user_sid;
+            // Delta coded in units of date_granularity. i64 so it works past 2038
+            last_timestamp += timestamps[index];
+            assert!(uid_id < i32::MAX);
+            PBFObjMeta {
+                id: id as ObjId,
+                version: Some(denseinfo.version[index] as u32),
+                deleted: !denseinfo.visible.get(index).unwrap_or(&true),
+                changeset_id: Some(changeset_id as u32),
+                timestamp: Some(Timestamp::from_epoch_secs(
+                    last_timestamp * block.date_granularity,
+                )),
+                uid: Some(uid_id as u32),
+                user: Some(stringtable[user_sid as usize].clone()),
+            }
+        } else {
+            PBFObjMeta {
+                id: id as ObjId,
+                version: None,
+                deleted: false,
+                changeset_id: None,
+                timestamp: None,
+                uid: None,
+                user: None,
             }
-            // FIXME infinite loop detection maybe?
+        };
+        if !wanted {
+            continue;
         }
 
-        let changeset_id = changesets[index] + last_changset;
-        last_changset = changeset_id;
-        let uid_id = uids[index] + last_uid;
-        last_uid = uid_id;
-        let user_sid = user_sids[index] + last_user_sid;
-        last_user_sid = user_sid;
-        let timestamp = timestamps[index] as i32 + last_timestamp;
-        let timestamp = timestamp * date_granularity;
-        last_timestamp = timestamp;
-        let timestamp = TimestampFormat::EpochNumber(timestamp as i64);
-        assert!(uid_id < i32::MAX);
-
-        results.push_back(StringOSMObj::Node(StringNode {
-            _id: id as ObjId,
-            _tags: tags,
-            _lat_lon: Some((Lat(internal_lat), Lon(internal_lon))),
-            _deleted: !denseinfo.visible.get(index).unwrap_or(&true),
-            _changeset_id: Some(changeset_id as u32),
-            _uid: Some(uid_id as u32),
-            _user: Some(stringtable[user_sid as usize].clone()),
-            _version: Some(denseinfo.version[index] as u32),
-            _timestamp: Some(timestamp),
-        }));
+        let tags = tags.chunks_exact(2).map(|kv| {
+            (
+                stringtable[kv[0] as usize].clone(),
+                stringtable[kv[1] as usize].clone(),
+            )
+        });
+        results.push_back(objects.node(meta, tags, lat_lon));
         num_objects_written += 1
     }
 
     num_objects_written
 }
 
-fn decode_ways(
+/// The metadata of a (non-dense) object
+fn info_meta<S: Clone>(
+    id: ObjId,
+    info: &osmformat::Info,
+    block: &BlockInfo,
+    stringtable: &[S],
+) -> PBFObjMeta<S> {
+    PBFObjMeta {
+        id,
+        version: info.version.map(|v| v as u32),
+        deleted: !info.visible.unwrap_or(true),
+        changeset_id: info.changeset.map(|c| c as u32),
+        timestamp: info
+            .timestamp
+            .map(|t| Timestamp::from_epoch_secs(t * block.date_granularity)),
+        uid: info.uid.map(|u| u as u32),
+        user: info.user_sid.map(|sid| stringtable[sid as usize].clone()),
+    }
+}
+
+/// Delta decode these ids
+fn delta_decode(deltas: &[i64]) -> impl ExactSizeIterator<Item = ObjId> + '_ {
+    let mut last_id = 0;
+    deltas.iter().map(move |delta| {
+        last_id += delta;
+        last_id as ObjId
+    })
+}
+
+/// Decode the (delta coded) node locations of a way, from a `LocationsOnWays` file
+fn decode_way_locations(way: &osmformat::Way, block: &BlockInfo) -> Option<Vec<(Lat, Lon)>> {
+    if way.lat.is_empty() || way.lat.len() != way.refs.len() || way.lon.len() != way.refs.len() {
+        return None;
+    }
+    let to_internal = |raw: i64, offset: i64| {
+        // granularity & offsets are in nanodegrees
+        ((offset + raw * block.granularity as i64) / COORD_PRECISION_NANOS as i64) as i32
+    };
+    let mut raw_lat = 0;
+    let mut raw_lon = 0;
+    Some(
+        way.lat
+            .iter()
+            .zip(way.lon.iter())
+            .map(|(dlat, dlon)| {
+                raw_lat += dlat;
+                raw_lon += dlon;
+                (
+                    Lat(to_internal(raw_lat, block.lat_offset)),
+                    Lon(to_internal(raw_lon, block.lon_offset)),
+                )
+            })
+            .collect(),
+    )
+}
+
+/// The (key, value) tags of a (non-dense) object
+fn tags<'a, S: Clone>(
+    keys: &'a [u32],
+    vals: &'a [u32],
+    stringtable: &'a [S],
+) -> impl ExactSizeIterator<Item = (S, S)> + 'a {
+    // TODO check for +itive keys/vals
+    assert_eq!(keys.len(), vals.len());
+    keys.iter().zip(vals.iter()).map(|(&k, &v)| {
+        (
+            stringtable[k as usize].clone(),
+            stringtable[v as usize].clone(),
+        )
+    })
+}
+
+fn decode_ways<B: PBFObjects>(
     primitive_group: &osmformat::PrimitiveGroup,
-    _granularity: i32,
-    _lat_offset: i64,
-    _lon_offset: i64,
-    _date_granularity: i32,
-    stringtable: &[SmolStr],
-    results: &mut VecDeque<StringOSMObj>,
+    block: &BlockInfo,
+    stringtable: &[B::Str],
+    wanted_ids: Option<&IdSet>,
+    objects: &mut B,
+    results: &mut VecDeque<B::Obj>,
 ) -> usize {
     let mut num_objects_written = 0;
     results.reserve(primitive_group.ways.len());
     for way in primitive_group.ways.iter() {
         let id = way.id.unwrap() as ObjId;
-        let mut tags = SmallVec::with_capacity(way.keys.len());
-        // TODO check for +itive keys/vals
-        let keys = way
-            .keys
-            .iter()
-            .map(|&idx| stringtable[idx as usize].clone());
-        let vals = way
-            .vals
-            .iter()
-            .map(|&idx| stringtable[idx as usize].clone());
-        assert_eq!(keys.len(), vals.len());
-        tags.extend(keys.zip(vals));
-
-        let refs = &way.refs;
-        let mut nodes = SmallVec::with_capacity(refs.len());
-        // TODO assert node.len() > 0
-        if !refs.is_empty() {
-            let mut last_id = refs[0];
-            nodes.push(last_id as ObjId);
-            for nid in &refs[1..] {
-                last_id += nid;
-                nodes.push(last_id as ObjId);
-            }
+        if wanted_ids.is_some_and(|w| !w.contains(id)) {
+            continue;
         }
-
         // TODO assert all node ids are positive
-
-        // TODO could there be *no* info? What should be done there
-
-        //println!("from pbf {} last_timestamp {}", way.get_info().get_timestamp(), last_timestamp);
-        //let timestamp = way.get_info().get_timestamp() as i32 + last_timestamp;
-        //let timestamp = timestamp * date_granularity;
-        //last_timestamp = timestamp;
-        //let timestamp = epoch_to_iso(timestamp);
-        let timestamp = TimestampFormat::EpochNumber(way.info.timestamp.unwrap());
-
-        results.push_back(StringOSMObj::Way(StringWay {
-            _id: id,
-            _tags: tags,
-            _nodes: nodes,
-            _deleted: !way.info.visible.unwrap_or(false),
-            _changeset_id: Some(way.info.changeset.unwrap() as u32),
-            _uid: Some(way.info.uid.unwrap() as u32),
-            _user: Some(stringtable[way.info.user_sid.unwrap() as usize].clone()),
-            _version: Some(way.info.version.unwrap() as u32),
-            _timestamp: Some(timestamp),
-        }));
+        results.push_back(objects.way(
+            info_meta(id, &way.info, block, stringtable),
+            tags(&way.keys, &way.vals, stringtable),
+            delta_decode(&way.refs),
+            decode_way_locations(way, block),
+        ));
         num_objects_written += 1;
     }
     num_objects_written
 }
 
-fn decode_relations(
+fn decode_relations<B: PBFObjects>(
     primitive_group: &osmformat::PrimitiveGroup,
-    _granularity: i32,
-    _lat_offset: i64,
-    _lon_offset: i64,
-    _date_granularity: i32,
-    stringtable: &[SmolStr],
-    sink: &mut VecDeque<StringOSMObj>,
+    block: &BlockInfo,
+    stringtable: &[B::Str],
+    wanted_ids: Option<&IdSet>,
+    objects: &mut B,
+    sink: &mut VecDeque<B::Obj>,
 ) -> usize {
-    let _last_timestamp = 0;
     let mut num_objects_written = 0;
     sink.reserve(primitive_group.relations.len());
     for relation in primitive_group.relations.iter() {
         let id = relation.id() as ObjId;
-        // TODO check for +itive keys/vals
-        let keys = relation
-            .keys
-            .iter()
-            .map(|&idx| stringtable[idx as usize].clone());
-        let vals = relation
-            .vals
-            .iter()
-            .map(|&idx| stringtable[idx as usize].clone());
-        assert_eq!(keys.len(), vals.len());
-        let tags: SmallVec<_> = keys.zip(vals).collect();
-
-        let roles = relation
-            .roles_sid
-            .iter()
-            .map(|&idx| stringtable[idx as usize].clone());
-
-        let refs = &relation.memids;
-        let mut member_ids = Vec::with_capacity(refs.len());
-        // TODO assert node.len() > 0
-        if !refs.is_empty() {
-            let mut last_id = refs[0];
-            member_ids.push(last_id as ObjId);
-            for nid in &refs[1..] {
-                last_id += nid;
-                member_ids.push(last_id as ObjId);
-            }
+        if wanted_ids.is_some_and(|w| !w.contains(id)) {
+            continue;
         }
-        let _num_members = member_ids.len();
-        let member_ids = member_ids.iter();
 
         let member_types = relation
             .types
@@ -329,145 +370,175 @@ fn decode_relations(
                 osmformat::relation::MemberType::WAY => OSMObjectType::Way,
                 osmformat::relation::MemberType::RELATION => OSMObjectType::Relation,
             });
-
-        let members: Vec<_> = member_types
-            .zip(member_ids)
+        let roles = relation
+            .roles_sid
+            .iter()
+            .map(|&idx| stringtable[idx as usize].clone());
+        let members = member_types
+            .zip(delta_decode(&relation.memids))
             .zip(roles)
-            .map(|((t, &id), r)| (t, id, r.clone()))
-            .collect();
+            .map(|((t, id), r)| (t, id, r));
 
-        // TODO could there be *no* info? What should be done there
-        //let timestamp = relation.get_info().get_timestamp() as i32 + last_timestamp;
-        //let timestamp = timestamp * date_granularity;
-        //last_timestamp = timestamp;
-        //let timestamp = epoch_to_iso(timestamp);
-        let timestamp = TimestampFormat::EpochNumber(relation.info.timestamp.unwrap());
-
-        sink.push_back(StringOSMObj::Relation(StringRelation {
-            _id: id,
-            _tags: tags,
-            _members: members,
-            _deleted: !relation.info.visible.unwrap_or(false),
-            _changeset_id: Some(relation.info.changeset.unwrap() as u32),
-            _uid: Some(relation.info.uid.unwrap() as u32),
-            _user: Some(stringtable[relation.info.user_sid.unwrap() as usize].clone()),
-            _version: Some(relation.info.version.unwrap() as u32),
-            _timestamp: Some(timestamp),
-        }));
+        sink.push_back(objects.relation(
+            info_meta(id, &relation.info, block, stringtable),
+            tags(&relation.keys, &relation.vals, stringtable),
+            members,
+        ));
         num_objects_written += 1;
     }
     num_objects_written
 }
 
-#[allow(clippy::too_many_arguments)]
-fn decode_primitive_group_to_objs(
+/// One of the `decode_*` functions
+type DecodeGroup<B> = fn(
+    &osmformat::PrimitiveGroup,
+    &BlockInfo,
+    &[<B as PBFObjects>::Str],
+    Option<&IdSet>,
+    &mut B,
+    &mut VecDeque<<B as PBFObjects>::Obj>,
+) -> usize;
+
+fn decode_primitive_group_to_objs<B: PBFObjects>(
     primitive_group: &osmformat::PrimitiveGroup,
-    granularity: i32,
-    lat_offset: i64,
-    lon_offset: i64,
-    date_granularity: i32,
+    block: &BlockInfo,
     raw_stringtable: osmformat::StringTable,
     object_filter: &ObjectFilter,
-    sink: &mut VecDeque<StringOSMObj>,
+    id_filter: &IdFilter,
+    objects: &mut B,
+    sink: &mut VecDeque<B::Obj>,
 ) -> usize {
-    let date_granularity = date_granularity / 1000;
-    let mut num_objects_written = 0;
-    if !primitive_group.nodes.is_empty() && object_filter.0 {
-        let mut stringtable: Vec<SmolStr> = Vec::with_capacity(raw_stringtable.s.len());
-        stringtable.extend(
-            raw_stringtable.s.iter().map(|chars| {
-                SmolStr::from(str::from_utf8(chars).expect("Invalid, non-utf8 String"))
-            }),
-        );
-
-        num_objects_written += decode_nodes(
-            primitive_group,
-            granularity,
-            lat_offset,
-            lon_offset,
-            date_granularity,
-            &stringtable,
-            sink,
-        );
+    if !group_has_wanted_ids(primitive_group, id_filter) {
+        // Don't bother decoding the stringtable
+        return 0;
+    }
+    let decode: DecodeGroup<B>;
+    let wanted_ids = if !primitive_group.nodes.is_empty() && object_filter.0 {
+        decode = decode_nodes;
+        &id_filter[0]
     } else if primitive_group.dense.is_some() && object_filter.0 {
-        let mut stringtable: Vec<SmolStr> = Vec::with_capacity(raw_stringtable.s.len());
-        stringtable.extend(raw_stringtable.s.into_iter().map(|chars| {
-            SmolStr::from(String::from_utf8(chars).expect("Invalid, non-utf8 String"))
-        }));
-
-        num_objects_written += decode_dense_nodes(
-            primitive_group,
-            granularity,
-            lat_offset,
-            lon_offset,
-            date_granularity,
-            &stringtable,
-            sink,
-        );
+        decode = decode_dense_nodes;
+        &id_filter[0]
     } else if !primitive_group.ways.is_empty() && object_filter.1 {
-        let mut stringtable: Vec<SmolStr> = Vec::with_capacity(raw_stringtable.s.len());
-        stringtable.extend(raw_stringtable.s.into_iter().map(|chars| {
-            SmolStr::from(String::from_utf8(chars).expect("Invalid, non-utf8 String"))
-        }));
-
-        num_objects_written += decode_ways(
-            primitive_group,
-            granularity,
-            lat_offset,
-            lon_offset,
-            date_granularity,
-            &stringtable,
-            sink,
-        );
+        decode = decode_ways;
+        &id_filter[1]
     } else if !primitive_group.relations.is_empty() && object_filter.2 {
-        let mut stringtable: Vec<SmolStr> = Vec::with_capacity(raw_stringtable.s.len());
-        stringtable.extend(raw_stringtable.s.into_iter().map(|chars| {
-            SmolStr::from(String::from_utf8(chars).expect("Invalid, non-utf8 String"))
-        }));
-
-        num_objects_written += decode_relations(
-            primitive_group,
-            granularity,
-            lat_offset,
-            lon_offset,
-            date_granularity,
-            &stringtable,
-            sink,
-        );
+        decode = decode_relations;
+        &id_filter[2]
     } else {
         // can happen if there is an object filter in operation
+        return 0;
+    };
+
+    let stringtable: Vec<B::Str> = raw_stringtable
+        .s
+        .iter()
+        .map(|chars| objects.string(str::from_utf8(chars).expect("Invalid, non-utf8 String")))
+        .collect();
+    decode(
+        primitive_group,
+        block,
+        &stringtable,
+        wanted_ids.as_ref(),
+        objects,
+        sink,
+    )
+}
+
+/// The type of objects in this block
+fn block_object_type(block: &osmformat::PrimitiveBlock) -> Option<OSMObjectType> {
+    let group = block.primitivegroup.first()?;
+    if group.dense.is_some() || !group.nodes.is_empty() {
+        Some(OSMObjectType::Node)
+    } else if !group.ways.is_empty() {
+        Some(OSMObjectType::Way)
+    } else if !group.relations.is_empty() {
+        Some(OSMObjectType::Relation)
+    } else {
+        None
     }
+}
 
-    num_objects_written
+/// Does the filter want objects of this type?
+fn wants_type(object_filter: &ObjectFilter, object_type: OSMObjectType) -> bool {
+    match object_type {
+        OSMObjectType::Node => object_filter.0,
+        OSMObjectType::Way => object_filter.1,
+        OSMObjectType::Relation => object_filter.2,
+    }
+}
+
+/// Does the filter want objects of this type, or a later type (in sorted order)?
+fn wants_type_at_or_after(object_filter: &ObjectFilter, object_type: OSMObjectType) -> bool {
+    match object_type {
+        OSMObjectType::Node => object_filter.0 || object_filter.1 || object_filter.2,
+        OSMObjectType::Way => object_filter.1 || object_filter.2,
+        OSMObjectType::Relation => object_filter.2,
+    }
 }
 
-fn decode_block_to_objs(
+/// Could this group have any objects in the `id_filter`?
+fn group_has_wanted_ids(primitive_group: &osmformat::PrimitiveGroup, id_filter: &IdFilter) -> bool {
+    if let Some(wanted) = &id_filter[0] {
+        if let Some(dense) = primitive_group.dense.as_ref() {
+            let mut id = 0;
+            return dense.id.iter().any(|delta| {
+                id += delta;
+                wanted.contains(id as ObjId)
+            });
+        } else if !primitive_group.nodes.is_empty() {
+            return primitive_group
+                .nodes
+                .iter()
+                .any(|n| wanted.contains(n.id() as ObjId));
+        }
+    }
+    if let Some(wanted) = &id_filter[1]
+        && !primitive_group.ways.is_empty()
+    {
+        return primitive_group
+            .ways
+            .iter()
+            .any(|w| wanted.contains(w.id() as ObjId));
+    }
+    if let Some(wanted) = &id_filter[2]
+        && !primitive_group.relations.is_empty()
+    {
+        return primitive_group
+            .relations
+            .iter()
+            .any(|r| wanted.contains(r.id() as ObjId));
+    }
+    true
+}
+
+fn decode_block_to_objs<B: PBFObjects>(
     mut block: osmformat::PrimitiveBlock,
     object_filter: &ObjectFilter,
-    sink: &mut VecDeque<StringOSMObj>,
+    id_filter: &IdFilter,
+    objects: &mut B,
+    sink: &mut VecDeque<B::Obj>,
 ) -> usize {
     let raw_stringtable = block.stringtable.take().unwrap();
 
-    let granularity = block.granularity();
-    let lat_offset = block.lat_offset();
-    let lon_offset = block.lon_offset();
-    let date_granularity = block.date_granularity();
-
-    let mut results = 0;
+    let block_info = BlockInfo {
+        granularity: block.granularity(),
+        lat_offset: block.lat_offset(),
+        lon_offset: block.lon_offset(),
+        // date_granularity is in milliseconds
+        date_granularity: block.date_granularity() as i64 / 1000,
+    };
 
     assert_eq!(block.primitivegroup.len(), 1);
-    results += decode_primitive_group_to_objs(
+    decode_primitive_group_to_objs(
         &block.primitivegroup[0],
-        granularity,
-        lat_offset,
-        lon_offset,
-        date_granularity,
+        &block_info,
         raw_stringtable,
         object_filter,
+        id_filter,
+        objects,
         sink,
-    );
-
-    results
+    )
 }
 
 impl<R: Read> Iterator for FileReader<R> {
@@ -478,61 +549,365 @@ impl<R: Read> Iterator for FileReader<R> {
     }
 }
 
-/// A thing that read PBF files
-pub struct PBFReader<R: Read> {
+/// Reads PBF files, building the objects with `B`
+///
+/// Usually used via one of the aliases: [`PBFReader`] (`StringOSMObj`), [`ArcPBFReader`]
+/// (`ArcOSMObj`) or [`RcPBFReader`] (`RcOSMObj`).
+pub struct GenericPBFReader<R: Read, B: PBFObjects = StringObjects> {
     filereader: FileReader<R>,
-    buffer: VecDeque<StringOSMObj>,
+    objects: B,
+    buffer: VecDeque<B::Obj>,
     _sorted_assumption: bool,
     object_filter: ObjectFilter,
+    id_filter: IdFilter,
+    /// File offsets of the OSMData blocks, for `find`
+    block_offsets: Option<Vec<u64>>,
+    /// The block `next_object_into` is reading, and the index of its next object
+    view: Option<(PrimitiveBlockView, usize)>,
+    /// The block a sorted read stopped at, which is read next
+    pending_block: Option<osmformat::PrimitiveBlock>,
+    /// Checks the order of objects returned by `OSMReader` with the sorted assumption
+    sorted_check: AssumedSortedCheck,
+    /// The blocks a sorted parallel read stopped at (with their order), which are read next
+    #[cfg(feature = "rayon")]
+    par_pending_blocks: std::sync::Mutex<Vec<(usize, osmformat::PrimitiveBlock)>>,
 }
 
-impl<R: Read> PBFReader<R> {
+/// Reads PBF files as [`StringOSMObj`](crate::obj_types::StringOSMObj)s
+pub type PBFReader<R> = GenericPBFReader<R, StringObjects>;
+/// Reads PBF files as [`ArcOSMObj`](crate::obj_types::ArcOSMObj)s
+pub type ArcPBFReader<R> = GenericPBFReader<R, ArcObjects>;
+/// Reads PBF files as [`RcOSMObj`](crate::obj_types::RcOSMObj)s
+pub type RcPBFReader<R> = GenericPBFReader<R, RcObjects>;
+
+impl<R: Read, B: PBFObjects> GenericPBFReader<R, B> {
+    /// A reader which builds objects with `objects`
+    pub fn with_objects(reader: R, objects: B) -> Self {
+        GenericPBFReader {
+            filereader: FileReader::new(reader),
+            objects,
+            buffer: VecDeque::new(),
+            _sorted_assumption: false,
+            object_filter: (true, true, true),
+            id_filter: Default::default(),
+            block_offsets: None,
+            view: None,
+            pending_block: None,
+            sorted_check: AssumedSortedCheck::default(),
+            #[cfg(feature = "rayon")]
+            par_pending_blocks: Default::default(),
+        }
+    }
+
+    /// The next object, for any `B`. ([`OSMReader`] needs `B::Obj` to be an [`OSMObj`])
+    pub fn next_object(&mut self) -> Option<B::Obj> {
+        while self.buffer.is_empty() {
+            // get the next file block and fill up our buffer
+            // FIXME make this parallel
+            let block = self.next_raw_block().unwrap()?;
+
+            if self._sorted_assumption
+                && let Some(block_type) = block_object_type(&block)
+                && !wants_type_at_or_after(&self.object_filter, block_type)
+            {
+                // Sorted, so there's nothing more we want. Keep the block for a later read with
+                // another filter.
+                self.pending_block = Some(block);
+                return None;
+            }
+
+            // Turn a block into OSM objects
+            decode_block_to_objs(
+                block,
+                &self.object_filter,
+                &self.id_filter,
+                &mut self.objects,
+                &mut self.buffer,
+            );
+        }
+
+        self.buffer.pop_front()
+    }
+
+    /// The next `OSMData` block, without decoding the objects in it. The filters aren't used.
+    ///
+    /// Objects already decoded by [`next_object`](Self::next_object) (or `OSMReader::next`)
+    /// aren't in it, so don't mix them.
+    pub fn next_block(&mut self) -> Option<PrimitiveBlockView> {
+        self.read_block_view().unwrap()
+    }
+
+    fn read_block_view(&mut self) -> Result<Option<PrimitiveBlockView>> {
+        Ok(self.next_raw_block()?.map(PrimitiveBlockView::new))
+    }
+
+    /// The next block, starting with the one a sorted read stopped at
+    fn next_raw_block(&mut self) -> Result<Option<osmformat::PrimitiveBlock>> {
+        if let Some(block) = self.pending_block.take() {
+            return Ok(Some(block));
+        }
+        #[cfg(feature = "rayon")]
+        {
+            let par_pending = self.par_pending_blocks.get_mut().unwrap();
+            if let Some(first) = (0..par_pending.len()).min_by_key(|&i| par_pending[i].0) {
+                return Ok(Some(par_pending.swap_remove(first).1));
+            }
+        }
+        let Some(mut blob) = self.filereader.next() else {
+            return Ok(None);
+        };
+        let mut blob_data = Vec::new();
+        blob_raw_data(&mut blob, &mut blob_data, &self.object_filter);
+        Ok(Some(osmformat::PrimitiveBlock::parse_from_bytes(
+            &blob_data,
+        )?))
+    }
+
+    /// Overwrite `obj` with the next object, reusing its buffers (see
+    /// [`PBFObjects::node_into`]). `false` at the end of the file.
+    ///
+    /// The objects are built straight from the block, rather than decoded in bulk like
+    /// [`next_object`](Self::next_object). Objects already decoded by that are returned first.
+    pub fn next_object_into(&mut self, obj: &mut B::Obj) -> Result<bool> {
+        if let Some(next) = self.buffer.pop_front() {
+            *obj = next;
+            return Ok(true);
+        }
+        loop {
+            if let Some((view, idx)) = &mut self.view {
+                while *idx < view.len() {
+                    *idx += 1;
+                    if view.build_into(*idx - 1, &self.id_filter, &mut self.objects, obj) {
+                        return Ok(true);
+                    }
+                }
+            }
+
+            self.view = None;
+            let Some(block) = self.next_raw_block()? else {
+                return Ok(false);
+            };
+            let Some(block_type) = block_object_type(&block) else {
+                continue;
+            };
+            if self._sorted_assumption && !wants_type_at_or_after(&self.object_filter, block_type) {
+                // Sorted, so there's nothing more we want
+                self.pending_block = Some(block);
+                return Ok(false);
+            }
+            if wants_type(&self.object_filter, block_type) {
+                self.view = Some((PrimitiveBlockView::new(block), 0));
+            }
+        }
+    }
+
+    /// Iterate over the remaining `OSMData` blocks. See [`next_block`](Self::next_block).
+    pub fn blocks(&mut self) -> impl Iterator<Item = PrimitiveBlockView> + '_ {
+        std::iter::from_fn(move || self.next_block())
+    }
+
+    /// Only return objects of this type whose id is in `ids`. `None` returns all objects of
+    /// this type.
+    pub fn set_id_filter(&mut self, object_type: OSMObjectType, ids: Option<IdSet>) {
+        let idx = match object_type {
+            OSMObjectType::Node => 0,
+            OSMObjectType::Way => 1,
+            OSMObjectType::Relation => 2,
+        };
+        self.id_filter[idx] = ids;
+    }
+}
+
+impl<R: Read, B: PBFObjects + Default> GenericPBFReader<R, B>
+where
+    B::Obj: OSMObj,
+{
     /// Iterate over all the nodes in this source
-    pub fn nodes(&mut self) -> impl Iterator<Item = StringNode> + '_ {
+    pub fn nodes(&mut self) -> impl Iterator<Item = <B::Obj as OSMObj>::Node> + '_ {
         self.object_filter = (true, false, false);
         self.objects().filter_map(|o| o.into_node())
     }
 
     /// Iterate over all the ways in this source
-    pub fn ways(&mut self) -> impl Iterator<Item = StringWay> + '_ {
+    pub fn ways(&mut self) -> impl Iterator<Item = <B::Obj as OSMObj>::Way> + '_ {
         self.object_filter = (false, true, false);
         self.objects().filter_map(|o| o.into_way())
     }
 
     /// Iterate over all the relations in this source
-    pub fn relations(&mut self) -> impl Iterator<Item = StringRelation> + '_ {
+    pub fn relations(&mut self) -> impl Iterator<Item = <B::Obj as OSMObj>::Relation> + '_ {
         self.object_filter = (false, false, true);
         self.objects().filter_map(|o| o.into_relation())
     }
+
+    /// Iterate over the nodes whose id is in `ids`. Blocks without any of them aren't decoded.
+    pub fn nodes_in(&mut self, ids: IdSet) -> impl Iterator<Item = <B::Obj as OSMObj>::Node> + '_ {
+        self.set_id_filter(OSMObjectType::Node, Some(ids));
+        self.nodes()
+    }
+
+    /// Iterate over the ways whose id is in `ids`. Blocks without any of them aren't decoded.
+    pub fn ways_in(&mut self, ids: IdSet) -> impl Iterator<Item = <B::Obj as OSMObj>::Way> + '_ {
+        self.set_id_filter(OSMObjectType::Way, Some(ids));
+        self.ways()
+    }
+
+    /// Iterate over the relations whose id is in `ids`. Blocks without any of them aren't
+    /// decoded.
+    pub fn relations_in(
+        &mut self,
+        ids: IdSet,
+    ) -> impl Iterator<Item = <B::Obj as OSMObj>::Relation> + '_ {
+        self.set_id_filter(OSMObjectType::Relation, Some(ids));
+        self.relations()
+    }
+}
+
+impl<R: Read + Seek, B: PBFObjects> GenericPBFReader<R, B>
+where
+    B::Obj: OSMObjBase,
+{
+    /// Find the object with this type & id.
+    ///
+    /// With the [sorted assumption](OSMReader::set_sorted_assumption), this is a binary search
+    /// over the blocks, otherwise every block is read. The filters aren't used, and the reader's
+    /// position isn't changed.
+    pub fn find(&mut self, object_type: OSMObjectType, id: ObjId) -> Result<Option<B::Obj>> {
+        let start_pos = self.filereader.reader.stream_position()?;
+        let result = self.find_from_start(object_type, id);
+        self.filereader.reader.seek(SeekFrom::Start(start_pos))?;
+        result
+    }
+
+    fn find_from_start(&mut self, object_type: OSMObjectType, id: ObjId) -> Result<Option<B::Obj>> {
+        let target = (object_type, id);
+        let is_target = |o: &B::Obj| (o.object_type(), o.id()) == target;
+        let num_blocks = self.block_offsets()?.len();
+
+        if !self._sorted_assumption {
+            for idx in 0..num_blocks {
+                if let Some(obj) = self.read_block(idx)?.into_iter().find(is_target) {
+                    return Ok(Some(obj));
+                }
+            }
+            return Ok(None);
+        }
+
+        // Find the last block which starts at or before the target
+        let (mut lo, mut hi) = (0, num_blocks);
+        let mut candidate = None;
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let objs = self.read_block(mid)?;
+            match objs.front() {
+                Some(first) if (first.object_type(), first.id()) > target => hi = mid,
+                _ => {
+                    lo = mid + 1;
+                    candidate = Some(objs);
+                }
+            }
+        }
+        // The last block read with lo moving up is the one
+        Ok(candidate.and_then(|objs| objs.into_iter().find(is_target)))
+    }
+
+    /// Scan the file for the start of every OSMData block. Only the block headers are read.
+    fn block_offsets(&mut self) -> Result<&[u64]> {
+        if self.block_offsets.is_none() {
+            let reader = &mut self.filereader.reader;
+            reader.seek(SeekFrom::Start(0))?;
+            let mut offsets = Vec::new();
+            loop {
+                let offset = reader.stream_position()?;
+                let Ok(size) = reader.read_u32::<byteorder::BigEndian>() else {
+                    break;
+                };
+                let mut header_bytes = vec![0; size as usize];
+                reader.read_exact(&mut header_bytes)?;
+                let blob_header = fileformat::BlobHeader::parse_from_bytes(&header_bytes)?;
+                if blob_header.type_() == "OSMData" {
+                    offsets.push(offset);
+                }
+                reader.seek(SeekFrom::Current(blob_header.datasize() as i64))?;
+            }
+            self.block_offsets = Some(offsets);
+        }
+        Ok(self.block_offsets.as_deref().unwrap())
+    }
+
+    /// Decode all the objects in this block
+    fn read_block(&mut self, idx: usize) -> Result<VecDeque<B::Obj>> {
+        let offset = self.block_offsets()?[idx];
+        self.filereader.reader.seek(SeekFrom::Start(offset))?;
+        let mut objs = VecDeque::new();
+        let Some(mut blob) = self.filereader.next() else {
+            return Ok(objs);
+        };
+        let mut blob_data = Vec::new();
+        blob_raw_data(&mut blob, &mut blob_data, &(true, true, true));
+        let block = osmformat::PrimitiveBlock::parse_from_bytes(&blob_data)?;
+        decode_block_to_objs(
+            block,
+            &(true, true, true),
+            &Default::default(),
+            &mut self.objects,
+            &mut objs,
+        );
+        Ok(objs)
+    }
 }
 
-impl PBFReader<BufReader<File>> {
+impl<B: PBFObjects + Default> GenericPBFReader<BufReader<File>, B> {
     /// Creates a PBF Reader from a path.
     pub fn from_filename(filename: impl AsRef<Path>) -> Result<Self> {
         let filename: &Path = filename.as_ref();
-        Ok(Self::new(BufReader::new(File::open(filename)?)))
+        Ok(Self::with_objects(
+            BufReader::new(File::open(filename)?),
+            B::default(),
+        ))
     }
 }
 
-impl<R: Read> OSMReader for PBFReader<R> {
+/// Blocks are read whole, so this is at the end of the last block read
+impl<R: Read + ReadProgress, B: PBFObjects> ReadProgress for GenericPBFReader<R, B> {
+    fn bytes_read(&self) -> u64 {
+        self.filereader.reader.bytes_read()
+    }
+
+    fn total_bytes(&self) -> Option<u64> {
+        self.filereader.reader.total_bytes()
+    }
+}
+
+impl<R: Read, B: PBFObjects + Default> OSMReader for GenericPBFReader<R, B>
+where
+    B::Obj: OSMObj,
+{
     type R = R;
-    type Obj = StringOSMObj;
+    type Obj = B::Obj;
 
-    fn new(reader: R) -> PBFReader<R> {
-        PBFReader {
-            filereader: FileReader::new(reader),
-            buffer: VecDeque::new(),
-            _sorted_assumption: false,
-            object_filter: (true, true, true),
-        }
+    fn new(reader: R) -> Self {
+        Self::with_objects(reader, B::default())
     }
 
     fn set_sorted_assumption(&mut self, sorted_assumption: bool) {
         self._sorted_assumption = sorted_assumption;
+        self.sorted_check = AssumedSortedCheck::default();
     }
     fn get_sorted_assumption(&mut self) -> bool {
         self._sorted_assumption
     }
+    fn unsorted_error(&self) -> Option<&UnsortedError> {
+        self.sorted_check.error()
+    }
+
+    /// Blocks of other types aren't decoded
+    fn set_object_filter(&mut self, object_filter: ObjectFilter) {
+        self.object_filter = object_filter;
+    }
+    fn get_object_filter(&self) -> ObjectFilter {
+        self.object_filter
+    }
 
     fn inner(&self) -> &R {
         self.filereader.inner()
@@ -542,28 +917,202 @@ impl<R: Read> OSMReader for PBFReader<R> {
         self.filereader.into_inner()
     }
 
-    fn next(&mut self) -> Option<StringOSMObj> {
-        let mut blob_data = Vec::new();
-        while self.buffer.is_empty() {
-            // get the next file block and fill up our buffer
-            // FIXME make this parallel
+    fn next(&mut self) -> Option<B::Obj> {
+        if !self._sorted_assumption {
+            return self.next_object();
+        }
+        if self.sorted_check.stopped() {
+            return None;
+        }
+        let obj = self.next_object()?;
+        self.sorted_check.check(&obj).then_some(obj)
+    }
 
-            // get the next block
-            let mut blob = self.filereader.next()?;
+    fn unread(&mut self, obj: B::Obj) {
+        self.buffer.push_front(obj);
+    }
 
-            blob_data.clear();
-            blob_raw_data(&mut blob, &mut blob_data, &self.object_filter);
-            if blob_data.is_empty() {
-                // maybe the filter meant nothing was read
-                continue;
+    fn next_into(&mut self, obj: &mut B::Obj) -> Result<bool> {
+        if !self._sorted_assumption {
+            return self.next_object_into(obj);
+        }
+        if self.sorted_check.stopped() {
+            return Ok(false);
+        }
+        Ok(self.next_object_into(obj)? && self.sorted_check.check(obj))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::{
+        ArcOSMObj, RcOSMObj, StringNodeBuilder, StringOSMObj, StringRelationBuilder,
+        StringWayBuilder,
+    };
+
+    fn node(id: ObjId) -> StringOSMObj {
+        let mut builder = StringNodeBuilder::default();
+        builder
+            ._id(id)
+            ._version(1)
+            ._changeset_id(id as u32)
+            ._timestamp(1_600_000_000.into())
+            ._uid(4)
+            ._user(format!("user{}", id % 3).into())
+            ._lat_lon((Lat(id as i32), Lon(-id as i32)));
+        if id % 2 == 0 {
+            builder._tags(smallvec::smallvec![("n".into(), id.to_string().into())]);
+        }
+        builder.build().unwrap().into()
+    }
+
+    fn way(id: ObjId) -> StringOSMObj {
+        StringWayBuilder::default()
+            ._id(id)
+            ._version(1)
+            ._changeset_id(id as u32)
+            ._timestamp(1_600_000_000.into())
+            ._uid(4)
+            ._user("".into())
+            ._nodes(smallvec::smallvec![2, 4])
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    fn to_pbf(objs: &[StringOSMObj]) -> Vec<u8> {
+        let mut writer = PBFWriter::new(Vec::new());
+        for obj in objs.iter() {
+            writer.write_obj(obj).unwrap();
+        }
+        writer.into_inner()
+    }
+
+    #[test]
+    fn next_into() {
+        let relation: StringOSMObj = StringRelationBuilder::default()
+            ._id(20)
+            ._version(1)
+            ._changeset_id(3)
+            ._timestamp(1_600_000_000.into())
+            ._uid(5)
+            ._user("bob".into())
+            ._members(vec![
+                (OSMObjectType::Way, 10, "outer".into()),
+                (OSMObjectType::Node, 1, "".into()),
+            ])
+            .build()
+            .unwrap()
+            .into();
+        let objs = vec![node(1), node(2), way(10), relation];
+        let output = to_pbf(&objs);
+
+        // Overwriting one object
+        let mut reader = PBFReader::new(output.as_slice());
+        let mut obj = objs[1].clone();
+        let mut read = Vec::new();
+        while reader.next_into(&mut obj).unwrap() {
+            read.push(obj.clone());
+        }
+        assert_eq!(read, objs);
+        let mut reader = ArcPBFReader::new(output.as_slice());
+        let mut obj = ArcOSMObj::from(objs[1].clone());
+        let mut read = Vec::new();
+        while reader.next_into(&mut obj).unwrap() {
+            read.push(obj.clone());
+        }
+        assert_eq!(
+            read,
+            objs.iter()
+                .cloned()
+                .map(ArcOSMObj::from)
+                .collect::<Vec<_>>()
+        );
+        let mut reader = RcPBFReader::new(output.as_slice());
+        reader.set_object_filter((false, true, true));
+        let mut obj = RcOSMObj::from(objs[0].clone());
+        assert!(reader.next_into(&mut obj).unwrap());
+        assert_eq!(obj, RcOSMObj::from(objs[2].clone()));
+    }
+
+    #[test]
+    fn read_only_some_ids() {
+        let objs: Vec<StringOSMObj> = (1..=20_000).map(node).collect();
+        let output = to_pbf(&objs);
+
+        let wanted: IdSet = [2, 5, 8_001, 19_999].into_iter().collect();
+        let read: Vec<StringOSMObj> = PBFReader::new(output.as_slice())
+            .nodes_in(wanted)
+            .map(|n| n.into())
+            .collect();
+        let expected: Vec<StringOSMObj> = [2, 5, 8_001, 19_999].into_iter().map(node).collect();
+        assert_eq!(read, expected);
+    }
+
+    #[test]
+    fn sorted_find() {
+        let objs: Vec<StringOSMObj> = (1..=20_000)
+            .map(|id| node(id * 2))
+            .chain((1..=10).map(way))
+            .collect();
+        let output = to_pbf(&objs);
+
+        for sorted in [false, true] {
+            let mut reader = PBFReader::new(Cursor::new(output.as_slice()));
+            reader.set_sorted_assumption(sorted);
+            assert_eq!(reader.next().unwrap().id(), 2);
+            for id in [2, 16_000, 16_002, 40_000] {
+                let node = reader.find(OSMObjectType::Node, id).unwrap().unwrap();
+                assert_eq!(node.id(), id);
+                assert!(node.is_node());
             }
-            let block: osmformat::PrimitiveBlock =
-                osmformat::PrimitiveBlock::parse_from_bytes(&blob_data).unwrap();
+            assert_eq!(reader.find(OSMObjectType::Node, 3).unwrap(), None);
+            assert_eq!(reader.find(OSMObjectType::Node, 40_002).unwrap(), None);
+            assert_eq!(reader.find(OSMObjectType::Relation, 1).unwrap(), None);
+            let way = reader.find(OSMObjectType::Way, 10).unwrap().unwrap();
+            assert!(way.is_way());
+            // The position hasn't changed
+            assert_eq!(reader.next().unwrap().id(), 4);
+        }
 
-            // Turn a block into OSM objects
-            decode_block_to_objs(block, &self.object_filter, &mut self.buffer);
+        // The first way block isn't lost when nodes() stops
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        assert_eq!(reader.nodes().count(), 20_000);
+        assert_eq!(reader.ways().count(), 10);
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        assert_eq!(reader.ways().count(), 10);
+
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        reader.set_object_filter((true, false, false));
+        let mut obj = objs[0].clone();
+        let mut num_nodes = 0;
+        while reader.next_into(&mut obj).unwrap() {
+            num_nodes += 1;
         }
+        assert_eq!(num_nodes, 20_000);
+        reader.set_object_filter((false, true, false));
+        assert!(reader.next_into(&mut obj).unwrap());
+        assert_eq!((obj.object_type(), obj.id()), (OSMObjectType::Way, 1));
+        assert!(reader.unsorted_error().is_none());
+    }
 
-        self.buffer.pop_front()
+    #[test]
+    fn unsorted_if_assumed_sorted() {
+        let output = to_pbf(&[node(1), node(3), node(2), node(4)]);
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        let ids: Vec<ObjId> = reader.objects().map(|o| o.id()).collect();
+        assert_eq!(ids, [1, 3]);
+        let err = reader.unsorted_error().unwrap();
+        assert_eq!(err.previous, (OSMObjectType::Node, 3, Some(1)));
+        assert_eq!(err.current, (OSMObjectType::Node, 2, Some(1)));
+
+        let mut reader = PBFReader::new(output.as_slice());
+        assert_eq!(reader.objects().count(), 4);
+        assert!(reader.unsorted_error().is_none());
     }
 }
@@ -0,0 +1,417 @@
+//! Turning decoded PBF data into objects
+use crate::obj_types::{
+    ArcNode, ArcOSMObj, ArcRelation, ArcWay, RcNode, RcOSMObj, RcRelation, RcWay, StringNode,
+    StringOSMObj, StringRelation, StringWay,
+};
+use crate::{Lat, Lon, OSMObjectType, ObjId, Timestamp};
+use smol_str::SmolStr;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// The metadata of an object in a PBF file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PBFObjMeta<S> {
+    pub id: ObjId,
+    pub version: Option<u32>,
+    pub deleted: bool,
+    pub changeset_id: Option<u32>,
+    pub timestamp: Option<Timestamp>,
+    pub uid: Option<u32>,
+    pub user: Option<S>,
+}
+
+impl<S> PBFObjMeta<S> {
+    /// Convert the user
+    pub fn map_user<T>(self, f: impl FnOnce(S) -> T) -> PBFObjMeta<T> {
+        PBFObjMeta {
+            id: self.id,
+            version: self.version,
+            deleted: self.deleted,
+            changeset_id: self.changeset_id,
+            timestamp: self.timestamp,
+            uid: self.uid,
+            user: self.user.map(f),
+        }
+    }
+}
+
+/// How to build objects from a PBF file.
+///
+/// [`GenericPBFReader`](super::GenericPBFReader) decodes the file, and calls this to make each
+/// object. Implement this to read into your own object types.
+pub trait PBFObjects {
+    /// The objects which are returned
+    type Obj;
+    /// A string from a block's string table. Each string is converted once per block, then
+    /// cloned for every tag, user or role which uses it.
+    type Str: Clone;
+
+    fn string(&mut self, s: &str) -> Self::Str;
+
+    fn node(
+        &mut self,
+        meta: PBFObjMeta<Self::Str>,
+        tags: impl ExactSizeIterator<Item = (Self::Str, Self::Str)>,
+        lat_lon: (Lat, Lon),
+    ) -> Self::Obj;
+
+    /// `node_locations` is only set for `LocationsOnWays` files
+    fn way(
+        &mut self,
+        meta: PBFObjMeta<Self::Str>,
+        tags: impl ExactSizeIterator<Item = (Self::Str, Self::Str)>,
+        nodes: impl ExactSizeIterator<Item = ObjId>,
+        node_locations: Option<Vec<(Lat, Lon)>>,
+    ) -> Self::Obj;
+
+    fn relation(
+        &mut self,
+        meta: PBFObjMeta<Self::Str>,
+        tags: impl ExactSizeIterator<Item = (Self::Str, Self::Str)>,
+        members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, Self::Str)>,
+    ) -> Self::Obj;
+
+    /// Overwrite `target` with this node, reusing its buffers where possible. By default it's
+    /// replaced with a new [`node`](Self::node).
+    fn node_into<'a>(
+        &mut self,
+        target: &mut Self::Obj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        lat_lon: (Lat, Lon),
+    ) {
+        let meta = meta.map_user(|u| self.string(u));
+        let tags: Vec<_> = tags
+            .map(|(k, v)| (self.string(k), self.string(v)))
+            .collect();
+        *target = self.node(meta, tags.into_iter(), lat_lon);
+    }
+
+    /// Overwrite `target` with this way, reusing its buffers where possible. By default it's
+    /// replaced with a new [`way`](Self::way).
+    fn way_into<'a>(
+        &mut self,
+        target: &mut Self::Obj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        nodes: impl ExactSizeIterator<Item = ObjId>,
+        node_locations: Option<Vec<(Lat, Lon)>>,
+    ) {
+        let meta = meta.map_user(|u| self.string(u));
+        let tags: Vec<_> = tags
+            .map(|(k, v)| (self.string(k), self.string(v)))
+            .collect();
+        *target = self.way(meta, tags.into_iter(), nodes, node_locations);
+    }
+
+    /// Overwrite `target` with this relation, reusing its buffers where possible. By default
+    /// it's replaced with a new [`relation`](Self::relation).
+    fn relation_into<'a>(
+        &mut self,
+        target: &mut Self::Obj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)>,
+    ) {
+        let meta = meta.map_user(|u| self.string(u));
+        let tags: Vec<_> = tags
+            .map(|(k, v)| (self.string(k), self.string(v)))
+            .collect();
+        let members: Vec<_> = members
+            .map(|(t, id, role)| (t, id, self.string(role)))
+            .collect();
+        *target = self.relation(meta, tags.into_iter(), members.into_iter());
+    }
+}
+
+/// Overwrite the metadata of an object, converting the user with `$string`
+macro_rules! set_meta {
+    ($obj:expr, $meta:expr, $string:path) => {{
+        let (obj, meta) = (&mut *$obj, $meta);
+        obj._id = meta.id;
+        obj._version = meta.version;
+        obj._deleted = meta.deleted;
+        obj._changeset_id = meta.changeset_id;
+        obj._timestamp = meta.timestamp;
+        obj._uid = meta.uid;
+        obj._user = meta.user.map($string);
+    }};
+}
+
+/// Build [`StringOSMObj`]s
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StringObjects;
+
+impl PBFObjects for StringObjects {
+    type Obj = StringOSMObj;
+    type Str = SmolStr;
+
+    fn string(&mut self, s: &str) -> SmolStr {
+        SmolStr::from(s)
+    }
+
+    fn node(
+        &mut self,
+        meta: PBFObjMeta<SmolStr>,
+        tags: impl ExactSizeIterator<Item = (SmolStr, SmolStr)>,
+        lat_lon: (Lat, Lon),
+    ) -> StringOSMObj {
+        StringOSMObj::Node(StringNode {
+            _id: meta.id,
+            _version: meta.version,
+            _deleted: meta.deleted,
+            _changeset_id: meta.changeset_id,
+            _timestamp: meta.timestamp,
+            _uid: meta.uid,
+            _user: meta.user,
+            _tags: tags.collect(),
+            _lat_lon: Some(lat_lon),
+        })
+    }
+
+    fn way(
+        &mut self,
+        meta: PBFObjMeta<SmolStr>,
+        tags: impl ExactSizeIterator<Item = (SmolStr, SmolStr)>,
+        nodes: impl ExactSizeIterator<Item = ObjId>,
+        node_locations: Option<Vec<(Lat, Lon)>>,
+    ) -> StringOSMObj {
+        StringOSMObj::Way(StringWay {
+            _id: meta.id,
+            _version: meta.version,
+            _deleted: meta.deleted,
+            _changeset_id: meta.changeset_id,
+            _timestamp: meta.timestamp,
+            _uid: meta.uid,
+            _user: meta.user,
+            _tags: tags.collect(),
+            _nodes: nodes.collect(),
+            _node_locations: node_locations,
+        })
+    }
+
+    fn relation(
+        &mut self,
+        meta: PBFObjMeta<SmolStr>,
+        tags: impl ExactSizeIterator<Item = (SmolStr, SmolStr)>,
+        members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, SmolStr)>,
+    ) -> StringOSMObj {
+        StringOSMObj::Relation(StringRelation {
+            _id: meta.id,
+            _version: meta.version,
+            _deleted: meta.deleted,
+            _changeset_id: meta.changeset_id,
+            _timestamp: meta.timestamp,
+            _uid: meta.uid,
+            _user: meta.user,
+            _tags: tags.collect(),
+            _members: members.collect(),
+        })
+    }
+
+    fn node_into<'a>(
+        &mut self,
+        target: &mut StringOSMObj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        lat_lon: (Lat, Lon),
+    ) {
+        let tags = tags.map(|(k, v)| (SmolStr::from(k), SmolStr::from(v)));
+        let StringOSMObj::Node(node) = target else {
+            *target = self.node(meta.map_user(SmolStr::from), tags, lat_lon);
+            return;
+        };
+        // Short strings are stored inline, without allocating
+        set_meta!(node, meta, SmolStr::from);
+        node._tags.clear();
+        node._tags.extend(tags);
+        node._lat_lon = Some(lat_lon);
+    }
+
+    fn way_into<'a>(
+        &mut self,
+        target: &mut StringOSMObj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        nodes: impl ExactSizeIterator<Item = ObjId>,
+        node_locations: Option<Vec<(Lat, Lon)>>,
+    ) {
+        let tags = tags.map(|(k, v)| (SmolStr::from(k), SmolStr::from(v)));
+        let StringOSMObj::Way(way) = target else {
+            *target = self.way(meta.map_user(SmolStr::from), tags, nodes, node_locations);
+            return;
+        };
+        set_meta!(way, meta, SmolStr::from);
+        way._tags.clear();
+        way._tags.extend(tags);
+        way._nodes.clear();
+        way._nodes.extend(nodes);
+        way._node_locations = node_locations;
+    }
+
+    fn relation_into<'a>(
+        &mut self,
+        target: &mut StringOSMObj,
+        meta: PBFObjMeta<&'a str>,
+        tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+        members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)>,
+    ) {
+        let tags = tags.map(|(k, v)| (SmolStr::from(k), SmolStr::from(v)));
+        let members = members.map(|(t, id, role)| (t, id, SmolStr::from(role)));
+        let StringOSMObj::Relation(relation) = target else {
+            *target = self.relation(meta.map_user(SmolStr::from), tags, members);
+            return;
+        };
+        set_meta!(relation, meta, SmolStr::from);
+        relation._tags.clear();
+        relation._tags.extend(tags);
+        relation._members.clear();
+        relation._members.extend(members);
+    }
+}
+
+macro_rules! shared_str_objects {
+    ($name:ident, $ptr:ident, $obj:ident, $node:ident, $way:ident, $relation:ident) => {
+        #[doc = concat!("Build [`", stringify!($obj), "`]s, sharing the strings of each block")]
+        ///
+        /// The `*_into` methods reuse the tag, node & member buffers, but the strings are still
+        /// allocated.
+        #[derive(Debug, Clone, Copy, Default)]
+        pub struct $name;
+
+        impl PBFObjects for $name {
+            type Obj = $obj;
+            type Str = $ptr<str>;
+
+            fn string(&mut self, s: &str) -> $ptr<str> {
+                $ptr::from(s)
+            }
+
+            fn node(
+                &mut self,
+                meta: PBFObjMeta<$ptr<str>>,
+                tags: impl ExactSizeIterator<Item = ($ptr<str>, $ptr<str>)>,
+                lat_lon: (Lat, Lon),
+            ) -> $obj {
+                $obj::Node($node {
+                    _id: meta.id,
+                    _version: meta.version,
+                    _deleted: meta.deleted,
+                    _changeset_id: meta.changeset_id,
+                    _timestamp: meta.timestamp,
+                    _uid: meta.uid,
+                    _user: meta.user,
+                    _tags: (tags.len() > 0).then(|| tags.collect()),
+                    _lat_lon: Some(lat_lon),
+                })
+            }
+
+            fn way(
+                &mut self,
+                meta: PBFObjMeta<$ptr<str>>,
+                tags: impl ExactSizeIterator<Item = ($ptr<str>, $ptr<str>)>,
+                nodes: impl ExactSizeIterator<Item = ObjId>,
+                node_locations: Option<Vec<(Lat, Lon)>>,
+            ) -> $obj {
+                $obj::Way($way {
+                    _id: meta.id,
+                    _version: meta.version,
+                    _deleted: meta.deleted,
+                    _changeset_id: meta.changeset_id,
+                    _timestamp: meta.timestamp,
+                    _uid: meta.uid,
+                    _user: meta.user,
+                    _tags: tags.collect(),
+                    _nodes: nodes.collect(),
+                    _node_locations: node_locations,
+                })
+            }
+
+            fn relation(
+                &mut self,
+                meta: PBFObjMeta<$ptr<str>>,
+                tags: impl ExactSizeIterator<Item = ($ptr<str>, $ptr<str>)>,
+                members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, $ptr<str>)>,
+            ) -> $obj {
+                $obj::Relation($relation {
+                    _id: meta.id,
+                    _version: meta.version,
+                    _deleted: meta.deleted,
+                    _changeset_id: meta.changeset_id,
+                    _timestamp: meta.timestamp,
+                    _uid: meta.uid,
+                    _user: meta.user,
+                    _tags: tags.collect(),
+                    _members: members.collect(),
+                })
+            }
+
+            fn node_into<'a>(
+                &mut self,
+                target: &mut $obj,
+                meta: PBFObjMeta<&'a str>,
+                tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+                lat_lon: (Lat, Lon),
+            ) {
+                let tags = tags.map(|(k, v)| ($ptr::from(k), $ptr::from(v)));
+                let $obj::Node(node) = target else {
+                    *target = self.node(meta.map_user($ptr::from), tags, lat_lon);
+                    return;
+                };
+                set_meta!(node, meta, $ptr::from);
+                match &mut node._tags {
+                    Some(old) if tags.len() > 0 => {
+                        old.clear();
+                        old.extend(tags);
+                    }
+                    old => *old = (tags.len() > 0).then(|| tags.collect()),
+                }
+                node._lat_lon = Some(lat_lon);
+            }
+
+            fn way_into<'a>(
+                &mut self,
+                target: &mut $obj,
+                meta: PBFObjMeta<&'a str>,
+                tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+                nodes: impl ExactSizeIterator<Item = ObjId>,
+                node_locations: Option<Vec<(Lat, Lon)>>,
+            ) {
+                let tags = tags.map(|(k, v)| ($ptr::from(k), $ptr::from(v)));
+                let $obj::Way(way) = target else {
+                    *target = self.way(meta.map_user($ptr::from), tags, nodes, node_locations);
+                    return;
+                };
+                set_meta!(way, meta, $ptr::from);
+                way._tags.clear();
+                way._tags.extend(tags);
+                way._nodes.clear();
+                way._nodes.extend(nodes);
+                way._node_locations = node_locations;
+            }
+
+            fn relation_into<'a>(
+                &mut self,
+                target: &mut $obj,
+                meta: PBFObjMeta<&'a str>,
+                tags: impl ExactSizeIterator<Item = (&'a str, &'a str)>,
+                members: impl ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)>,
+            ) {
+                let tags = tags.map(|(k, v)| ($ptr::from(k), $ptr::from(v)));
+                let members = members.map(|(t, id, role)| (t, id, $ptr::from(role)));
+                let $obj::Relation(relation) = target else {
+                    *target = self.relation(meta.map_user($ptr::from), tags, members);
+                    return;
+                };
+                set_meta!(relation, meta, $ptr::from);
+                relation._tags.clear();
+                relation._tags.extend(tags);
+                relation._members.clear();
+                relation._members.extend(members);
+            }
+        }
+    };
+}
+
+shared_str_objects!(ArcObjects, Arc, ArcOSMObj, ArcNode, ArcWay, ArcRelation);
+shared_str_objects!(RcObjects, Rc, RcOSMObj, RcNode, RcWay, RcRelation);
@@ -2030,6 +2030,16 @@ pub struct Way {
     pub info: ::protobuf::MessageField<Info>,
     // @@protoc_insertion_point(field:OSMPBF.Way.refs)
     pub refs: ::std::vec::Vec<i64>,
+    ///  The following two fields are optional. They are only used in a special
+    ///  format where node locations are also added to the ways. This makes the
+    ///  files larger, but allows creating way geometries directly.
+    ///
+    ///  If this is used, you MUST set the optional_features tag "LocationsOnWays"
+    ///  and the number of values in refs, lat, and lon MUST be the same.
+    // @@protoc_insertion_point(field:OSMPBF.Way.lat)
+    pub lat: ::std::vec::Vec<i64>,
+    // @@protoc_insertion_point(field:OSMPBF.Way.lon)
+    pub lon: ::std::vec::Vec<i64>,
     // special fields
     // @@protoc_insertion_point(special_field:OSMPBF.Way.special_fields)
     pub special_fields: ::protobuf::SpecialFields,
@@ -2108,6 +2118,18 @@ impl ::protobuf::Message for Way {
                 64 => {
                     self.refs.push(is.read_sint64()?);
                 },
+                74 => {
+                    is.read_repeated_packed_sint64_into(&mut self.lat)?;
+                },
+                72 => {
+                    self.lat.push(is.read_sint64()?);
+                },
+                82 => {
+                    is.read_repeated_packed_sint64_into(&mut self.lon)?;
+                },
+                80 => {
+                    self.lon.push(is.read_sint64()?);
+                },
                 tag => {
                     ::protobuf::rt::read_unknown_or_skip_group(tag, is, self.special_fields.mut_unknown_fields())?;
                 },
@@ -2130,6 +2152,8 @@ impl ::protobuf::Message for Way {
             my_size += 1 + ::protobuf::rt::compute_raw_varint64_size(len) + len;
         }
         my_size += ::protobuf::rt::vec_packed_sint64_size(8, &self.refs);
+        my_size += ::protobuf::rt::vec_packed_sint64_size(9, &self.lat);
+        my_size += ::protobuf::rt::vec_packed_sint64_size(10, &self.lon);
         my_size += ::protobuf::rt::unknown_fields_size(self.special_fields.unknown_fields());
         self.special_fields.cached_size().set(my_size as u32);
         my_size
@@ -2145,6 +2169,8 @@ impl ::protobuf::Message for Way {
             ::protobuf::rt::write_message_field_with_cached_size(4, v, os)?;
         }
         os.write_repeated_packed_sint64(8, &self.refs)?;
+        os.write_repeated_packed_sint64(9, &self.lat)?;
+        os.write_repeated_packed_sint64(10, &self.lon)?;
         os.write_unknown_fields(self.special_fields.unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -2167,6 +2193,8 @@ impl ::protobuf::Message for Way {
         self.vals.clear();
         self.info.clear();
         self.refs.clear();
+        self.lat.clear();
+        self.lon.clear();
         self.special_fields.clear();
     }
 
@@ -2177,6 +2205,8 @@ impl ::protobuf::Message for Way {
             vals: ::std::vec::Vec::new(),
             info: ::protobuf::MessageField::none(),
             refs: ::std::vec::Vec::new(),
+            lat: ::std::vec::Vec::new(),
+            lon: ::std::vec::Vec::new(),
             special_fields: ::protobuf::SpecialFields::new(),
         };
         &instance
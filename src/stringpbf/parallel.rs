@@ -0,0 +1,250 @@
+//! Decoding the blocks of a PBF file in parallel, with rayon. Needs the `rayon` feature.
+//!
+//! ```no_run
+//! use osmio::stringpbf::ArcPBFReader;
+//! use osmio::{OSMObjBaseRead, OSMReader};
+//! use rayon::prelude::*;
+//!
+//! let mut reader = ArcPBFReader::from_filename("planet.osm.pbf")?;
+//! let benches = reader
+//!     .par_nodes()
+//!     .filter(|n| n.tag("amenity") == Some("bench"))
+//!     .count();
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use super::*;
+use rayon::prelude::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A block to decode in parallel
+enum Work {
+    /// Already read, e.g. where a sorted read stopped
+    Block(osmformat::PrimitiveBlock),
+    Blob(fileformat::Blob),
+}
+
+impl<R: Read + Send, B> GenericPBFReader<R, B>
+where
+    B: PBFObjects + Default + Clone + Send + Sync,
+    B::Obj: OSMObj + Send,
+{
+    /// All the remaining objects, one block per work item. They're not in order.
+    ///
+    /// The object & id filters are used. With the sorted assumption, this stops at the first block
+    /// of a later type than the filter wants, like [`next_object`](Self::next_object), and the
+    /// following reads start there.
+    pub fn par_objects(&mut self) -> impl ParallelIterator<Item = B::Obj> + '_ {
+        self.par_objects_of_types(self.object_filter)
+    }
+
+    /// All the remaining nodes, in parallel. Other blocks aren't decoded.
+    pub fn par_nodes(&mut self) -> impl ParallelIterator<Item = <B::Obj as OSMObj>::Node> + '_
+    where
+        <B::Obj as OSMObj>::Node: Send,
+    {
+        self.par_objects_of_types((true, false, false))
+            .filter_map(|o| o.into_node())
+    }
+
+    /// All the remaining ways, in parallel. Other blocks aren't decoded.
+    pub fn par_ways(&mut self) -> impl ParallelIterator<Item = <B::Obj as OSMObj>::Way> + '_
+    where
+        <B::Obj as OSMObj>::Way: Send,
+    {
+        self.par_objects_of_types((false, true, false))
+            .filter_map(|o| o.into_way())
+    }
+
+    /// All the remaining relations, in parallel. Other blocks aren't decoded.
+    pub fn par_relations(
+        &mut self,
+    ) -> impl ParallelIterator<Item = <B::Obj as OSMObj>::Relation> + '_
+    where
+        <B::Obj as OSMObj>::Relation: Send,
+    {
+        self.par_objects_of_types((false, false, true))
+            .filter_map(|o| o.into_relation())
+    }
+
+    /// `par_objects`, with this filter instead of the reader's. Buffered objects of other types
+    /// are kept for later reads.
+    fn par_objects_of_types(
+        &mut self,
+        object_filter: ObjectFilter,
+    ) -> impl ParallelIterator<Item = B::Obj> + '_ {
+        let sorted = self._sorted_assumption;
+        let (buffered, kept): (VecDeque<_>, VecDeque<_>) = std::mem::take(&mut self.buffer)
+            .into_iter()
+            .partition(|o| wants_type(&object_filter, o.object_type()));
+        let stopped = Arc::new(AtomicBool::new(
+            sorted
+                && kept
+                    .iter()
+                    .any(|o| !wants_type_at_or_after(&object_filter, o.object_type())),
+        ));
+        self.buffer = kept;
+
+        // Blocks already read come first, in file order
+        let mut pending = std::mem::take(self.par_pending_blocks.get_mut().unwrap());
+        pending.sort_by_key(|(seq, _)| *seq);
+        let pending = self
+            .pending_block
+            .take()
+            .into_iter()
+            .chain(pending.into_iter().map(|(_, block)| block));
+        let mut work = pending
+            .map(Work::Block)
+            .chain((&mut self.filereader).map(Work::Blob))
+            .enumerate();
+        let work = {
+            let stopped = stopped.clone();
+            // Checked before reading, so no blob is lost
+            std::iter::from_fn(move || {
+                if stopped.load(Ordering::Relaxed) {
+                    None
+                } else {
+                    work.next()
+                }
+            })
+        };
+
+        let objects = self.objects.clone();
+        let id_filter = &self.id_filter;
+        let par_pending_blocks = &self.par_pending_blocks;
+        let blocks = work.par_bridge().flat_map_iter(move |(seq, work)| {
+            let mut decoded = VecDeque::new();
+            let block = match work {
+                Work::Block(block) => block,
+                Work::Blob(mut blob) => {
+                    let mut blob_data = Vec::new();
+                    blob_raw_data(&mut blob, &mut blob_data, &object_filter);
+                    if blob_data.is_empty() {
+                        return decoded;
+                    }
+                    osmformat::PrimitiveBlock::parse_from_bytes(&blob_data).unwrap()
+                }
+            };
+            if sorted
+                && let Some(block_type) = block_object_type(&block)
+                && !wants_type_at_or_after(&object_filter, block_type)
+            {
+                // Sorted, so nothing more is wanted. Blocks other threads have already read are
+                // kept too.
+                stopped.store(true, Ordering::Relaxed);
+                par_pending_blocks.lock().unwrap().push((seq, block));
+                return decoded;
+            }
+            decode_block_to_objs(
+                block,
+                &object_filter,
+                id_filter,
+                &mut objects.clone(),
+                &mut decoded,
+            );
+            decoded
+        });
+        buffered.into_par_iter().chain(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::{ArcOSMObj, StringNodeBuilder, StringOSMObj, StringWayBuilder};
+
+    #[test]
+    fn same_as_sequential() {
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        for id in 1..=3 {
+            let node: StringOSMObj = StringNodeBuilder::default()
+                ._id(id)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_000.into())
+                ._uid(4)
+                ._user("alice".into())
+                ._lat_lon((Lat(id as i32), Lon(1)))
+                .build()
+                .unwrap()
+                .into();
+            writer.write_obj(&node).unwrap();
+        }
+        let way: StringOSMObj = StringWayBuilder::default()
+            ._id(10)
+            ._version(1)
+            ._changeset_id(3)
+            ._timestamp(1_600_000_000.into())
+            ._uid(4)
+            ._user("alice".into())
+            ._nodes(smallvec::smallvec![1, 2, 3])
+            ._tags(smallvec::smallvec![("highway".into(), "path".into())])
+            .build()
+            .unwrap()
+            .into();
+        writer.write_obj(&way).unwrap();
+        writer.close().unwrap();
+        drop(writer);
+
+        let expected: Vec<ArcOSMObj> = ArcPBFReader::new(output.as_slice()).objects().collect();
+        let mut reader = ArcPBFReader::new(output.as_slice());
+        // One already buffered
+        let first = reader.next().unwrap();
+        let mut objs: Vec<ArcOSMObj> = reader.par_objects().collect();
+        objs.push(first);
+        objs.sort_by_key(|o| (o.object_type(), o.id()));
+        assert_eq!(objs, expected);
+
+        let mut reader = PBFReader::new(output.as_slice());
+        assert_eq!(reader.par_nodes().count(), 3);
+        let mut reader = PBFReader::new(output.as_slice());
+        let ways: Vec<_> = reader.par_ways().collect();
+        assert_eq!(ways.len(), 1);
+        assert_eq!(ways[0].tag("highway"), Some("path"));
+        // The reader's filter isn't changed
+        assert_eq!(reader.get_object_filter(), (true, true, true));
+    }
+
+    #[test]
+    fn sorted_stops_early() {
+        // Several blocks of each type
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        for id in 1..=20_000 {
+            let node: StringOSMObj = StringNodeBuilder::default()
+                ._id(id)
+                ._lat_lon((Lat(id as i32), Lon(1)))
+                .build()
+                .unwrap()
+                .into();
+            writer.write_obj(&node).unwrap();
+        }
+        for id in 1..=20_000 {
+            let way: StringOSMObj = StringWayBuilder::default()
+                ._id(id)
+                ._nodes(smallvec::smallvec![id, id + 1])
+                .build()
+                .unwrap()
+                .into();
+            writer.write_obj(&way).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        assert_eq!(reader.par_nodes().count(), 20_000);
+        // The way blocks read in parallel aren't lost, and are still in order
+        let way_ids: Vec<ObjId> = reader.ways().map(|w| w.id()).collect();
+        assert_eq!(way_ids, (1..=20_000).collect::<Vec<_>>());
+
+        // Buffered ways stop a sorted read of nodes
+        let mut reader = PBFReader::new(output.as_slice());
+        reader.assume_sorted();
+        reader.set_object_filter((false, true, false));
+        assert_eq!(reader.next().unwrap().id(), 1);
+        assert_eq!(reader.par_nodes().count(), 0);
+        assert_eq!(reader.par_ways().count(), 19_999);
+    }
+}
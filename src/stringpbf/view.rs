@@ -0,0 +1,750 @@
+//! Borrowed views of the objects in a PBF block
+//!
+//! A [`PrimitiveBlockView`] is one decompressed `OSMData` block. The [`NodeRef`]s, [`WayRef`]s &
+//! [`RelationRef`]s it hands out read straight from the block, so looking at tags doesn't
+//! allocate. They implement the read only traits ([`NodeRead`], [`WayRead`] & [`RelationRead`]),
+//! so generic code which only needs getters can use them. Turn one into an owned object with
+//! `build`, or `StringOSMObj::from`.
+//!
+//! ```no_run
+//! use osmio::stringpbf::PBFReader;
+//! use osmio::OSMReader;
+//! let mut reader = PBFReader::from_filename("planet.osm.pbf")?;
+//! for block in reader.blocks() {
+//!     for node in block.nodes() {
+//!         if node.tag("amenity") == Some("bench") {
+//!             println!("{} {:?}", node.id(), node.lat_lon());
+//!         }
+//!     }
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use super::objects::{PBFObjMeta, PBFObjects, StringObjects};
+use super::{
+    BlockInfo, IdFilter, block_object_type, decode_way_locations, delta_decode, osmformat,
+};
+use crate::idset::IdSet;
+use crate::obj_types::StringOSMObj;
+use crate::{Lat, Lon, OSMObjectType, ObjId, Timestamp};
+use crate::{NodeRead, OSMObjBaseRead, RelationRead, WayRead};
+use std::fmt;
+
+/// One `OSMData` block of a PBF file, which the objects are borrowed from
+pub struct PrimitiveBlockView {
+    /// The dense nodes' columns have been delta decoded
+    block: osmformat::PrimitiveBlock,
+    info: BlockInfo,
+    /// The string table, one string after the other
+    strings: String,
+    /// Where each string ends in `strings`
+    string_ends: Vec<usize>,
+    /// Where each dense node's tags start in `keys_vals`, plus the end. Empty if no node has tags.
+    dense_tag_starts: Vec<usize>,
+    /// Are there versions, timestamps etc. for the dense nodes?
+    dense_has_info: bool,
+    /// The node locations of all the ways, for `LocationsOnWays` files
+    way_locations: Vec<(Lat, Lon)>,
+    /// Where each way's locations start in `way_locations`. Empty if no way has any.
+    way_location_starts: Vec<Option<usize>>,
+}
+
+/// Turn a delta coded column into the values
+fn undelta<T: Copy + std::ops::AddAssign>(column: &mut [T]) {
+    let mut values = column.iter_mut();
+    if let Some(first) = values.next() {
+        let mut last = *first;
+        for value in values {
+            *value += last;
+            last = *value;
+        }
+    }
+}
+
+impl PrimitiveBlockView {
+    pub(super) fn new(mut block: osmformat::PrimitiveBlock) -> Self {
+        assert_eq!(block.primitivegroup.len(), 1);
+        let raw_stringtable = block.stringtable.take().unwrap();
+        let mut strings = String::with_capacity(raw_stringtable.s.iter().map(Vec::len).sum());
+        let string_ends = raw_stringtable
+            .s
+            .iter()
+            .map(|chars| {
+                strings.push_str(str::from_utf8(chars).expect("Invalid, non-utf8 String"));
+                strings.len()
+            })
+            .collect();
+
+        let info = BlockInfo {
+            granularity: block.granularity(),
+            lat_offset: block.lat_offset(),
+            lon_offset: block.lon_offset(),
+            // date_granularity is in milliseconds
+            date_granularity: block.date_granularity() as i64 / 1000,
+        };
+
+        let mut dense_has_info = false;
+        let mut dense_tag_starts = Vec::new();
+        if let Some(dense) = block.primitivegroup[0].dense.as_mut() {
+            undelta(&mut dense.id);
+            undelta(&mut dense.lat);
+            undelta(&mut dense.lon);
+            dense_has_info = dense.denseinfo.version.len() == dense.id.len();
+            if let Some(denseinfo) = dense.denseinfo.as_mut()
+                && dense_has_info
+            {
+                undelta(&mut denseinfo.timestamp);
+                undelta(&mut denseinfo.changeset);
+                undelta(&mut denseinfo.uid);
+                undelta(&mut denseinfo.user_sid);
+            }
+        }
+        if let Some(dense) = block.primitivegroup[0].dense.as_ref()
+            && !dense.keys_vals.is_empty()
+        {
+            // Each node's (key, value) string ids end with a 0
+            dense_tag_starts.reserve(dense.id.len() + 1);
+            dense_tag_starts.push(0);
+            let mut idx = 0;
+            while idx < dense.keys_vals.len() {
+                if dense.keys_vals[idx] == 0 {
+                    idx += 1;
+                    dense_tag_starts.push(idx);
+                } else {
+                    idx += 2;
+                }
+            }
+        }
+
+        let mut way_locations = Vec::new();
+        let mut way_location_starts = Vec::new();
+        let ways = &mut block.primitivegroup[0].ways;
+        if ways.iter().any(|way| !way.lat.is_empty()) {
+            way_location_starts = ways
+                .iter()
+                .map(|way| {
+                    let locations = decode_way_locations(way, &info)?;
+                    way_locations.extend(locations);
+                    Some(way_locations.len() - way.refs.len())
+                })
+                .collect();
+        }
+        for way in ways.iter_mut() {
+            undelta(&mut way.refs);
+        }
+
+        PrimitiveBlockView {
+            block,
+            info,
+            strings,
+            string_ends,
+            dense_tag_starts,
+            dense_has_info,
+            way_locations,
+            way_location_starts,
+        }
+    }
+
+    fn group(&self) -> &osmformat::PrimitiveGroup {
+        &self.block.primitivegroup[0]
+    }
+
+    fn string(&self, idx: impl TryInto<usize>) -> &str {
+        let Ok(idx) = idx.try_into() else {
+            panic!("Negative string id");
+        };
+        let start = idx.checked_sub(1).map_or(0, |i| self.string_ends[i]);
+        &self.strings[start..self.string_ends[idx]]
+    }
+
+    /// The type of objects in this block. `None` for an empty block.
+    pub fn object_type(&self) -> Option<OSMObjectType> {
+        block_object_type(&self.block)
+    }
+
+    /// Number of objects in this block
+    pub fn len(&self) -> usize {
+        let group = self.group();
+        group.dense.id.len() + group.ways.len() + group.relations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The (dense) nodes in this block. Non-dense nodes aren't supported.
+    pub fn nodes(&self) -> impl ExactSizeIterator<Item = NodeRef<'_>> {
+        (0..self.group().dense.id.len()).map(move |idx| NodeRef { block: self, idx })
+    }
+
+    pub fn ways(&self) -> impl ExactSizeIterator<Item = WayRef<'_>> {
+        self.group()
+            .ways
+            .iter()
+            .enumerate()
+            .map(move |(idx, obj)| WayRef {
+                block: self,
+                obj,
+                idx,
+            })
+    }
+
+    pub fn relations(&self) -> impl ExactSizeIterator<Item = RelationRef<'_>> {
+        self.group()
+            .relations
+            .iter()
+            .map(move |obj| RelationRef { block: self, obj })
+    }
+
+    /// Overwrite `target` with the `idx`th object, if it's in the `id_filter`
+    pub(super) fn build_into<B: PBFObjects>(
+        &self,
+        idx: usize,
+        id_filter: &IdFilter,
+        objects: &mut B,
+        target: &mut B::Obj,
+    ) -> bool {
+        let unwanted =
+            |filter: &Option<IdSet>, id| filter.as_ref().is_some_and(|w| !w.contains(id));
+        let group = self.group();
+        match self.object_type() {
+            Some(OSMObjectType::Node) => {
+                let node = NodeRef { block: self, idx };
+                if unwanted(&id_filter[0], node.id()) {
+                    return false;
+                }
+                node.build_into(objects, target);
+            }
+            Some(OSMObjectType::Way) => {
+                let way = WayRef {
+                    block: self,
+                    obj: &group.ways[idx],
+                    idx,
+                };
+                if unwanted(&id_filter[1], way.id()) {
+                    return false;
+                }
+                way.build_into(objects, target);
+            }
+            Some(OSMObjectType::Relation) => {
+                let relation = RelationRef {
+                    block: self,
+                    obj: &group.relations[idx],
+                };
+                if unwanted(&id_filter[2], relation.id()) {
+                    return false;
+                }
+                relation.build_into(objects, target);
+            }
+            None => return false,
+        }
+        true
+    }
+}
+
+/// The methods every object view has, given `id`, `tag_ids` & `meta`
+macro_rules! obj_ref_methods {
+    ($name:ident, $object_type:expr) => {
+        impl<'a> $name<'a> {
+            pub fn object_type(&self) -> OSMObjectType {
+                $object_type
+            }
+
+            pub fn user(&self) -> Option<&'a str> {
+                self.user_sid().map(|sid| self.block.string(sid))
+            }
+
+            pub fn tags(&self) -> impl ExactSizeIterator<Item = (&'a str, &'a str)> + 'a {
+                let block = self.block;
+                self.tag_ids()
+                    .map(move |(k, v)| (block.string(k), block.string(v)))
+            }
+
+            pub fn tag(&self, key: impl AsRef<str>) -> Option<&'a str> {
+                let key = key.as_ref();
+                self.tags().find(|(k, _)| *k == key).map(|(_, v)| v)
+            }
+
+            pub fn has_tag(&self, key: impl AsRef<str>) -> bool {
+                self.tag(key).is_some()
+            }
+
+            pub fn num_tags(&self) -> usize {
+                self.tag_ids().len()
+            }
+
+            pub fn tagged(&self) -> bool {
+                self.num_tags() > 0
+            }
+
+            fn str_meta(&self) -> PBFObjMeta<&'a str> {
+                PBFObjMeta {
+                    id: self.id(),
+                    version: self.version(),
+                    deleted: self.deleted(),
+                    changeset_id: self.changeset_id(),
+                    timestamp: self.timestamp(),
+                    uid: self.uid(),
+                    user: self.user(),
+                }
+            }
+
+            fn meta<B: PBFObjects>(&self, objects: &mut B) -> PBFObjMeta<B::Str> {
+                self.str_meta().map_user(|u| objects.string(u))
+            }
+
+            fn owned_tags<B: PBFObjects>(
+                &self,
+                objects: &mut B,
+            ) -> std::vec::IntoIter<(B::Str, B::Str)> {
+                self.tags()
+                    .map(|(k, v)| (objects.string(k), objects.string(v)))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+            }
+        }
+
+        impl OSMObjBaseRead for $name<'_> {
+            fn id(&self) -> ObjId {
+                $name::id(self)
+            }
+            fn version(&self) -> Option<u32> {
+                $name::version(self)
+            }
+            fn deleted(&self) -> bool {
+                $name::deleted(self)
+            }
+            fn changeset_id(&self) -> Option<u32> {
+                $name::changeset_id(self)
+            }
+            fn timestamp(&self) -> Option<Timestamp> {
+                $name::timestamp(self)
+            }
+            fn uid(&self) -> Option<u32> {
+                $name::uid(self)
+            }
+            fn user(&self) -> Option<&str> {
+                $name::user(self)
+            }
+            fn tags<'b>(&'b self) -> Box<dyn ExactSizeIterator<Item = (&'b str, &'b str)> + 'b> {
+                Box::new($name::tags(self))
+            }
+            fn tag(&self, key: impl AsRef<str>) -> Option<&str> {
+                $name::tag(self, key)
+            }
+            fn num_tags(&self) -> usize {
+                $name::num_tags(self)
+            }
+            fn object_type(&self) -> OSMObjectType {
+                $object_type
+            }
+        }
+
+        impl From<$name<'_>> for StringOSMObj {
+            fn from(obj: $name<'_>) -> StringOSMObj {
+                obj.build(&mut StringObjects)
+            }
+        }
+
+        impl fmt::Debug for $name<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("id", &self.id())
+                    .field("tags", &self.tags().collect::<Vec<_>>())
+                    .finish_non_exhaustive()
+            }
+        }
+    };
+}
+
+/// A node in a [`PrimitiveBlockView`]
+#[derive(Clone, Copy)]
+pub struct NodeRef<'a> {
+    block: &'a PrimitiveBlockView,
+    idx: usize,
+}
+
+obj_ref_methods!(NodeRef, OSMObjectType::Node);
+
+impl<'a> NodeRef<'a> {
+    fn dense(&self) -> &'a osmformat::DenseNodes {
+        &self.block.group().dense
+    }
+
+    /// Only for files with metadata
+    fn info(&self) -> Option<&'a osmformat::DenseInfo> {
+        self.block.dense_has_info.then(|| &*self.dense().denseinfo)
+    }
+
+    fn user_sid(&self) -> Option<i32> {
+        self.info().map(|i| i.user_sid[self.idx])
+    }
+
+    fn tag_ids(&self) -> impl ExactSizeIterator<Item = (i32, i32)> + 'a {
+        let tags = match self.block.dense_tag_starts.get(self.idx..=self.idx + 1) {
+            // Without the 0 at the end
+            Some(&[start, next]) => &self.dense().keys_vals[start..next - 1],
+            _ => &[],
+        };
+        tags.chunks_exact(2).map(|kv| (kv[0], kv[1]))
+    }
+
+    pub fn id(&self) -> ObjId {
+        self.dense().id[self.idx] as ObjId
+    }
+
+    pub fn version(&self) -> Option<u32> {
+        self.info().map(|i| i.version[self.idx] as u32)
+    }
+
+    pub fn deleted(&self) -> bool {
+        self.info()
+            .and_then(|i| i.visible.get(self.idx))
+            .is_some_and(|visible| !visible)
+    }
+
+    pub fn changeset_id(&self) -> Option<u32> {
+        self.info().map(|i| i.changeset[self.idx] as u32)
+    }
+
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        self.info().map(|i| {
+            Timestamp::from_epoch_secs(i.timestamp[self.idx] * self.block.info.date_granularity)
+        })
+    }
+
+    pub fn uid(&self) -> Option<u32> {
+        self.info().map(|i| i.uid[self.idx] as u32)
+    }
+
+    pub fn lat_lon(&self) -> Option<(Lat, Lon)> {
+        let dense = self.dense();
+        Some(
+            self.block
+                .info
+                .dense_lat_lon(dense.lat[self.idx], dense.lon[self.idx]),
+        )
+    }
+
+    pub fn lat_lon_f64(&self) -> Option<(f64, f64)> {
+        self.lat_lon().map(|(lat, lon)| (lat.into(), lon.into()))
+    }
+
+    /// Build an owned object
+    pub fn build<B: PBFObjects>(&self, objects: &mut B) -> B::Obj {
+        let meta = self.meta(objects);
+        let tags = self.owned_tags(objects);
+        objects.node(meta, tags, self.lat_lon().unwrap())
+    }
+
+    /// Overwrite `target` with this node, reusing its buffers. See
+    /// [`PBFObjects::node_into`].
+    pub fn build_into<B: PBFObjects>(&self, objects: &mut B, target: &mut B::Obj) {
+        objects.node_into(
+            target,
+            self.str_meta(),
+            self.tags(),
+            self.lat_lon().unwrap(),
+        );
+    }
+}
+
+impl NodeRead for NodeRef<'_> {
+    fn lat_lon(&self) -> Option<(Lat, Lon)> {
+        NodeRef::lat_lon(self)
+    }
+}
+
+/// The metadata getters of ways & relations
+macro_rules! info_getters {
+    ($name:ident) => {
+        impl<'a> $name<'a> {
+            fn user_sid(&self) -> Option<u32> {
+                self.obj.info.user_sid
+            }
+
+            fn tag_ids(&self) -> impl ExactSizeIterator<Item = (u32, u32)> + 'a {
+                // TODO check for +itive keys/vals
+                assert_eq!(self.obj.keys.len(), self.obj.vals.len());
+                self.obj
+                    .keys
+                    .iter()
+                    .copied()
+                    .zip(self.obj.vals.iter().copied())
+            }
+
+            pub fn id(&self) -> ObjId {
+                self.obj.id() as ObjId
+            }
+
+            pub fn version(&self) -> Option<u32> {
+                self.obj.info.version.map(|v| v as u32)
+            }
+
+            pub fn deleted(&self) -> bool {
+                !self.obj.info.visible.unwrap_or(true)
+            }
+
+            pub fn changeset_id(&self) -> Option<u32> {
+                self.obj.info.changeset.map(|c| c as u32)
+            }
+
+            pub fn timestamp(&self) -> Option<Timestamp> {
+                self.obj
+                    .info
+                    .timestamp
+                    .map(|t| Timestamp::from_epoch_secs(t * self.block.info.date_granularity))
+            }
+
+            pub fn uid(&self) -> Option<u32> {
+                self.obj.info.uid.map(|u| u as u32)
+            }
+        }
+    };
+}
+
+/// A way in a [`PrimitiveBlockView`]
+#[derive(Clone, Copy)]
+pub struct WayRef<'a> {
+    block: &'a PrimitiveBlockView,
+    obj: &'a osmformat::Way,
+    idx: usize,
+}
+
+obj_ref_methods!(WayRef, OSMObjectType::Way);
+info_getters!(WayRef);
+
+impl<'a> WayRef<'a> {
+    pub fn nodes(&self) -> &'a [ObjId] {
+        &self.obj.refs
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.obj.refs.len()
+    }
+
+    /// The node locations, for `LocationsOnWays` files
+    pub fn node_locations(&self) -> Option<&'a [(Lat, Lon)]> {
+        let start = (*self.block.way_location_starts.get(self.idx)?)?;
+        Some(&self.block.way_locations[start..start + self.num_nodes()])
+    }
+
+    /// Build an owned object
+    pub fn build<B: PBFObjects>(&self, objects: &mut B) -> B::Obj {
+        let meta = self.meta(objects);
+        let tags = self.owned_tags(objects);
+        objects.way(
+            meta,
+            tags,
+            self.nodes().iter().copied(),
+            self.node_locations().map(<[_]>::to_vec),
+        )
+    }
+
+    /// Overwrite `target` with this way, reusing its buffers. See [`PBFObjects::way_into`].
+    pub fn build_into<B: PBFObjects>(&self, objects: &mut B, target: &mut B::Obj) {
+        objects.way_into(
+            target,
+            self.str_meta(),
+            self.tags(),
+            self.nodes().iter().copied(),
+            self.node_locations().map(<[_]>::to_vec),
+        );
+    }
+}
+
+impl WayRead for WayRef<'_> {
+    fn nodes(&self) -> &[ObjId] {
+        WayRef::nodes(self)
+    }
+    fn num_nodes(&self) -> usize {
+        WayRef::num_nodes(self)
+    }
+    fn node(&self, idx: usize) -> Option<ObjId> {
+        WayRef::nodes(self).get(idx).copied()
+    }
+    fn node_locations(&self) -> Option<&[(Lat, Lon)]> {
+        WayRef::node_locations(self)
+    }
+}
+
+/// A relation in a [`PrimitiveBlockView`]
+#[derive(Clone, Copy)]
+pub struct RelationRef<'a> {
+    block: &'a PrimitiveBlockView,
+    obj: &'a osmformat::Relation,
+}
+
+obj_ref_methods!(RelationRef, OSMObjectType::Relation);
+info_getters!(RelationRef);
+
+impl<'a> RelationRef<'a> {
+    pub fn members(&self) -> impl ExactSizeIterator<Item = (OSMObjectType, ObjId, &'a str)> + 'a {
+        let block = self.block;
+        let member_types = self
+            .obj
+            .types
+            .iter()
+            .map(::protobuf::EnumOrUnknown::unwrap)
+            .map(|t| match t {
+                osmformat::relation::MemberType::NODE => OSMObjectType::Node,
+                osmformat::relation::MemberType::WAY => OSMObjectType::Way,
+                osmformat::relation::MemberType::RELATION => OSMObjectType::Relation,
+            });
+        let roles = self.obj.roles_sid.iter().map(move |&sid| block.string(sid));
+        member_types
+            .zip(delta_decode(&self.obj.memids))
+            .zip(roles)
+            .map(|((t, id), r)| (t, id, r))
+    }
+
+    /// Build an owned object
+    pub fn build<B: PBFObjects>(&self, objects: &mut B) -> B::Obj {
+        let meta = self.meta(objects);
+        let tags = self.owned_tags(objects);
+        let members = self
+            .members()
+            .map(|(t, id, role)| (t, id, objects.string(role)))
+            .collect::<Vec<_>>();
+        objects.relation(meta, tags, members.into_iter())
+    }
+
+    /// Overwrite `target` with this relation, reusing its buffers. See
+    /// [`PBFObjects::relation_into`].
+    pub fn build_into<B: PBFObjects>(&self, objects: &mut B, target: &mut B::Obj) {
+        objects.relation_into(target, self.str_meta(), self.tags(), self.members());
+    }
+}
+
+impl RelationRead for RelationRef<'_> {
+    fn members<'b>(
+        &'b self,
+    ) -> Box<dyn ExactSizeIterator<Item = (OSMObjectType, ObjId, &'b str)> + 'b> {
+        Box::new(RelationRef::members(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::way_coords;
+    use crate::nodestore::{HashMapStore, NodeLocationStore};
+    use crate::obj_types::{StringNodeBuilder, StringRelationBuilder, StringWayBuilder};
+    use crate::stringpbf::{ArcObjects, ArcPBFReader, PBFReader, PBFWriter};
+    use crate::tagfilter::TagFilter;
+    use crate::{OSMReader, OSMWriter};
+
+    #[test]
+    fn views_match_decoded() {
+        let objs: Vec<StringOSMObj> = vec![
+            StringNodeBuilder::default()
+                ._id(1)
+                ._version(2)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_000.into())
+                ._uid(4)
+                ._user("alice".into())
+                ._lat_lon((Lat(515000000), Lon(-1000000)))
+                ._tags(smallvec::smallvec![("amenity".into(), "bench".into())])
+                .build()
+                .unwrap()
+                .into(),
+            StringNodeBuilder::default()
+                ._id(5)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_001.into())
+                ._uid(4)
+                ._user("alice".into())
+                ._lat_lon((Lat(-515000000), Lon(1000000)))
+                .build()
+                .unwrap()
+                .into(),
+            StringWayBuilder::default()
+                ._id(10)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_002.into())
+                ._uid(5)
+                ._user("bob".into())
+                ._nodes(smallvec::smallvec![1, 5, 1])
+                ._tags(smallvec::smallvec![("highway".into(), "path".into())])
+                .build()
+                .unwrap()
+                .into(),
+            StringRelationBuilder::default()
+                ._id(20)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_003.into())
+                ._uid(5)
+                ._user("bob".into())
+                ._tags(smallvec::smallvec![("type".into(), "route".into())])
+                ._members(vec![
+                    (OSMObjectType::Way, 10, "outer".into()),
+                    (OSMObjectType::Node, 1, "".into()),
+                ])
+                .build()
+                .unwrap()
+                .into(),
+        ];
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        for obj in objs.iter() {
+            writer.write_obj(obj).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        let mut reader = PBFReader::new(output.as_slice());
+        let blocks: Vec<PrimitiveBlockView> = reader.blocks().collect();
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[0].object_type(), Some(OSMObjectType::Node));
+        assert_eq!(blocks[0].len(), 2);
+
+        let nodes: Vec<NodeRef> = blocks[0].nodes().collect();
+        assert_eq!(nodes[0].tag("amenity"), Some("bench"));
+        assert_eq!(nodes[0].user(), Some("alice"));
+        assert_eq!(nodes[1].tags().len(), 0);
+        assert_eq!(nodes[1].lat_lon(), Some((Lat(-515000000), Lon(1000000))));
+        let way = blocks[1].ways().next().unwrap();
+        assert_eq!(way.nodes(), [1, 5, 1]);
+        assert!(way.has_tag("highway"));
+        let relation = blocks[2].relations().next().unwrap();
+        assert_eq!(
+            relation.members().nth(1),
+            Some((OSMObjectType::Node, 1, ""))
+        );
+
+        // Generic code which only needs getters
+        let filter: TagFilter = "w/highway".parse().unwrap();
+        assert!(filter.matches(&way));
+        assert!(!filter.matches(&nodes[0]));
+        let mut store = HashMapStore::default();
+        for node in nodes.iter() {
+            store.set(node.id(), node.lat_lon().unwrap()).unwrap();
+        }
+        assert_eq!(
+            way_coords(&way, &store).unwrap(),
+            [
+                nodes[0].lat_lon().unwrap(),
+                nodes[1].lat_lon().unwrap(),
+                nodes[0].lat_lon().unwrap()
+            ]
+        );
+        assert_eq!(RelationRead::members(&relation).len(), 2);
+
+        let built: Vec<StringOSMObj> = nodes
+            .into_iter()
+            .map(StringOSMObj::from)
+            .chain(std::iter::once(way.into()))
+            .chain(std::iter::once(relation.into()))
+            .collect();
+        assert_eq!(built, objs);
+
+        let arc: Vec<_> = ArcPBFReader::new(output.as_slice()).objects().collect();
+        assert_eq!(
+            blocks[1].ways().next().unwrap().build(&mut ArcObjects),
+            arc[2]
+        );
+    }
+}
@@ -0,0 +1,575 @@
+use super::{fileformat, osmformat};
+use crate::{BBox, Lat, Lon, Node, OSMObj, OSMObjBase, OSMObjectType, Relation, Way, WayRead};
+use crate::{COORD_PRECISION_NANOS, OSMWriteError, OSMWriter, WrongNumberOfLocations, version};
+
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use protobuf::{EnumOrUnknown, Message, MessageField};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// Maximum number of objects in one block. Osmium & osmosis use 8,000.
+const MAX_BLOCK_OBJECTS: usize = 8_000;
+
+#[derive(PartialEq)]
+enum State {
+    Initial,
+    WritingObjects,
+    Closed,
+}
+
+/// Writes PBF files.
+///
+/// Nodes are written as dense nodes. Each block only has one object type, so files are smaller if
+/// the objects are sorted by type.
+///
+/// Call [`close`](OSMWriter::close) when finished, to see any errors. Dropping the writer closes
+/// it too, but ignores errors.
+pub struct PBFWriter<W: Write> {
+    /// Only `None` after `into_inner`
+    writer: Option<W>,
+    bbox: Option<BBox>,
+    locations_on_ways: bool,
+    block: BlockBuilder,
+    _state: State,
+}
+
+impl<W: Write> PBFWriter<W> {
+    /// Store node locations on ways (the `LocationsOnWays` feature).
+    ///
+    /// Ways are written with their [`node_locations`](WayRead::node_locations), or the locations
+    /// given to [`write_way_with_locations`](Self::write_way_with_locations). Must be set before
+    /// any objects are written.
+    pub fn set_locations_on_ways(&mut self, locations_on_ways: bool) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => {
+                self.locations_on_ways = locations_on_ways;
+                Ok(())
+            }
+            State::Closed => Err(OSMWriteError::AlreadyClosed),
+            _ => Err(OSMWriteError::AlreadyStarted),
+        }
+    }
+
+    /// Store this bounding box in the file header. Must be set before any objects are written.
+    pub fn set_bbox(&mut self, bbox: BBox) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => {
+                self.bbox = Some(bbox);
+                Ok(())
+            }
+            State::Closed => Err(OSMWriteError::AlreadyClosed),
+            _ => Err(OSMWriteError::AlreadyStarted),
+        }
+    }
+
+    /// Write a way, with these node locations (one per node). Errors unless
+    /// [`set_locations_on_ways`](Self::set_locations_on_ways) is on.
+    pub fn write_way_with_locations(
+        &mut self,
+        way: &impl Way,
+        locations: &[(Lat, Lon)],
+    ) -> Result<(), OSMWriteError> {
+        if !self.locations_on_ways {
+            return Err(OSMWriteError::LocationsOnWaysDisabled);
+        }
+        WrongNumberOfLocations::check(way.num_nodes(), locations.len())?;
+        self.start_object(OSMObjectType::Way)?;
+        self.block.add_way(way, Some(locations));
+        Ok(())
+    }
+
+    fn ensure_header(&mut self) -> Result<(), OSMWriteError> {
+        if self._state == State::Initial {
+            let mut header = osmformat::HeaderBlock::new();
+            header.required_features = vec!["OsmSchema-V0.6".into(), "DenseNodes".into()];
+            if self.locations_on_ways {
+                header.optional_features.push("LocationsOnWays".into());
+            }
+            header.writingprogram = Some(format!("osmio/{}", version()));
+            if let Some(bbox) = &self.bbox {
+                let nanos = |inner: i32| inner as i64 * COORD_PRECISION_NANOS as i64;
+                let mut header_bbox = osmformat::HeaderBBox::new();
+                header_bbox.left = Some(nanos(bbox.min_lon.inner()));
+                header_bbox.right = Some(nanos(bbox.max_lon.inner()));
+                header_bbox.top = Some(nanos(bbox.max_lat.inner()));
+                header_bbox.bottom = Some(nanos(bbox.min_lat.inner()));
+                header.bbox = MessageField::some(header_bbox);
+            }
+            write_blob(self.writer(), "OSMHeader", &header)?;
+            self._state = State::WritingObjects;
+        }
+        Ok(())
+    }
+
+    /// Get ready to add an object of this type, writing out the current block if needed.
+    fn start_object(&mut self, object_type: OSMObjectType) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => self.ensure_header()?,
+            State::WritingObjects => {}
+            State::Closed => return Err(OSMWriteError::AlreadyClosed),
+        }
+        if self.block.object_type.is_some_and(|t| t != object_type)
+            || self.block.len >= MAX_BLOCK_OBJECTS
+        {
+            self.write_block()?;
+        }
+        self.block.object_type = Some(object_type);
+        self.block.len += 1;
+        Ok(())
+    }
+
+    fn writer(&mut self) -> &mut W {
+        self.writer
+            .as_mut()
+            .expect("writer is only taken by into_inner")
+    }
+
+    fn write_block(&mut self) -> Result<(), OSMWriteError> {
+        if self.block.len > 0 {
+            let block = std::mem::take(&mut self.block).finish();
+            write_blob(self.writer(), "OSMData", &block)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> OSMWriter<W> for PBFWriter<W> {
+    fn new(writer: W) -> Self {
+        PBFWriter {
+            writer: Some(writer),
+            bbox: None,
+            locations_on_ways: false,
+            block: BlockBuilder::default(),
+            _state: State::Initial,
+        }
+    }
+
+    fn set_header(&mut self, _key_value: (&str, &str)) -> Result<(), OSMWriteError> {
+        Err(OSMWriteError::FormatDoesntSupportHeaders)
+    }
+
+    fn is_open(&self) -> bool {
+        self._state != State::Closed
+    }
+
+    fn close(&mut self) -> Result<(), OSMWriteError> {
+        if self._state == State::Closed {
+            return Ok(());
+        }
+        self.ensure_header()?;
+        self.write_block()?;
+        self.writer().flush()?;
+        self._state = State::Closed;
+        Ok(())
+    }
+
+    fn write_obj(&mut self, obj: &impl OSMObj) -> Result<(), OSMWriteError> {
+        let locations = obj
+            .as_way()
+            .and_then(|way| way.node_locations())
+            .filter(|_| self.locations_on_ways);
+        if let (Some(way), Some(locations)) = (obj.as_way(), locations) {
+            WrongNumberOfLocations::check(way.num_nodes(), locations.len())?;
+        }
+        self.start_object(obj.object_type())?;
+        if let Some(node) = obj.as_node() {
+            self.block.add_node(node);
+        } else if let Some(way) = obj.as_way() {
+            self.block.add_way(way, locations);
+        } else if let Some(relation) = obj.as_relation() {
+            self.block.add_relation(relation);
+        }
+        Ok(())
+    }
+
+    /// Closes the writer first. Errors are ignored, so call `close` before this to see them.
+    fn into_inner(mut self) -> W {
+        let _ = self.close();
+        self.writer.take().unwrap()
+    }
+}
+
+/// Closes the writer, ignoring errors
+impl<W: Write> Drop for PBFWriter<W> {
+    fn drop(&mut self) {
+        if self.writer.is_some() {
+            let _ = self.close();
+        }
+    }
+}
+
+/// Write one (zlib compressed) blob
+fn write_blob(
+    writer: &mut impl Write,
+    blob_type: &str,
+    message: &impl Message,
+) -> std::io::Result<()> {
+    let raw = message.write_to_bytes().map_err(std::io::Error::other)?;
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&raw)?;
+
+    let mut blob = fileformat::Blob::new();
+    blob.raw_size = Some(raw.len() as i32);
+    blob.zlib_data = Some(encoder.finish()?);
+    let blob = blob.write_to_bytes().map_err(std::io::Error::other)?;
+
+    let mut blob_header = fileformat::BlobHeader::new();
+    blob_header.set_type(blob_type.to_string());
+    blob_header.set_datasize(blob.len() as i32);
+    let blob_header = blob_header
+        .write_to_bytes()
+        .map_err(std::io::Error::other)?;
+
+    writer.write_all(&(blob_header.len() as u32).to_be_bytes())?;
+    writer.write_all(&blob_header)?;
+    writer.write_all(&blob)?;
+    Ok(())
+}
+
+/// Coordinates are written with the default granularity of 100 nanodegrees, which is our internal
+/// precision, so no conversion is needed.
+const GRANULARITY: i32 = COORD_PRECISION_NANOS;
+
+/// A block of objects (all the same type) being built up
+#[derive(Default)]
+struct BlockBuilder {
+    object_type: Option<OSMObjectType>,
+    len: usize,
+    strings: Vec<Vec<u8>>,
+    string_ids: HashMap<String, u32>,
+    dense: osmformat::DenseNodes,
+    dense_info: osmformat::DenseInfo,
+    dense_deleted: Vec<bool>,
+    // (id, lat, lon, timestamp, changeset, uid, user_sid) of the previous dense node
+    dense_last: (i64, i64, i64, i64, i64, i32, i32),
+    ways: Vec<osmformat::Way>,
+    relations: Vec<osmformat::Relation>,
+}
+
+impl BlockBuilder {
+    fn string_id(&mut self, s: &str) -> u32 {
+        if self.strings.is_empty() {
+            // string 0 is never used, since it's the dense node tag separator
+            self.strings.push(Vec::new());
+        }
+        if let Some(&id) = self.string_ids.get(s) {
+            return id;
+        }
+        let id = self.strings.len() as u32;
+        self.strings.push(s.as_bytes().to_vec());
+        self.string_ids.insert(s.to_string(), id);
+        id
+    }
+
+    fn info(&mut self, obj: &impl OSMObjBase) -> osmformat::Info {
+        let mut info = osmformat::Info::new();
+        info.version = Some(obj.version().unwrap_or(0) as i32);
+        info.timestamp = Some(obj.timestamp().map_or(0, |t| t.epoch_secs()));
+        info.changeset = Some(obj.changeset_id().unwrap_or(0) as i64);
+        info.uid = Some(obj.uid().unwrap_or(0) as i32);
+        info.user_sid = Some(self.string_id(obj.user().unwrap_or("")));
+        if obj.deleted() {
+            info.visible = Some(false);
+        }
+        info
+    }
+
+    fn tags(&mut self, obj: &impl OSMObjBase) -> (Vec<u32>, Vec<u32>) {
+        obj.tags()
+            .map(|(k, v)| (self.string_id(k), self.string_id(v)))
+            .unzip()
+    }
+
+    fn add_node(&mut self, node: &impl Node) {
+        let (lat, lon) = node.lat_lon().map_or((0, 0), |(lat, lon)| {
+            (lat.inner() as i64, lon.inner() as i64)
+        });
+        let timestamp = node.timestamp().map_or(0, |t| t.epoch_secs());
+        let changeset = node.changeset_id().unwrap_or(0) as i64;
+        let uid = node.uid().unwrap_or(0) as i32;
+        let user_sid = self.string_id(node.user().unwrap_or("")) as i32;
+        let last = self.dense_last;
+
+        self.dense.id.push(node.id() - last.0);
+        self.dense.lat.push(lat - last.1);
+        self.dense.lon.push(lon - last.2);
+        for (k, v) in node.tags() {
+            let (k, v) = (self.string_id(k), self.string_id(v));
+            self.dense.keys_vals.push(k as i32);
+            self.dense.keys_vals.push(v as i32);
+        }
+        self.dense.keys_vals.push(0);
+
+        self.dense_info
+            .version
+            .push(node.version().unwrap_or(0) as i32);
+        self.dense_info.timestamp.push(timestamp - last.3);
+        self.dense_info.changeset.push(changeset - last.4);
+        self.dense_info.uid.push(uid - last.5);
+        self.dense_info.user_sid.push(user_sid - last.6);
+        self.dense_deleted.push(node.deleted());
+
+        self.dense_last = (node.id(), lat, lon, timestamp, changeset, uid, user_sid);
+    }
+
+    fn add_way(&mut self, way: &impl Way, locations: Option<&[(Lat, Lon)]>) {
+        let mut pbf_way = osmformat::Way::new();
+        pbf_way.id = Some(way.id());
+        (pbf_way.keys, pbf_way.vals) = self.tags(way);
+        pbf_way.info = MessageField::some(self.info(way));
+        pbf_way.refs = delta_encode(way.nodes().iter().copied());
+        if let Some(locations) = locations {
+            pbf_way.lat = delta_encode(locations.iter().map(|(lat, _)| lat.inner() as i64));
+            pbf_way.lon = delta_encode(locations.iter().map(|(_, lon)| lon.inner() as i64));
+        }
+        self.ways.push(pbf_way);
+    }
+
+    fn add_relation(&mut self, relation: &impl Relation) {
+        let mut pbf_relation = osmformat::Relation::new();
+        pbf_relation.id = Some(relation.id());
+        (pbf_relation.keys, pbf_relation.vals) = self.tags(relation);
+        pbf_relation.info = MessageField::some(self.info(relation));
+        let mut member_ids = Vec::new();
+        for (member_type, member_id, role) in relation.members() {
+            pbf_relation.roles_sid.push(self.string_id(role) as i32);
+            member_ids.push(member_id);
+            pbf_relation
+                .types
+                .push(EnumOrUnknown::new(match member_type {
+                    OSMObjectType::Node => osmformat::relation::MemberType::NODE,
+                    OSMObjectType::Way => osmformat::relation::MemberType::WAY,
+                    OSMObjectType::Relation => osmformat::relation::MemberType::RELATION,
+                }));
+        }
+        pbf_relation.memids = delta_encode(member_ids.into_iter());
+        self.relations.push(pbf_relation);
+    }
+
+    fn finish(mut self) -> osmformat::PrimitiveBlock {
+        let mut group = osmformat::PrimitiveGroup::new();
+        match self.object_type {
+            Some(OSMObjectType::Node) => {
+                if self.dense_deleted.iter().any(|&d| d) {
+                    self.dense_info.visible = self.dense_deleted.iter().map(|d| !d).collect();
+                }
+                self.dense.denseinfo = MessageField::some(self.dense_info);
+                group.dense = MessageField::some(self.dense);
+            }
+            Some(OSMObjectType::Way) => group.ways = self.ways,
+            Some(OSMObjectType::Relation) => group.relations = self.relations,
+            None => {}
+        }
+
+        let mut stringtable = osmformat::StringTable::new();
+        stringtable.s = self.strings;
+        if stringtable.s.is_empty() {
+            stringtable.s.push(Vec::new());
+        }
+
+        let mut block = osmformat::PrimitiveBlock::new();
+        block.stringtable = MessageField::some(stringtable);
+        block.primitivegroup.push(group);
+        block.granularity = Some(GRANULARITY);
+        block.date_granularity = Some(1000);
+        block
+    }
+}
+
+fn delta_encode(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut last = 0;
+    values
+        .map(|v| {
+            let delta = v - last;
+            last = v;
+            delta
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ObjId;
+    use crate::obj_types::{
+        ArcOSMObj, RcOSMObj, StringNodeBuilder, StringOSMObj, StringRelationBuilder,
+        StringWayBuilder,
+    };
+    use crate::pbf::PBFReader;
+    use crate::stringpbf::{ArcPBFReader, RcPBFReader};
+    use crate::{OSMObj, OSMObjBaseRead, OSMReader};
+
+    #[test]
+    fn write_then_read() {
+        let objs: Vec<StringOSMObj> = vec![
+            StringNodeBuilder::default()
+                ._id(1)
+                ._version(2)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_000.into())
+                ._uid(4)
+                ._user("alice".into())
+                ._lat_lon((Lat(515000000), Lon(-1000000)))
+                ._tags(smallvec::smallvec![("amenity".into(), "bench".into())])
+                .build()
+                .unwrap()
+                .into(),
+            StringNodeBuilder::default()
+                ._id(5)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_001.into())
+                ._uid(4)
+                ._user("alice".into())
+                ._lat_lon((Lat(-515000000), Lon(1000000)))
+                .build()
+                .unwrap()
+                .into(),
+            StringWayBuilder::default()
+                ._id(10)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_002.into())
+                ._uid(5)
+                ._user("bob".into())
+                ._nodes(smallvec::smallvec![1, 5, 1])
+                ._tags(smallvec::smallvec![("highway".into(), "path".into())])
+                .build()
+                .unwrap()
+                .into(),
+            StringRelationBuilder::default()
+                ._id(20)
+                ._version(1)
+                ._changeset_id(3)
+                ._timestamp(1_600_000_003.into())
+                ._uid(5)
+                ._user("bob".into())
+                ._members(vec![
+                    (OSMObjectType::Way, 10, "outer".into()),
+                    (OSMObjectType::Node, 1, "".into()),
+                ])
+                .build()
+                .unwrap()
+                .into(),
+        ];
+
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        for obj in objs.iter() {
+            writer.write_obj(obj).unwrap();
+        }
+        writer.close().unwrap();
+        drop(writer);
+
+        let read: Vec<StringOSMObj> = PBFReader::new(output.as_slice()).objects().collect();
+        assert_eq!(read, objs);
+
+        // Same decoder, other object types
+        let read: Vec<ArcOSMObj> = ArcPBFReader::new(output.as_slice()).objects().collect();
+        assert_eq!(
+            read,
+            objs.iter()
+                .cloned()
+                .map(ArcOSMObj::from)
+                .collect::<Vec<_>>()
+        );
+        let read: Vec<RcOSMObj> = RcPBFReader::new(output.as_slice()).objects().collect();
+        assert_eq!(
+            read,
+            objs.iter().cloned().map(RcOSMObj::from).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn locations_on_ways() {
+        let mut way = StringWayBuilder::default()
+            ._id(10)
+            ._version(1)
+            ._changeset_id(3)
+            ._timestamp(1_600_000_002.into())
+            ._uid(5)
+            ._user("bob".into())
+            ._nodes(smallvec::smallvec![1, 5])
+            .build()
+            .unwrap();
+        let locations = vec![(Lat(515000000), Lon(-1000000)), (Lat(-1), Lon(1))];
+
+        let mut output = Vec::new();
+        let mut writer = PBFWriter::new(&mut output);
+        writer.set_locations_on_ways(true).unwrap();
+        writer.write_way_with_locations(&way, &locations).unwrap();
+        writer.close().unwrap();
+        drop(writer);
+
+        let read = PBFReader::new(output.as_slice()).next().unwrap();
+        way.set_node_locations(Some(locations.clone())).unwrap();
+        assert_eq!(
+            read.as_way().unwrap().node_locations(),
+            Some(&locations[..])
+        );
+        assert_eq!(read, way.clone().into());
+        let read = RcPBFReader::new(output.as_slice()).next().unwrap();
+        assert_eq!(
+            read.as_way().unwrap().node_locations(),
+            Some(&locations[..])
+        );
+
+        // One location per node
+        assert_eq!(
+            way.set_node_locations(Some(locations[..1].to_vec())),
+            Err(WrongNumberOfLocations {
+                nodes: 2,
+                locations: 1
+            })
+        );
+        assert_eq!(way.node_locations(), Some(&locations[..]));
+        let mut writer = PBFWriter::new(Vec::new());
+        writer.set_locations_on_ways(true).unwrap();
+        assert!(matches!(
+            writer.write_way_with_locations(&way, &locations[..1]),
+            Err(OSMWriteError::WrongNumberOfLocations(_))
+        ));
+        let mut writer = PBFWriter::new(Vec::new());
+        assert!(matches!(
+            writer.write_way_with_locations(&way, &locations),
+            Err(OSMWriteError::LocationsOnWaysDisabled)
+        ));
+    }
+
+    #[test]
+    fn timestamps_after_2038() {
+        let node = |id: ObjId, timestamp: i64| -> StringOSMObj {
+            StringNodeBuilder::default()
+                ._id(id)
+                ._version(1)
+                ._changeset_id(1)
+                ._timestamp(timestamp.into())
+                ._uid(1)
+                ._user("u".into())
+                ._lat_lon((Lat(1), Lon(1)))
+                .build()
+                .unwrap()
+                .into()
+        };
+        let objs = vec![
+            node(1, 1_600_000_000),
+            node(2, 4_102_444_800),
+            node(3, 4_102_444_801),
+        ];
+
+        // into_inner writes the last block
+        let mut writer = PBFWriter::new(Vec::new());
+        for obj in objs.iter() {
+            writer.write_obj(obj).unwrap();
+        }
+        let output = writer.into_inner();
+
+        let read: Vec<StringOSMObj> = PBFReader::new(output.as_slice()).objects().collect();
+        assert_eq!(read, objs);
+        assert_eq!(
+            read[2].timestamp().unwrap().to_string(),
+            "2100-01-01T00:00:01Z"
+        );
+    }
+}
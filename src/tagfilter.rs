@@ -0,0 +1,320 @@
+//! Filter objects by their tags, with `osmium tags-filter` style expressions
+//!
+//! An expression is `[!][TYPES/]KEY[=VALUE[,VALUE…]]` or `[!][TYPES/]KEY!=VALUE[,VALUE…]`:
+//!
+//! * `TYPES` is some of `n`, `w` & `r` (e.g. `nw/`). Only those object types can match. Default
+//!   is all types.
+//! * `KEY` & `VALUE`s can end with `*` to match anything starting with that.
+//! * `KEY` on its own matches objects with that tag. `KEY=VALUE,…` matches if the value is one
+//!   of those, and `KEY!=VALUE,…` matches if the object has that tag, with another value.
+//! * A leading `!` inverts the tag match (the type still has to match).
+//!
+//! A [`TagFilter`] is a list of expressions, and matches if any expression matches.
+//!
+//! ```
+//! use osmio::tagfilter::TagFilter;
+//! use osmio::obj_types::StringWayBuilder;
+//! let filter: TagFilter = "w/highway=primary,secondary".parse()?;
+//! let way = StringWayBuilder::default()
+//!     ._id(1)
+//!     ._tags(smallvec::smallvec![("highway".into(), "primary".into())])
+//!     .build()?;
+//! assert!(filter.matches(&way));
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use crate::{OSMObjBaseRead, OSMObjectType};
+
+use anyhow::{Result, bail};
+use std::fmt;
+use std::str::FromStr;
+
+/// A key or value to match
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pattern {
+    Exact(String),
+    Prefix(String),
+}
+
+impl Pattern {
+    fn new(s: &str) -> Self {
+        match s.strip_suffix('*') {
+            Some(prefix) => Pattern::Prefix(prefix.to_string()),
+            None => Pattern::Exact(s.to_string()),
+        }
+    }
+
+    fn matches(&self, s: &str) -> bool {
+        match self {
+            Pattern::Exact(exact) => s == exact,
+            Pattern::Prefix(prefix) => s.starts_with(prefix.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pattern::Exact(s) => write!(f, "{}", s),
+            Pattern::Prefix(s) => write!(f, "{}*", s),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ValueMatch {
+    Any,
+    OneOf(Vec<Pattern>),
+    NoneOf(Vec<Pattern>),
+}
+
+/// One tag filter expression, e.g. `w/highway=primary`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagFilterExpr {
+    /// nodes, ways, relations
+    types: (bool, bool, bool),
+    inverted: bool,
+    key: Pattern,
+    values: ValueMatch,
+}
+
+impl TagFilterExpr {
+    /// Does this object match?
+    pub fn matches(&self, obj: &impl OSMObjBaseRead) -> bool {
+        let type_matches = match obj.object_type() {
+            OSMObjectType::Node => self.types.0,
+            OSMObjectType::Way => self.types.1,
+            OSMObjectType::Relation => self.types.2,
+        };
+        if !type_matches {
+            return false;
+        }
+        let tag_matches = match &self.key {
+            Pattern::Exact(key) => obj.tag(key).is_some_and(|v| self.value_matches(v)),
+            Pattern::Prefix(_) => obj
+                .tags()
+                .any(|(k, v)| self.key.matches(k) && self.value_matches(v)),
+        };
+        tag_matches != self.inverted
+    }
+
+    fn value_matches(&self, value: &str) -> bool {
+        match &self.values {
+            ValueMatch::Any => true,
+            ValueMatch::OneOf(values) => values.iter().any(|p| p.matches(value)),
+            ValueMatch::NoneOf(values) => !values.iter().any(|p| p.matches(value)),
+        }
+    }
+}
+
+impl FromStr for TagFilterExpr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rest = s.trim();
+        let mut inverted = false;
+        if let Some(r) = rest.strip_prefix('!') {
+            inverted = true;
+            rest = r;
+        }
+
+        let mut types = (true, true, true);
+        if let Some((prefix, r)) = rest.split_once('/')
+            && !prefix.is_empty()
+            && prefix.chars().all(|c| matches!(c, 'n' | 'w' | 'r'))
+        {
+            types = (
+                prefix.contains('n'),
+                prefix.contains('w'),
+                prefix.contains('r'),
+            );
+            rest = r;
+            if let Some(r) = rest.strip_prefix('!') {
+                inverted = true;
+                rest = r;
+            }
+        }
+
+        let (key, values) = if let Some((key, values)) = rest.split_once("!=") {
+            (
+                key,
+                ValueMatch::NoneOf(values.split(',').map(Pattern::new).collect()),
+            )
+        } else if let Some((key, values)) = rest.split_once('=') {
+            (
+                key,
+                ValueMatch::OneOf(values.split(',').map(Pattern::new).collect()),
+            )
+        } else {
+            (rest, ValueMatch::Any)
+        };
+        if key.is_empty() {
+            bail!("Tag filter expression {:?} has no key", s);
+        }
+
+        Ok(TagFilterExpr {
+            types,
+            inverted,
+            key: Pattern::new(key),
+            values,
+        })
+    }
+}
+
+impl fmt::Display for TagFilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.inverted {
+            write!(f, "!")?;
+        }
+        if self.types != (true, true, true) {
+            for (included, c) in [
+                (self.types.0, 'n'),
+                (self.types.1, 'w'),
+                (self.types.2, 'r'),
+            ] {
+                if included {
+                    write!(f, "{}", c)?;
+                }
+            }
+            write!(f, "/")?;
+        }
+        write!(f, "{}", self.key)?;
+        let join = |values: &[Pattern]| {
+            values
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        match &self.values {
+            ValueMatch::Any => Ok(()),
+            ValueMatch::OneOf(values) => write!(f, "={}", join(values)),
+            ValueMatch::NoneOf(values) => write!(f, "!={}", join(values)),
+        }
+    }
+}
+
+/// Some tag filter expressions. Objects match if they match any expression.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TagFilter {
+    exprs: Vec<TagFilterExpr>,
+}
+
+impl TagFilter {
+    /// Parse each of these expressions.
+    pub fn parse_all(exprs: impl IntoIterator<Item = impl AsRef<str>>) -> Result<Self> {
+        let exprs = exprs
+            .into_iter()
+            .map(|e| e.as_ref().parse())
+            .collect::<Result<_>>()?;
+        Ok(TagFilter { exprs })
+    }
+
+    pub fn push(&mut self, expr: TagFilterExpr) {
+        self.exprs.push(expr);
+    }
+
+    pub fn exprs(&self) -> &[TagFilterExpr] {
+        &self.exprs
+    }
+
+    /// Does this object match any of the expressions?
+    pub fn matches(&self, obj: &impl OSMObjBaseRead) -> bool {
+        self.exprs.iter().any(|e| e.matches(obj))
+    }
+
+    /// Only the objects from `objects` which match.
+    ///
+    /// e.g. `filter.filter(reader.objects())`
+    pub fn filter<'a, O, I>(&'a self, objects: I) -> impl Iterator<Item = O> + 'a
+    where
+        O: OSMObjBaseRead,
+        I: IntoIterator<Item = O>,
+        I::IntoIter: 'a,
+    {
+        objects.into_iter().filter(move |o| self.matches(o))
+    }
+}
+
+/// One expression
+impl FromStr for TagFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(TagFilter {
+            exprs: vec![s.parse()?],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::obj_types::{StringNodeBuilder, StringOSMObj, StringWayBuilder};
+
+    fn node(tags: &[(&str, &str)]) -> StringOSMObj {
+        StringNodeBuilder::default()
+            ._id(1)
+            ._tags(tags.iter().map(|&(k, v)| (k.into(), v.into())).collect())
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    fn way(tags: &[(&str, &str)]) -> StringOSMObj {
+        StringWayBuilder::default()
+            ._id(1)
+            ._tags(tags.iter().map(|&(k, v)| (k.into(), v.into())).collect())
+            .build()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn expressions() {
+        let f: TagFilterExpr = "w/highway=primary,secondary".parse().unwrap();
+        assert!(f.matches(&way(&[("highway", "primary")])));
+        assert!(f.matches(&way(&[("highway", "secondary")])));
+        assert!(!f.matches(&way(&[("highway", "service")])));
+        assert!(!f.matches(&node(&[("highway", "primary")])));
+
+        let f: TagFilterExpr = "nwr/name".parse().unwrap();
+        assert!(f.matches(&node(&[("name", "x")])));
+        assert!(!f.matches(&way(&[("name:en", "x")])));
+
+        let f: TagFilterExpr = "!amenity=parking".parse().unwrap();
+        assert!(f.matches(&node(&[])));
+        assert!(f.matches(&node(&[("amenity", "bench")])));
+        assert!(!f.matches(&way(&[("amenity", "parking")])));
+
+        let f: TagFilterExpr = "highway!=motorway*".parse().unwrap();
+        assert!(f.matches(&way(&[("highway", "primary")])));
+        assert!(!f.matches(&way(&[("highway", "motorway_link")])));
+        assert!(!f.matches(&way(&[])));
+
+        let f: TagFilterExpr = "n/addr:*".parse().unwrap();
+        assert!(f.matches(&node(&[("addr:street", "Main Street")])));
+        assert!(!f.matches(&node(&[("address", "x")])));
+
+        for expr in [
+            "w/highway=primary,secondary",
+            "!nr/addr:*",
+            "highway!=motorway*",
+            "name",
+        ] {
+            assert_eq!(expr.parse::<TagFilterExpr>().unwrap().to_string(), expr);
+        }
+        assert!("w/".parse::<TagFilterExpr>().is_err());
+        assert!("=x".parse::<TagFilterExpr>().is_err());
+    }
+
+    #[test]
+    fn filter() {
+        let filter = TagFilter::parse_all(["r/type=multipolygon", "w/building"]).unwrap();
+        let objs = vec![
+            way(&[("building", "yes")]),
+            node(&[("building", "yes")]),
+            way(&[("type", "multipolygon")]),
+        ];
+        assert_eq!(filter.filter(objs).count(), 1);
+    }
+}
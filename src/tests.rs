@@ -21,7 +21,7 @@ fn string_to_objtype() {
     assert!(" ".parse::<OSMObjectType>().is_err());
 }
 
-mod timestamp_format {
+mod timestamp {
     use super::*;
     use std::cmp::Ordering::*;
     use std::cmp::*;
@@ -30,8 +30,8 @@ mod timestamp_format {
         ( $name:ident, $a:expr_2021, $b:expr_2021, $expected_ord:expr_2021 ) => {
             #[test]
             fn $name() {
-                let a: TimestampFormat = $a;
-                let b: TimestampFormat = $b;
+                let a: Timestamp = $a;
+                let b: Timestamp = $b;
                 assert_eq!(a.partial_cmp(&b), Some($expected_ord));
             }
         };
@@ -75,15 +75,15 @@ mod timestamp_format {
 
     assert_cmp!(
         int_iso3,
-        TimestampFormat::EpochNumber(1577836800),
-        TimestampFormat::ISOString("2020-01-01T00:00:00Z".to_string()),
+        Timestamp::from_epoch_secs(1577836800),
+        "2020-01-01T01:00:00+01:00".parse().unwrap(),
         Equal
     );
     assert_cmp!(
         int_iso4,
-        TimestampFormat::ISOString("2020-01-01T00:00:00Z".to_string()),
-        TimestampFormat::EpochNumber(1577836800),
-        Equal
+        "2100-01-01T00:00:00Z".parse().unwrap(),
+        Timestamp::from_epoch_secs(i32::MAX as i64),
+        Greater
     );
 }
 
@@ -163,3 +163,68 @@ fn area_ways() {
         </osm>"#;
     assert_closed_area!(closed_explicit_area_input, true, true);
 }
+
+mod sorted_assumption {
+    use super::*;
+    use crate::xml::XMLReader;
+
+    const UNSORTED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="1" lon="1"/>
+  <way id="10"><nd ref="1"/></way>
+  <node id="2" lat="1" lon="1"/>
+  <relation id="20"/>
+</osm>"#;
+
+    #[test]
+    fn checked_objects() {
+        let mut reader = XMLReader::new(UNSORTED.as_bytes());
+        let results: Vec<_> = reader.checked_objects().collect();
+        assert_eq!(results.len(), 3);
+        assert!(results[1].is_ok());
+        let err = results[2].as_ref().unwrap_err();
+        assert_eq!(err.current, (OSMObjectType::Node, 2, None));
+
+        // Not checked by default
+        let mut reader = XMLReader::new(UNSORTED.as_bytes());
+        assert_eq!(reader.objects().count(), 4);
+    }
+
+    #[test]
+    fn unsorted_detected_if_assumed_sorted() {
+        let mut reader = XMLReader::new(UNSORTED.as_bytes());
+        reader.assume_sorted();
+        // Stops before node 2
+        assert_eq!(reader.objects().count(), 2);
+        let err = reader.unsorted_error().unwrap();
+        assert_eq!(err.previous, (OSMObjectType::Way, 10, None));
+        assert_eq!(err.current, (OSMObjectType::Node, 2, None));
+        assert!(reader.next().is_none());
+
+        let mut reader = XMLReader::new(UNSORTED.as_bytes());
+        reader.assume_sorted();
+        assert_eq!(reader.nodes().count(), 1);
+        assert_eq!(reader.ways().count(), 1);
+        assert!(reader.unsorted_error().is_some());
+        assert_eq!(reader.relations().count(), 0);
+    }
+
+    #[test]
+    fn stop_early() {
+        let sorted = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6">
+  <node id="1" lat="1" lon="1"/>
+  <node id="2" lat="1" lon="1"/>
+  <way id="10"><nd ref="1"/></way>
+  <way id="11"><nd ref="2"/></way>
+  <relation id="20"/>
+</osm>"#;
+        let mut reader = XMLReader::new(sorted.as_bytes());
+        reader.assume_sorted();
+        assert_eq!(reader.nodes().count(), 2);
+        // The first way is put back by nodes()
+        assert_eq!(reader.ways().count(), 2);
+        assert_eq!(reader.relations().count(), 1);
+        assert!(reader.unsorted_error().is_none());
+    }
+}
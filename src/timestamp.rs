@@ -0,0 +1,232 @@
+//! Timestamps of OSM objects & files
+//!
+//! A [`Timestamp`] is whole seconds since the unix epoch (like in PBF files), and is shown as an
+//! ISO 8601 string in UTC (like in XML files), e.g. `2020-01-01T00:00:00Z`.
+//!
+//! ```
+//! use osmio::Timestamp;
+//! let t: Timestamp = "2020-01-01T00:00:00Z".parse()?;
+//! assert_eq!(t.epoch_secs(), 1577836800);
+//! assert_eq!(t, Timestamp::from(1577836800));
+//! assert_eq!(t.to_string(), "2020-01-01T00:00:00Z");
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// A point in time, as whole seconds since the unix epoch
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    pub fn from_epoch_secs(secs: i64) -> Self {
+        Timestamp(secs)
+    }
+
+    /// Any fractional seconds are dropped
+    pub fn from_datetime(dt: DateTime<Utc>) -> Self {
+        Timestamp(dt.timestamp())
+    }
+
+    /// Seconds since the unix epoch
+    pub fn epoch_secs(&self) -> i64 {
+        self.0
+    }
+
+    /// The current time, to the second
+    pub fn now() -> Self {
+        Self::from_datetime(Utc::now())
+    }
+
+    /// As a chrono `DateTime`. `None` if it's outside chrono's range.
+    pub fn to_datetime(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.0, 0)
+    }
+
+    pub fn to_iso_string(&self) -> String {
+        self.to_string()
+    }
+
+    #[deprecated(note = "Use epoch_secs()")]
+    pub fn to_epoch_number(&self) -> i64 {
+        self.0
+    }
+}
+
+impl<T> From<T> for Timestamp
+where
+    T: Into<i64>,
+{
+    fn from(v: T) -> Self {
+        Timestamp(v.into())
+    }
+}
+
+/// A string which isn't a valid timestamp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimestampError(chrono::ParseError);
+
+impl fmt::Display for ParseTimestampError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid timestamp: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseTimestampError {}
+
+/// Parse `YYYY-MM-DDTHH:MM:SSZ` without going through chrono's generic parser
+fn parse_simple_iso(s: &str) -> Option<Timestamp> {
+    let b = s.as_bytes();
+    if b.len() != 20
+        || b[4] != b'-'
+        || b[7] != b'-'
+        || b[10] != b'T'
+        || b[13] != b':'
+        || b[16] != b':'
+        || b[19] != b'Z'
+    {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| -> Option<u32> {
+        b[range].iter().try_fold(0, |acc, &c| {
+            c.is_ascii_digit().then(|| acc * 10 + (c - b'0') as u32)
+        })
+    };
+    let datetime = NaiveDate::from_ymd_opt(num(0..4)? as i32, num(5..7)?, num(8..10)?)?
+        .and_hms_opt(num(11..13)?, num(14..16)?, num(17..19)?)?;
+    Some(Timestamp(datetime.and_utc().timestamp()))
+}
+
+/// Parses RFC 3339 timestamps, e.g. `2020-01-01T00:00:00Z` or `2020-01-01T01:00:00+01:00`
+impl FromStr for Timestamp {
+    type Err = ParseTimestampError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(t) = parse_simple_iso(s) {
+            return Ok(t);
+        }
+        DateTime::parse_from_rfc3339(s)
+            .map(|dt| Timestamp(dt.timestamp()))
+            .map_err(ParseTimestampError)
+    }
+}
+
+/// ISO 8601 in UTC, e.g. `2020-01-01T00:00:00Z`
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_datetime() {
+            Some(dt) => write!(
+                f,
+                "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+                dt.year(),
+                dt.month(),
+                dt.day(),
+                dt.hour(),
+                dt.minute(),
+                dt.second()
+            ),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
+
+impl fmt::Debug for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Timestamp({})", self)
+    }
+}
+
+/// Serialized as an ISO string in human readable formats (e.g. JSON), otherwise as seconds
+#[cfg(feature = "serde")]
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_i64(self.0)
+        }
+    }
+}
+
+/// From an ISO string, or an integer number of seconds
+#[cfg(feature = "serde")]
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if !deserializer.is_human_readable() {
+            return i64::deserialize(deserializer).map(Timestamp);
+        }
+        struct Visitor;
+        impl serde::de::Visitor<'_> for Visitor {
+            type Value = Timestamp;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "an ISO 8601 timestamp, or seconds since the epoch")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, s: &str) -> Result<Timestamp, E> {
+                s.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Timestamp, E> {
+                Ok(Timestamp(v))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Timestamp, E> {
+                i64::try_from(v)
+                    .map(Timestamp)
+                    .map_err(|_| E::custom("timestamp too large"))
+            }
+        }
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let t: Timestamp = "2008-09-21T21:37:45Z".parse().unwrap();
+        assert_eq!(t.epoch_secs(), 1222033065);
+        assert_eq!(t.to_string(), "2008-09-21T21:37:45Z");
+        assert_eq!("2008-09-21T23:37:45+02:00".parse::<Timestamp>().unwrap(), t);
+        assert_eq!("2008-09-21T21:37:45.9Z".parse::<Timestamp>().unwrap(), t);
+
+        assert!("2008-13-21T21:37:45Z".parse::<Timestamp>().is_err());
+        assert!("yesterday".parse::<Timestamp>().is_err());
+        assert_eq!(
+            "".parse::<Timestamp>().unwrap_err().to_string(),
+            "invalid timestamp: premature end of input"
+        );
+
+        // Past 2038
+        let t = Timestamp::from(4_102_444_800_i64);
+        assert_eq!(t.to_string(), "2100-01-01T00:00:00Z");
+        assert_eq!(Timestamp::from(0).to_string(), "1970-01-01T00:00:00Z");
+        assert_eq!(Timestamp::from(-1).to_string(), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn chrono() {
+        let t = Timestamp::from(1577836800);
+        let dt = t.to_datetime().unwrap();
+        assert_eq!(dt.to_rfc3339(), "2020-01-01T00:00:00+00:00");
+        assert_eq!(Timestamp::from_datetime(dt), t);
+        assert_eq!(Timestamp::from_epoch_secs(i64::MAX).to_datetime(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde() {
+        let t = Timestamp::from(1577836800);
+        let json = serde_json::to_string(&t).unwrap();
+        assert_eq!(json, "\"2020-01-01T00:00:00Z\"");
+        assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), t);
+        assert_eq!(serde_json::from_str::<Timestamp>("1577836800").unwrap(), t);
+        assert!(serde_json::from_str::<Timestamp>("\"x\"").is_err());
+    }
+}
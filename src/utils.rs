@@ -1,15 +1,13 @@
 //! Misc local utilities
-use chrono::{DateTime, offset::Utc};
+use crate::Timestamp;
 
+#[deprecated(note = "Use Timestamp")]
 pub fn epoch_to_iso(epoch: i32) -> String {
-    // Chrono is a little silly and can't easily convert from epoch to utc timezone
-    let d: DateTime<Utc> =
-        DateTime::from_timestamp(epoch as i64, 0).expect("epoch timestamp too high");
-    d.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+    Timestamp::from(epoch).to_string()
 }
 
+#[deprecated(note = "Use Timestamp")]
 pub fn iso_to_epoch(iso: &str) -> u32 {
-    DateTime::parse_from_rfc3339(iso)
-        .map(|x| x.timestamp() as u32)
-        .unwrap_or(0)
+    iso.parse::<Timestamp>()
+        .map_or(0, |t| t.epoch_secs() as u32)
 }
@@ -1,26 +1,97 @@
 //! XML file format
 
 use super::ObjId;
-use super::TimestampFormat;
+use super::Timestamp;
 use super::version;
-use super::{Node, OSMObj, OSMObjectType, Relation, Way};
+use super::{BBox, Lat, Lon};
+use super::{
+    Node, NodeRead, OSMObj, OSMObjBase, OSMObjBaseRead, OSMObjectType, RelationRead, WayRead,
+};
 use super::{OSMReader, OSMWriteError, OSMWriter};
+use crate::compression::{CompressedReader, CompressedWriter, Compression};
+use crate::josm::{JosmAction, UploadPolicy};
 use crate::obj_types::{StringNode, StringOSMObj, StringRelation, StringWay};
+use crate::progress::ReadProgress;
+use crate::sort::{AssumedSortedCheck, UnsortedError};
 use bzip2::read::MultiBzDecoder;
 use smallvec::SmallVec;
 use smol_str::SmolStr;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::iter::Iterator;
 use std::path::Path;
+use std::str::FromStr;
 
 use anyhow::Result;
-use xml_rs::attribute::OwnedAttribute;
-use xml_rs::reader::{EventReader, Events, XmlEvent};
+use quick_xml::XmlVersion;
+use quick_xml::events::attributes::Attribute;
+use quick_xml::events::{BytesStart, Event};
 
+/// Reads OSM XML files
 pub struct XMLReader<R: Read> {
-    parser: Events<BufReader<R>>,
+    reader: quick_xml::Reader<BufReader<R>>,
+    buf: Vec<u8>,
+    header: XMLHeader,
+    header_complete: bool,
+    pending: Option<(StringOSMObj, XMLObjInfo)>,
+    sorted_assumption: bool,
+    sorted_check: AssumedSortedCheck,
+}
+
+/// The file level metadata of an XML (or OSC) file, i.e. the attributes of the root `<osm>`
+/// element, and the `<bounds>`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XMLHeader {
+    /// The `version` attribute, e.g. `0.6`
+    pub version: Option<String>,
+    /// The program which created this file.
+    pub generator: Option<String>,
+    /// When the data was last updated (`timestamp` attribute). If it can't be parsed, it's kept
+    /// in `other` instead.
+    pub timestamp: Option<Timestamp>,
+    pub copyright: Option<String>,
+    pub attribution: Option<String>,
+    pub license: Option<String>,
+    /// The declared extent of the data in this file, from `<bounds>` (or the older `<bound>`)
+    pub bounds: Option<BBox>,
+    /// JOSM's `upload` attribute
+    pub upload: Option<UploadPolicy>,
+    /// JOSM's `locked="true"` attribute
+    pub locked: bool,
+    /// Any other attributes on the root element, in file order.
+    pub other: Vec<(String, String)>,
+}
+
+/// Per object data in an XML file which isn't part of the object itself.
+///
+/// This includes the inline geometry from Overpass (`out geom`, `out bb` & `out center`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XMLObjInfo {
+    /// JOSM's `action` attribute, if the object was changed locally
+    pub action: Option<JosmAction>,
+    /// The `<bounds>` of this way or relation
+    pub bounds: Option<BBox>,
+    /// The `<center>` of this way or relation
+    pub center: Option<(Lat, Lon)>,
+    /// For ways, the location of each node (from `<nd ref=".." lat=".." lon=".."/>`), in the same
+    /// order as [`WayRead::nodes`]. Empty if the file has no way geometry.
+    pub node_locations: Vec<Option<(Lat, Lon)>>,
+    /// For relations, the geometry of each member, in the same order as
+    /// [`RelationRead::members`]. Node members have 1 location, way members have one for each
+    /// node (`None` if outside the query bbox), relation members have none. Empty if the file
+    /// has no member geometry.
+    pub member_geometries: Vec<Vec<Option<(Lat, Lon)>>>,
+}
+
+impl XMLObjInfo {
+    /// The locations of the way's nodes, if every node has a location.
+    pub fn way_coords(&self) -> Option<Vec<(Lat, Lon)>> {
+        if self.node_locations.is_empty() {
+            return None;
+        }
+        self.node_locations.iter().copied().collect()
+    }
 }
 
 pub fn from_filename_bz2(
@@ -38,23 +109,21 @@ pub fn from_filename_uncompressed(
     Ok(XMLReader::new(BufReader::new(File::open(filename)?)))
 }
 
-/*
- * TODO, fix this
-pub fn from_filename(filename: impl AsRef<Path>) -> Result<XMLReader<dyn Read>>> {
-    match filename.as_ref().to_str() {
-        None => { anyhow::bail!("filename {:?} cannot automatically determine whether this is compressed or not, call from_filename_bz2(…) or from_filename_uncompressed(…) directly", filename); },
-        Some(s) => {
-            if s.ends_with(".osm.bz2") {
-                from_filename_bz2(s)
-            } else if s.ends_with(".osm") {
-                from_filename_uncompressed(s)
-            } else {
-                anyhow::bail!("filename {:?} cannot automatically determine whether this is compressed or not, call from_filename_bz2(…) or from_filename_uncompressed(…) directly", filename)
-                }
-        },
-    }
+/// Opens an XML file, decompressing it based on the filename extension (`.gz`, `.bz2` or
+/// `.zst`).
+pub fn from_filename(filename: impl AsRef<Path>) -> Result<XMLReader<CompressedReader<File>>> {
+    Ok(XMLReader::new(CompressedReader::open(filename)?))
+}
+
+/// Creates an XML file, compressing it based on the filename extension (`.gz`, `.bz2` or
+/// `.zst`).
+///
+/// The compression is finished when the writer is closed.
+pub fn to_filename(filename: impl AsRef<Path>) -> Result<XMLWriter<CompressedWriter<File>>> {
+    Ok(XMLWriter::from_compressed(CompressedWriter::create(
+        filename,
+    )?))
 }
-*/
 
 pub(crate) fn write_xml_escaped(writer: &mut impl Write, s: &str) -> std::io::Result<()> {
     for c in s.chars() {
@@ -70,65 +139,118 @@ pub(crate) fn write_xml_escaped(writer: &mut impl Write, s: &str) -> std::io::Re
     Ok(())
 }
 
+impl<R: Read> XMLReader<R> {
+    /// Returns the next OSM object, or an error if the XML is invalid.
+    pub fn next_obj(&mut self) -> Result<Option<StringOSMObj>> {
+        Ok(self.next_with_info()?.map(|(obj, _info)| obj))
+    }
+
+    /// Returns the next OSM object, along with the extra XML attributes (e.g. the JOSM `action`).
+    pub fn next_with_info(&mut self) -> Result<Option<(StringOSMObj, XMLObjInfo)>> {
+        if let Some(pending) = self.pending.take() {
+            return Ok(Some(pending));
+        }
+        self.read_next_obj()
+    }
+
+    /// The root element attributes & bounds of this file.
+    ///
+    /// This can be called at any time. If no objects have been read yet, the file is read up to
+    /// the first object.
+    pub fn header(&mut self) -> Result<&XMLHeader> {
+        if !self.header_complete {
+            self.pending = self.read_next_obj()?;
+        }
+        Ok(&self.header)
+    }
+
+    fn read_next_obj(&mut self) -> Result<Option<(StringOSMObj, XMLObjInfo)>> {
+        loop {
+            self.buf.clear();
+            let (e, is_empty) = match self.reader.read_event_into(&mut self.buf)? {
+                Event::Eof => {
+                    self.header_complete = true;
+                    return Ok(None);
+                }
+                Event::Start(e) => (e, false),
+                Event::Empty(e) => (e, true),
+                _ => continue,
+            };
+            if !self.header_complete && read_header_element(&mut self.header, &e)? {
+                continue;
+            }
+            let mut info = XMLObjInfo::default();
+            let Some(mut obj) = obj_from_start(&e, &mut info)? else {
+                continue;
+            };
+            self.header_complete = true;
+            if !is_empty {
+                read_obj_children(&mut obj, &mut info, &mut self.reader, &mut self.buf)?;
+            }
+            return Ok(Some((obj, info)));
+        }
+    }
+}
+
 impl<R: Read> OSMReader for XMLReader<R> {
     type R = R;
     type Obj = StringOSMObj;
 
     fn new(reader: R) -> XMLReader<R> {
         XMLReader {
-            parser: EventReader::new(BufReader::new(reader)).into_iter(),
+            reader: quick_xml::Reader::from_reader(BufReader::new(reader)),
+            buf: Vec::new(),
+            header: XMLHeader::default(),
+            header_complete: false,
+            pending: None,
+            sorted_assumption: false,
+            sorted_check: AssumedSortedCheck::default(),
         }
     }
 
+    fn set_sorted_assumption(&mut self, sorted_assumption: bool) {
+        self.sorted_assumption = sorted_assumption;
+        self.sorted_check = AssumedSortedCheck::default();
+    }
+    fn get_sorted_assumption(&mut self) -> bool {
+        self.sorted_assumption
+    }
+    fn unsorted_error(&self) -> Option<&UnsortedError> {
+        self.sorted_check.error()
+    }
+
     fn into_inner(self) -> R {
-        self.parser.into_inner().into_inner().into_inner()
+        self.reader.into_inner().into_inner()
     }
 
     fn inner(&self) -> &R {
-        todo!("{} {} XMLReader inner()", file!(), line!());
+        self.reader.get_ref().get_ref()
     }
 
     fn next(&mut self) -> Option<StringOSMObj> {
-        let mut elements = Vec::new();
-
-        // Pull xml/sax elements from the xml parser into a vector so we know what to work with.
-        let mut should_push = false;
-        loop {
-            let el = match self.parser.next() {
-                None => {
-                    break;
-                }
-                Some(e) => e,
-            };
-
-            let el = el.unwrap();
+        if !self.sorted_assumption {
+            return self.next_obj().expect("Invalid OSM XML");
+        }
+        if self.sorted_check.stopped() {
+            return None;
+        }
+        let obj = self.next_obj().expect("Invalid OSM XML")?;
+        self.sorted_check.check(&obj).then_some(obj)
+    }
 
-            let mut should_break = false;
-            match el {
-                XmlEvent::StartElement { ref name, .. } => match name.local_name.as_str() {
-                    "node" | "way" | "relation" => {
-                        should_push = true;
-                    }
-                    _ => {}
-                },
-                XmlEvent::EndElement { ref name, .. } => match name.local_name.as_str() {
-                    "node" | "way" | "relation" => {
-                        should_break = true;
-                    }
-                    _ => {}
-                },
-                _ => {}
-            }
+    /// The [`XMLObjInfo`] of `obj` isn't kept
+    fn unread(&mut self, obj: StringOSMObj) {
+        self.pending = Some((obj, XMLObjInfo::default()));
+    }
+}
 
-            if should_push {
-                elements.push(el);
-            }
-            if should_break {
-                break;
-            }
-        }
+impl<R: Read + ReadProgress> ReadProgress for XMLReader<R> {
+    fn bytes_read(&self) -> u64 {
+        self.reader.get_ref().bytes_read()
+    }
 
-        xml_elements_to_osm_obj(&mut elements)
+    fn total_bytes(&self) -> Option<u64> {
+        self.reader.get_ref().total_bytes()
     }
 }
 
@@ -144,216 +266,410 @@ enum State {
 pub struct XMLWriter<W: Write> {
     writer: W,
     headers: HashMap<String, String>,
+    bounds: Option<BBox>,
+    finish: fn(&mut W) -> std::io::Result<()>,
     _state: State,
 }
 
-#[allow(unused_variables)]
-pub(crate) fn extract_attrs(x: &mut XmlEvent) -> Option<&mut Vec<OwnedAttribute>> {
-    match *x {
-        XmlEvent::StartElement {
-            ref name,
-            ref mut attributes,
-            ref namespace,
-        } => Some(attributes),
-        _ => None,
-    }
+/// Parse a (non-escaped) attribute value, e.g. a number. Invalid values are ignored.
+pub(crate) fn attr_parse<T: FromStr>(attr: &Attribute) -> Option<T> {
+    std::str::from_utf8(&attr.value).ok()?.parse().ok()
 }
 
-pub(crate) fn get_xml_attribute(attrs: &mut Vec<OwnedAttribute>, key: &str) -> Option<String> {
-    attrs
-        .iter()
-        .position(|attr| attr.name.local_name == key)
-        .map(|idx| {
-            let attr = attrs.swap_remove(idx);
-            let OwnedAttribute { name: _, value } = attr;
-            value
-        })
+/// The (unescaped) string value of an attribute
+pub(crate) fn attr_str<'a>(attr: &'a Attribute) -> Result<std::borrow::Cow<'a, str>> {
+    Ok(attr.normalized_value(XmlVersion::Implicit1_0)?)
 }
 
-fn get_tags(els: &mut [XmlEvent]) -> Vec<(SmolStr, SmolStr)> {
-    let mut result = Vec::new();
-    for el in els.iter_mut() {
-        if let &mut XmlEvent::StartElement {
-            ref name,
-            ref mut attributes,
-            ..
-        } = el
-        {
-            if name.local_name == "tag" {
-                let ko = get_xml_attribute(attributes, "k");
-                let vo = get_xml_attribute(attributes, "v");
-                if let (Some(k), Some(v)) = (ko, vo) {
-                    result.push((SmolStr::new(k), SmolStr::new(v)));
+/// If this element is part of the file header (the root element, or `<bounds>`), store it in
+/// `header` and return `true`.
+pub(crate) fn read_header_element(header: &mut XMLHeader, e: &BytesStart) -> Result<bool> {
+    match e.local_name().as_ref() {
+        b"osm" | b"osmChange" => {
+            for attr in e.attributes() {
+                let attr = attr?;
+                let value = attr_str(&attr)?.into_owned();
+                match attr.key.as_ref() {
+                    b"version" => header.version = Some(value),
+                    b"generator" => header.generator = Some(value),
+                    b"timestamp" => match value.parse() {
+                        Ok(timestamp) => header.timestamp = Some(timestamp),
+                        Err(_) => header.other.push(("timestamp".to_string(), value)),
+                    },
+                    b"copyright" => header.copyright = Some(value),
+                    b"attribution" => header.attribution = Some(value),
+                    b"license" => header.license = Some(value),
+                    b"upload" => header.upload = value.parse().ok(),
+                    b"locked" => header.locked = value == "true",
+                    key => header
+                        .other
+                        .push((String::from_utf8_lossy(key).into_owned(), value)),
                 }
             }
+            Ok(true)
         }
+        b"bounds" => {
+            header.bounds = bounds_from_element(e)?;
+            Ok(true)
+        }
+        b"bound" => {
+            // Older osmosis format: <bound box="minlat,minlon,maxlat,maxlon" origin="…"/>
+            for attr in e.attributes() {
+                let attr = attr?;
+                if attr.key.as_ref() == b"box" {
+                    let value = attr_str(&attr)?;
+                    let parts: Vec<&str> = value.split(',').collect();
+                    if let [min_lat, min_lon, max_lat, max_lon] = parts[..] {
+                        header.bounds = Some(BBox::new(
+                            min_lat.trim().parse()?,
+                            min_lon.trim().parse()?,
+                            max_lat.trim().parse()?,
+                            max_lon.trim().parse()?,
+                        ));
+                    }
+                }
+            }
+            Ok(true)
+        }
+        _ => Ok(false),
     }
+}
 
-    result
+/// Parse the `minlat`, `minlon`, `maxlat` & `maxlon` attributes of a `<bounds>` element.
+pub(crate) fn bounds_from_element(e: &BytesStart) -> Result<Option<BBox>> {
+    let mut min_lat: Option<Lat> = None;
+    let mut min_lon: Option<Lon> = None;
+    let mut max_lat: Option<Lat> = None;
+    let mut max_lon: Option<Lon> = None;
+    for attr in e.attributes().with_checks(false) {
+        let attr = attr?;
+        match attr.key.local_name().as_ref() {
+            b"minlat" => min_lat = attr_parse(&attr),
+            b"minlon" => min_lon = attr_parse(&attr),
+            b"maxlat" => max_lat = attr_parse(&attr),
+            b"maxlon" => max_lon = attr_parse(&attr),
+            _ => {}
+        }
+    }
+    Ok(match (min_lat, min_lon, max_lat, max_lon) {
+        (Some(min_lat), Some(min_lon), Some(max_lat), Some(max_lon)) => {
+            Some(BBox::new(min_lat, min_lon, max_lat, max_lon))
+        }
+        _ => None,
+    })
 }
 
-fn get_nodes(els: &mut [XmlEvent]) -> Vec<ObjId> {
-    let mut result = Vec::new();
-
-    for el in els.iter_mut() {
-        if let &mut XmlEvent::StartElement {
-            ref name,
-            ref mut attributes,
-            ..
-        } = el
-        {
-            if name.local_name == "nd" {
-                let ref_id_o: Option<ObjId> =
-                    get_xml_attribute(attributes, "ref").and_then(|e| e.parse().ok());
-                if let Some(ref_id) = ref_id_o {
-                    result.push(ref_id);
-                }
-            }
+/// Parse the `lat` & `lon` attributes of an element, if it has both.
+fn lat_lon_from_element(e: &BytesStart) -> Result<Option<(Lat, Lon)>> {
+    let mut lat: Option<Lat> = None;
+    let mut lon: Option<Lon> = None;
+    for attr in e.attributes().with_checks(false) {
+        let attr = attr?;
+        match attr.key.local_name().as_ref() {
+            b"lat" => lat = attr_parse(&attr),
+            b"lon" => lon = attr_parse(&attr),
+            _ => {}
         }
     }
+    Ok(lat.zip(lon))
+}
 
-    result
+/// Write a `<bounds …/>` element
+pub(crate) fn write_bounds(writer: &mut impl Write, bounds: &BBox) -> std::io::Result<()> {
+    write!(
+        writer,
+        "\n\t<bounds minlat=\"{}\" minlon=\"{}\" maxlat=\"{}\" maxlon=\"{}\"/>",
+        bounds.min_lat, bounds.min_lon, bounds.max_lat, bounds.max_lon
+    )
 }
 
-fn get_members(els: &mut [XmlEvent]) -> Vec<(OSMObjectType, ObjId, SmolStr)> {
-    let mut result = Vec::new();
-
-    for el in els.iter_mut() {
-        if let &mut XmlEvent::StartElement {
-            ref name,
-            ref mut attributes,
-            ..
-        } = el
-        {
-            if name.local_name == "member" {
-                let ref_id_o: Option<ObjId> =
-                    get_xml_attribute(attributes, "ref").and_then(|e| e.parse().ok());
-                let member_type_o: Option<OSMObjectType> =
-                    get_xml_attribute(attributes, "type").and_then(|t| t.parse().ok());
-                let role = SmolStr::new(get_xml_attribute(attributes, "role").unwrap_or_default());
-                if let (Some(ref_id), Some(member_type)) = (ref_id_o, member_type_o) {
-                    result.push((member_type, ref_id, role));
-                }
-            }
+/// Start building an OSM object from its opening XML element.
+///
+/// Returns `None` if this element isn't a `node`, `way` or `relation`, or it has no `id`.
+/// Attributes which aren't part of the object are stored in `info`.
+pub(crate) fn obj_from_start(
+    e: &BytesStart,
+    info: &mut XMLObjInfo,
+) -> Result<Option<StringOSMObj>> {
+    let mut obj = match e.local_name().as_ref() {
+        b"node" => StringOSMObj::Node(StringNode {
+            _id: 0,
+            _version: None,
+            _deleted: false,
+            _changeset_id: None,
+            _timestamp: None,
+            _uid: None,
+            _user: None,
+            _tags: SmallVec::new(),
+            _lat_lon: None,
+        }),
+        b"way" => StringOSMObj::Way(StringWay {
+            _id: 0,
+            _version: None,
+            _deleted: false,
+            _changeset_id: None,
+            _timestamp: None,
+            _uid: None,
+            _user: None,
+            _tags: SmallVec::new(),
+            _nodes: SmallVec::new(),
+            _node_locations: None,
+        }),
+        b"relation" => StringOSMObj::Relation(StringRelation {
+            _id: 0,
+            _version: None,
+            _deleted: false,
+            _changeset_id: None,
+            _timestamp: None,
+            _uid: None,
+            _user: None,
+            _tags: SmallVec::new(),
+            _members: Vec::new(),
+        }),
+        _ => return Ok(None),
+    };
+
+    let mut id: Option<ObjId> = None;
+    let mut lat = None;
+    let mut lon = None;
+    for attr in e.attributes().with_checks(false) {
+        let attr = attr?;
+        match attr.key.local_name().as_ref() {
+            b"id" => id = attr_parse(&attr),
+            b"version" => obj.set_version(attr_parse::<u32>(&attr)),
+            b"changeset" => obj.set_changeset_id(attr_parse::<u32>(&attr)),
+            b"uid" => obj.set_uid(attr_parse::<u32>(&attr)),
+            b"user" => obj.set_user(attr_str(&attr)?.as_ref()),
+            // Invalid timestamps are ignored, like other invalid attributes
+            b"timestamp" => obj.set_timestamp(attr_parse::<Timestamp>(&attr)),
+            b"visible" => obj.set_deleted(attr.value.as_ref() == b"false"),
+            b"lat" => lat = attr_parse(&attr),
+            b"lon" => lon = attr_parse(&attr),
+            b"action" => info.action = attr_parse(&attr),
+            _ => {}
         }
     }
 
-    result
+    let Some(id) = id else {
+        return Ok(None);
+    };
+    obj.set_id(id);
+    if let (Some(node), Some(lat), Some(lon)) = (obj.as_node_mut(), lat, lon) {
+        node.set_lat_lon_direct(Some((lat, lon)));
+    }
+
+    Ok(Some(obj))
 }
 
-pub(crate) fn xml_elements_to_osm_obj(els: &mut [XmlEvent]) -> Option<StringOSMObj> {
-    match els.first() {
-        Some(XmlEvent::StartElement { name, .. }) => match name.local_name.as_str() {
-            "node" => node_xml_elements_to_osm_obj(els),
-            "way" => way_xml_elements_to_osm_obj(els),
-            "relation" => relation_xml_elements_to_osm_obj(els),
-            _ => None,
-        },
-        _ => None,
+/// Read the child elements (tags, way nodes, relation members, Overpass geometry) of an object, up
+/// to and including the object's closing element.
+pub(crate) fn read_obj_children(
+    obj: &mut StringOSMObj,
+    info: &mut XMLObjInfo,
+    reader: &mut quick_xml::Reader<impl BufRead>,
+    buf: &mut Vec<u8>,
+) -> Result<()> {
+    let end_name = obj.object_type().name_long().as_bytes();
+    loop {
+        buf.clear();
+        match reader.read_event_into(buf)? {
+            Event::Start(e) | Event::Empty(e) => read_obj_child(obj, info, &e)?,
+            Event::End(e) if e.local_name().as_ref() == end_name => {
+                // Pad out the geometry, so it lines up with the nodes/members
+                if let Some(way) = obj.as_way()
+                    && !info.node_locations.is_empty()
+                {
+                    info.node_locations.resize(way._nodes.len(), None);
+                }
+                if let Some(relation) = obj.as_relation()
+                    && !info.member_geometries.is_empty()
+                {
+                    info.member_geometries
+                        .resize(relation._members.len(), Vec::new());
+                }
+                return Ok(());
+            }
+            Event::Eof => {
+                anyhow::bail!(
+                    "Unexpected end of file inside {} {}",
+                    obj.object_type(),
+                    obj.id()
+                )
+            }
+            _ => {}
+        }
     }
 }
 
-fn node_xml_elements_to_osm_obj(els: &mut [XmlEvent]) -> Option<StringOSMObj> {
-    let attrs = extract_attrs(els.first_mut()?)?;
-    let id: ObjId = get_xml_attribute(attrs, "id").and_then(|x| x.parse().ok())?;
-    let version = get_xml_attribute(attrs, "version").and_then(|x| x.parse().ok());
-    let changeset_id = get_xml_attribute(attrs, "changeset").and_then(|x| x.parse().ok());
-    let timestamp =
-        get_xml_attribute(attrs, "timestamp").map(|x| TimestampFormat::ISOString(x.to_owned()));
-    let uid = get_xml_attribute(attrs, "uid").and_then(|x| x.parse().ok());
-    let user = get_xml_attribute(attrs, "user");
-    let lat = get_xml_attribute(attrs, "lat")?.parse();
-    let lon = get_xml_attribute(attrs, "lon")?.parse();
-
-    let lat_lon = match (lat, lon) {
-        (Ok(lat), Ok(lon)) => Some((lat, lon)),
-        _errs => None,
-    };
-    let deleted = get_xml_attribute(attrs, "visible")
-        .and_then(|val| match val.as_str() {
-            "true" => Some(false),
-            "false" => Some(true),
-            _ => None,
-        })
-        .unwrap_or(false);
-
-    let tags = get_tags(els);
-
-    Some(StringOSMObj::Node(StringNode {
-        _id: id,
-        _version: version,
-        _deleted: deleted,
-        _changeset_id: changeset_id,
-        _timestamp: timestamp,
-        _uid: uid,
-        _user: user.map(SmolStr::new),
-        _lat_lon: lat_lon,
-        _tags: SmallVec::from_vec(tags),
-    }))
+fn read_obj_child(obj: &mut StringOSMObj, info: &mut XMLObjInfo, e: &BytesStart) -> Result<()> {
+    match (e.local_name().as_ref(), obj) {
+        (b"tag", obj) => {
+            let mut k = None;
+            let mut v = None;
+            for attr in e.attributes().with_checks(false) {
+                let attr = attr?;
+                match attr.key.local_name().as_ref() {
+                    b"k" => k = Some(SmolStr::new(attr_str(&attr)?)),
+                    b"v" => v = Some(SmolStr::new(attr_str(&attr)?)),
+                    _ => {}
+                }
+            }
+            if let (Some(k), Some(v)) = (k, v) {
+                match obj {
+                    StringOSMObj::Node(n) => n._tags.push((k, v)),
+                    StringOSMObj::Way(w) => w._tags.push((k, v)),
+                    StringOSMObj::Relation(r) => r._tags.push((k, v)),
+                }
+            }
+        }
+        (b"nd", StringOSMObj::Way(way)) => {
+            let ref_id: Option<ObjId> = e
+                .attributes()
+                .with_checks(false)
+                .filter_map(|a| a.ok())
+                .find(|a| a.key.local_name().as_ref() == b"ref")
+                .and_then(|a| attr_parse(&a));
+            if let Some(ref_id) = ref_id {
+                way._nodes.push(ref_id);
+                if let Some(loc) = lat_lon_from_element(e)? {
+                    info.node_locations.resize(way._nodes.len() - 1, None);
+                    info.node_locations.push(Some(loc));
+                }
+            }
+        }
+        // Overpass geometry of a way member: <member type="way" …><nd lat=".." lon=".."/>
+        (b"nd", StringOSMObj::Relation(relation)) if !relation._members.is_empty() => {
+            info.member_geometries
+                .resize(relation._members.len(), Vec::new());
+            info.member_geometries
+                .last_mut()
+                .unwrap()
+                .push(lat_lon_from_element(e)?);
+        }
+        (b"bounds", _) => info.bounds = bounds_from_element(e)?,
+        (b"center", _) => info.center = lat_lon_from_element(e)?,
+        (b"member", StringOSMObj::Relation(relation)) => {
+            let mut ref_id: Option<ObjId> = None;
+            let mut member_type: Option<OSMObjectType> = None;
+            let mut role = SmolStr::default();
+            for attr in e.attributes().with_checks(false) {
+                let attr = attr?;
+                match attr.key.local_name().as_ref() {
+                    b"ref" => ref_id = attr_parse(&attr),
+                    b"type" => member_type = attr_parse(&attr),
+                    b"role" => role = SmolStr::new(attr_str(&attr)?),
+                    _ => {}
+                }
+            }
+            if let (Some(ref_id), Some(member_type)) = (ref_id, member_type) {
+                relation._members.push((member_type, ref_id, role));
+                // Overpass geometry of a node member is on the member element
+                if let Some(loc) = lat_lon_from_element(e)? {
+                    info.member_geometries
+                        .resize(relation._members.len() - 1, Vec::new());
+                    info.member_geometries.push(vec![Some(loc)]);
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
 }
 
-fn way_xml_elements_to_osm_obj(els: &mut [XmlEvent]) -> Option<StringOSMObj> {
-    let attrs = extract_attrs(els.first_mut()?)?;
-    let id: ObjId = get_xml_attribute(attrs, "id").and_then(|x| x.parse().ok())?;
-    let version = get_xml_attribute(attrs, "version").and_then(|x| x.parse().ok());
-    let changeset_id = get_xml_attribute(attrs, "changeset").and_then(|x| x.parse().ok());
-    let timestamp =
-        get_xml_attribute(attrs, "timestamp").map(|x| TimestampFormat::ISOString(x.to_owned()));
-    let uid = get_xml_attribute(attrs, "uid").and_then(|x| x.parse().ok());
-    let user = get_xml_attribute(attrs, "user");
-    let deleted = get_xml_attribute(attrs, "visible")
-        .and_then(|val| match val.as_str() {
-            "true" => Some(false),
-            "false" => Some(true),
-            _ => None,
-        })
-        .unwrap_or(false);
-
-    let tags = get_tags(els);
-    let nodes = get_nodes(els);
-    Some(StringOSMObj::Way(StringWay {
-        _id: id,
-        _version: version,
-        _deleted: deleted,
-        _changeset_id: changeset_id,
-        _timestamp: timestamp,
-        _uid: uid,
-        _user: user.map(SmolStr::new),
-        _tags: SmallVec::from_vec(tags),
-        _nodes: SmallVec::from_vec(nodes),
-    }))
-}
+/// Write one object as an XML element, with an optional JOSM `action` attribute.
+pub(crate) fn write_obj_element(
+    writer: &mut impl Write,
+    obj: &impl OSMObj,
+    action: Option<JosmAction>,
+) -> std::io::Result<()> {
+    write!(
+        writer,
+        "{}",
+        match obj.object_type() {
+            OSMObjectType::Node => "\n\t<node",
+            OSMObjectType::Way => "\n\t<way",
+            OSMObjectType::Relation => "\n\t<relation",
+        }
+    )?;
+    write!(writer, " id=\"{}\"", obj.id())?;
+    if let Some(action) = action {
+        write!(writer, " action=\"{}\"", action)?;
+    }
+    write!(
+        writer,
+        " visible=\"{}\"",
+        if obj.deleted() { "false" } else { "true" }
+    )?;
+    if let Some(version) = obj.version() {
+        write!(writer, " version=\"{}\"", version)?;
+    }
+    if let Some(user) = obj.user() {
+        write!(writer, " user=\"")?;
+        write_xml_escaped(writer, user)?;
+        write!(writer, "\"")?;
+    }
+    if let Some(uid) = obj.uid() {
+        write!(writer, " uid=\"{}\"", uid)?;
+    }
+    if let Some(changeset_id) = obj.changeset_id() {
+        write!(writer, " changeset=\"{}\"", changeset_id)?;
+    }
+    if let Some(timestamp) = obj.timestamp() {
+        write!(writer, " timestamp=\"{}\"", timestamp)?;
+    }
+
+    if let Some(node) = obj.as_node()
+        && let Some((lat, lon)) = node.lat_lon()
+    {
+        write!(writer, " lat=\"{}\"", lat)?;
+        write!(writer, " lon=\"{}\"", lon)?;
+    }
+
+    if obj.is_node() && obj.untagged() {
+        write!(writer, " />")?;
+        return Ok(());
+    }
+    write!(writer, ">")?;
+
+    if let Some(way) = obj.as_way() {
+        for nid in way.nodes() {
+            write!(writer, "\n\t\t<nd ref=\"{}\" />", nid)?;
+        }
+    }
 
-fn relation_xml_elements_to_osm_obj(els: &mut [XmlEvent]) -> Option<StringOSMObj> {
-    let attrs = extract_attrs(els.first_mut()?)?;
-    let id: ObjId = get_xml_attribute(attrs, "id").and_then(|x| x.parse().ok())?;
-    let version = get_xml_attribute(attrs, "version").and_then(|x| x.parse().ok());
-    let changeset_id = get_xml_attribute(attrs, "changeset").and_then(|x| x.parse().ok());
-    let timestamp =
-        get_xml_attribute(attrs, "timestamp").map(|x| TimestampFormat::ISOString(x.to_owned()));
-    let uid = get_xml_attribute(attrs, "uid").and_then(|x| x.parse().ok());
-    let user = get_xml_attribute(attrs, "user");
-    let deleted = get_xml_attribute(attrs, "visible")
-        .and_then(|val| match val.as_str() {
-            "true" => Some(false),
-            "false" => Some(true),
-            _ => None,
-        })
-        .unwrap_or(false);
-
-    let tags = get_tags(els);
-    let members = get_members(els);
-    Some(StringOSMObj::Relation(StringRelation {
-        _id: id,
-        _version: version,
-        _deleted: deleted,
-        _changeset_id: changeset_id,
-        _timestamp: timestamp,
-        _uid: uid,
-        _user: user.map(SmolStr::new),
-        _tags: SmallVec::from_vec(tags),
-        _members: members,
-    }))
+    if let Some(relation) = obj.as_relation() {
+        for member in relation.members() {
+            write!(
+                writer,
+                "\n\t\t<member type=\"{}\" ref=\"{}\" role=\"",
+                member.0, member.1
+            )?;
+            if !member.2.is_empty() {
+                write_xml_escaped(writer, member.2)?;
+            }
+            write!(writer, "\"/>")?;
+        }
+    }
+
+    for (k, v) in obj.tags() {
+        write!(writer, "\n\t\t<tag k=\"")?;
+        write_xml_escaped(writer, k)?;
+        write!(writer, "\" v=\"")?;
+        write_xml_escaped(writer, v)?;
+        write!(writer, "\" />")?;
+    }
+
+    write!(
+        writer,
+        "{}",
+        match obj.object_type() {
+            OSMObjectType::Node => "\n\t</node>",
+            OSMObjectType::Way => "\n\t</way>",
+            OSMObjectType::Relation => "\n\t</relation>",
+        }
+    )?;
+
+    Ok(())
 }
 
 impl From<quick_xml::Error> for OSMWriteError {
@@ -384,11 +700,70 @@ impl<W: Write> XMLWriter<W> {
                 write!(self.writer, "\"")?;
             }
             write!(self.writer, ">")?;
+            if let Some(bounds) = &self.bounds {
+                write_bounds(&mut self.writer, bounds)?;
+            }
 
             self._state = State::WritingObjects;
         }
         Ok(())
     }
+
+    /// Set JOSM's `upload` attribute on the root element.
+    pub fn set_upload_policy(&mut self, upload: UploadPolicy) -> Result<(), OSMWriteError> {
+        self.set_header(("upload", upload.as_str()))
+    }
+
+    /// Set JOSM's `locked` attribute on the root element.
+    pub fn set_locked(&mut self, locked: bool) -> Result<(), OSMWriteError> {
+        self.set_header(("locked", if locked { "true" } else { "false" }))
+    }
+
+    /// Write an object, with a JOSM `action` attribute (if set).
+    pub fn write_obj_with_action(
+        &mut self,
+        obj: &impl OSMObj,
+        action: Option<JosmAction>,
+    ) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => self.ensure_header()?, // This will update self._state
+            State::WritingObjects => {}
+            State::Closed => return Err(OSMWriteError::AlreadyClosed),
+        }
+
+        write_obj_element(&mut self.writer, obj, action)?;
+        Ok(())
+    }
+
+    /// Write a `<bounds>` element with this extent. Must be called before any objects are
+    /// written.
+    pub fn set_bounds(&mut self, bounds: BBox) -> Result<(), OSMWriteError> {
+        match self._state {
+            State::Initial => {
+                self.bounds = Some(bounds);
+                Ok(())
+            }
+            State::Closed => Err(OSMWriteError::AlreadyClosed),
+            _ => Err(OSMWriteError::AlreadyStarted),
+        }
+    }
+}
+
+impl<W: Write> XMLWriter<CompressedWriter<W>> {
+    /// Write XML to `writer`, compressed with `compression`.
+    pub fn new_compressed(writer: W, compression: Compression) -> Result<Self> {
+        Ok(Self::from_compressed(CompressedWriter::new(
+            writer,
+            compression,
+        )?))
+    }
+
+    /// Write XML to this compressed writer. Closing this will finish the compression.
+    pub fn from_compressed(writer: CompressedWriter<W>) -> Self {
+        let mut xml_writer = XMLWriter::new(writer);
+        xml_writer.finish = CompressedWriter::finish;
+        xml_writer
+    }
 }
 
 impl<W: Write> OSMWriter<W> for XMLWriter<W> {
@@ -397,6 +772,8 @@ impl<W: Write> OSMWriter<W> for XMLWriter<W> {
         XMLWriter {
             writer,
             headers: HashMap::new(),
+            bounds: None,
+            finish: W::flush,
             _state: State::Initial,
         }
     }
@@ -417,9 +794,13 @@ impl<W: Write> OSMWriter<W> for XMLWriter<W> {
     }
 
     fn close(&mut self) -> Result<(), OSMWriteError> {
+        if self._state == State::Closed {
+            return Ok(());
+        }
         self.ensure_header()?;
 
         write!(self.writer, "\n</osm>")?;
+        (self.finish)(&mut self.writer)?;
 
         self._state = State::Closed;
 
@@ -427,95 +808,7 @@ impl<W: Write> OSMWriter<W> for XMLWriter<W> {
     }
 
     fn write_obj(&mut self, obj: &impl OSMObj) -> Result<(), OSMWriteError> {
-        match self._state {
-            State::Initial => self.ensure_header()?, // This will update self._state
-            State::WritingObjects => {}
-            State::Closed => return Err(OSMWriteError::AlreadyClosed),
-        }
-
-        write!(
-            self.writer,
-            "{}",
-            match obj.object_type() {
-                OSMObjectType::Node => "\n\t<node",
-                OSMObjectType::Way => "\n\t<way",
-                OSMObjectType::Relation => "\n\t<relation",
-            }
-        )?;
-        write!(self.writer, " id=\"{}\"", obj.id())?;
-        write!(
-            self.writer,
-            " visible=\"{}\"",
-            if obj.deleted() { "false" } else { "true" }
-        )?;
-        write!(self.writer, " version=\"{}\"", obj.version().unwrap())?;
-        if let Some(user) = obj.user() {
-            write!(self.writer, " user=\"")?;
-            write_xml_escaped(&mut self.writer, user)?;
-            write!(self.writer, "\"")?;
-        }
-        if let Some(uid) = obj.uid() {
-            write!(self.writer, " uid=\"{}\"", uid)?;
-        }
-        if let Some(changeset_id) = obj.changeset_id() {
-            write!(self.writer, " changeset=\"{}\"", changeset_id)?;
-        }
-        if let Some(timestamp) = obj.timestamp() {
-            write!(self.writer, " timestamp=\"{}\"", timestamp)?;
-        }
-
-        if let Some(node) = obj.as_node() {
-            if let Some((lat, lon)) = node.lat_lon() {
-                write!(self.writer, " lat=\"{}\"", lat)?;
-                write!(self.writer, " lon=\"{}\"", lon)?;
-            }
-        }
-
-        if obj.is_node() && obj.untagged() {
-            write!(self.writer, " />")?;
-            return Ok(());
-        }
-        write!(self.writer, ">")?;
-
-        if let Some(way) = obj.as_way() {
-            for nid in way.nodes() {
-                write!(self.writer, "\n\t\t<nd ref=\"{}\" />", nid)?;
-            }
-        }
-
-        if let Some(relation) = obj.as_relation() {
-            for member in relation.members() {
-                write!(
-                    self.writer,
-                    "\n\t\t<member type=\"{}\" ref=\"{}\" role=\"",
-                    member.0, member.1
-                )?;
-                if !member.2.is_empty() {
-                    write_xml_escaped(&mut self.writer, member.2)?;
-                }
-                write!(self.writer, "\"/>")?;
-            }
-        }
-
-        for (k, v) in obj.tags() {
-            write!(self.writer, "\n\t\t<tag k=\"")?;
-            write_xml_escaped(&mut self.writer, k)?;
-            write!(self.writer, "\" v=\"")?;
-            write_xml_escaped(&mut self.writer, v)?;
-            write!(self.writer, "\" />")?;
-        }
-
-        write!(
-            self.writer,
-            "{}",
-            match obj.object_type() {
-                OSMObjectType::Node => "\n\t</node>",
-                OSMObjectType::Way => "\n\t</way>",
-                OSMObjectType::Relation => "\n\t</relation>",
-            }
-        )?;
-
-        Ok(())
+        self.write_obj_with_action(obj, None)
     }
 
     fn into_inner(self) -> W {
@@ -591,8 +884,247 @@ mod tests {
             .build()
             .unwrap(),
         format!(
-            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<osm version=\"0.6\" generator=\"osmio/{}\">\n\t<node id=\"1\" visible=\"true\" version=\"2\" user=\"&amp;foo\" uid=\"1\" changeset=\"1\" timestamp=\"1970-01-01T00:11:40Z\" lat=\"0\" lon=\"0\" />\n</osm>\n</osm>",
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<osm version=\"0.6\" generator=\"osmio/{}\">\n\t<node id=\"1\" visible=\"true\" version=\"2\" user=\"&amp;foo\" uid=\"1\" changeset=\"1\" timestamp=\"1970-01-01T00:11:40Z\" lat=\"0\" lon=\"0\" />\n</osm>",
             crate::version()
         )
     );
+
+    #[test]
+    fn compressed_write_then_read() {
+        use crate::OSMReader;
+        let node: StringOSMObj = StringNodeBuilder::default()
+            ._id(1)
+            ._version(1)
+            ._lat_lon((Lat(10), Lon(20)))
+            .build()
+            .unwrap()
+            .into();
+
+        for compression in [Compression::Gzip, Compression::Bzip2, Compression::Zstd] {
+            let mut output = Vec::new();
+            let mut xmlwr = XMLWriter::new_compressed(&mut output, compression).unwrap();
+            xmlwr.write_obj(&node).unwrap();
+            xmlwr.close().unwrap();
+            drop(xmlwr);
+
+            let reader = CompressedReader::new(output.as_slice(), compression).unwrap();
+            let objs: Vec<_> = XMLReader::new(reader).objects().collect();
+            assert_eq!(objs, vec![node.clone()]);
+        }
+    }
+
+    #[test]
+    fn read_objects() {
+        use crate::OSMReader;
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" generator="CGImap 0.0.2">
+ <bounds minlat="54.0889580" minlon="12.2487570" maxlat="54.0913900" maxlon="12.2524800"/>
+ <node id="298884269" lat="54.0901746" lon="12.2482632" user="SvenHRO" uid="46882" visible="true" version="1" changeset="676636" timestamp="2008-09-21T21:37:45Z"/>
+ <node id="1831881213" version="2" changeset="12370172" user="lafkor" uid="75625" visible="false" timestamp="2012-07-20T09:43:19Z"/>
+ <way id="26659127" user="Masch" uid="55988" visible="true" version="5" changeset="4142606" timestamp="2010-03-16T11:47:08Z">
+  <nd ref="292403538"/>
+  <nd ref="298884289"/>
+  <tag k="highway" v="unclassified"/>
+  <tag k="name" v="Pastower Stra&#223;e &amp; &quot;Co&quot;"/>
+ </way>
+ <relation id="56688" user="kmvar" uid="56190" visible="true" version="28" changeset="6947637" timestamp="2011-01-12T14:23:49Z">
+  <member type="node" ref="294942404" role=""/>
+  <member type="way" ref="4579143" role="outer"/>
+  <tag k="type" v="route"/>
+ </relation>
+</osm>"#;
+        let mut reader = XMLReader::new(input.as_bytes());
+        assert_eq!(reader.inner().len(), input.len());
+
+        let node = reader.next().unwrap().into_node().unwrap();
+        assert_eq!(node.id(), 298884269);
+        assert_eq!(node.version(), Some(1));
+        assert_eq!(node.user(), Some("SvenHRO"));
+        assert_eq!(node.uid(), Some(46882));
+        assert_eq!(node.changeset_id(), Some(676636));
+        assert!(!node.deleted());
+        assert_eq!(
+            node.lat_lon(),
+            Some((Lat::from_inner(540901746), Lon::from_inner(122482632)))
+        );
+        assert!(node.untagged());
+
+        // deleted nodes have no location
+        let node = reader.next().unwrap().into_node().unwrap();
+        assert_eq!(node.id(), 1831881213);
+        assert!(node.deleted());
+        assert_eq!(node.lat_lon(), None);
+
+        let way = reader.next().unwrap().into_way().unwrap();
+        assert_eq!(way.nodes(), &[292403538, 298884289]);
+        assert_eq!(way.tag("highway"), Some("unclassified"));
+        assert_eq!(way.tag("name"), Some("Pastower Straße & \"Co\""));
+
+        let relation = reader.next().unwrap().into_relation().unwrap();
+        assert_eq!(
+            relation.members().collect::<Vec<_>>(),
+            vec![
+                (OSMObjectType::Node, 294942404, ""),
+                (OSMObjectType::Way, 4579143, "outer")
+            ]
+        );
+        assert_eq!(relation.tag("type"), Some("route"));
+
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn header_and_bounds() {
+        use crate::OSMReader;
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" generator="CGImap 0.0.2" copyright="OpenStreetMap and contributors" timestamp="2020-01-01T00:00:00Z" upload="false">
+ <bounds minlat="54.0889580" minlon="12.2487570" maxlat="54.0913900" maxlon="12.2524800"/>
+ <node id="1" lat="54.09" lon="12.25"/>
+</osm>"#;
+        let mut reader = XMLReader::new(input.as_bytes());
+        let header = reader.header().unwrap().clone();
+        assert_eq!(header.version.as_deref(), Some("0.6"));
+        assert_eq!(header.generator.as_deref(), Some("CGImap 0.0.2"));
+        assert_eq!(
+            header.copyright.as_deref(),
+            Some("OpenStreetMap and contributors")
+        );
+        assert_eq!(header.timestamp, Some(Timestamp::from(1577836800)));
+        assert_eq!(header.upload, Some(UploadPolicy::Discouraged));
+        assert!(header.other.is_empty());
+        let bounds = header.bounds.unwrap();
+        assert_eq!(bounds.min_lat, Lat::from_inner(540889580));
+        assert_eq!(bounds.max_lon, Lon::from_inner(122524800));
+
+        // reading the header doesn't lose the first object
+        assert_eq!(reader.next().unwrap().id(), 1);
+        assert!(reader.next().is_none());
+
+        // bounds survive a round trip through the writer
+        let mut output = Vec::new();
+        let mut xmlwr = XMLWriter::new(&mut output);
+        xmlwr.set_bounds(bounds).unwrap();
+        xmlwr.close().unwrap();
+        drop(xmlwr);
+        let mut reader = XMLReader::new(output.as_slice());
+        assert_eq!(reader.header().unwrap().bounds, Some(bounds));
+    }
+
+    #[test]
+    fn invalid_timestamps() {
+        use crate::OSMReader;
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" timestamp="yesterday">
+ <node id="1" lat="54.09" lon="12.25" timestamp="2020-13-45T99:00:00Z"/>
+ <node id="2" lat="54.09" lon="12.25" timestamp="2020-01-01T00:00:00Z"/>
+</osm>"#;
+        let mut reader = XMLReader::new(input.as_bytes());
+        let header = reader.header().unwrap();
+        assert_eq!(header.timestamp, None);
+        assert_eq!(
+            header.other,
+            vec![("timestamp".to_string(), "yesterday".to_string())]
+        );
+        let nodes: Vec<_> = reader.objects().collect();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].timestamp(), None);
+        assert_eq!(nodes[1].timestamp(), Some(Timestamp::from(1577836800)));
+    }
+
+    #[test]
+    fn josm_action_round_trip() {
+        let node: StringOSMObj = StringNodeBuilder::default()
+            ._id(-1)
+            ._lat_lon((Lat(10), Lon(20)))
+            .build()
+            .unwrap()
+            .into();
+
+        let mut output = Vec::new();
+        let mut xmlwr = XMLWriter::new(&mut output);
+        xmlwr.set_upload_policy(UploadPolicy::Blocked).unwrap();
+        xmlwr.set_locked(true).unwrap();
+        xmlwr
+            .write_obj_with_action(&node, Some(JosmAction::Modify))
+            .unwrap();
+        xmlwr.close().unwrap();
+        drop(xmlwr);
+
+        let mut reader = XMLReader::new(output.as_slice());
+        let header = reader.header().unwrap();
+        assert_eq!(header.upload, Some(UploadPolicy::Blocked));
+        assert!(header.locked);
+        let (obj, info) = reader.next_with_info().unwrap().unwrap();
+        assert_eq!(obj, node);
+        assert_eq!(info.action, Some(JosmAction::Modify));
+    }
+
+    #[test]
+    fn overpass_geometry() {
+        use crate::OSMReader;
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<osm version="0.6" generator="Overpass API 0.7.62">
+  <way id="10" version="1">
+    <bounds minlat="51.0" minlon="-0.2" maxlat="51.1" maxlon="-0.1"/>
+    <nd ref="1" lat="51.0" lon="-0.2"/>
+    <nd ref="2"/>
+    <nd ref="3" lat="51.1" lon="-0.1"/>
+    <tag k="highway" v="track"/>
+  </way>
+  <way id="11" version="1">
+    <center lat="51.05" lon="-0.15"/>
+    <nd ref="1"/>
+    <nd ref="3"/>
+  </way>
+  <relation id="20" version="1">
+    <member type="node" ref="1" role="label" lat="51.0" lon="-0.2"/>
+    <member type="relation" ref="21" role=""/>
+    <member type="way" ref="10" role="outer">
+      <nd lat="51.0" lon="-0.2"/>
+      <nd lat="51.1" lon="-0.1"/>
+    </member>
+  </relation>
+</osm>"#;
+        let mut reader = XMLReader::new(input.as_bytes());
+
+        let (way, info) = reader.next_with_info().unwrap().unwrap();
+        assert_eq!(way.into_way().unwrap().nodes(), &[1, 2, 3]);
+        assert_eq!(
+            info.bounds,
+            Some(BBox::new(
+                Lat(510000000),
+                Lon(-2000000),
+                Lat(511000000),
+                Lon(-1000000)
+            ))
+        );
+        assert_eq!(
+            info.node_locations,
+            vec![
+                Some((Lat(510000000), Lon(-2000000))),
+                None,
+                Some((Lat(511000000), Lon(-1000000)))
+            ]
+        );
+        assert_eq!(info.way_coords(), None);
+
+        let (_way, info) = reader.next_with_info().unwrap().unwrap();
+        assert_eq!(info.center, Some((Lat(510500000), Lon(-1500000))));
+        assert!(info.node_locations.is_empty());
+
+        let (relation, info) = reader.next_with_info().unwrap().unwrap();
+        assert_eq!(relation.into_relation().unwrap().members().count(), 3);
+        assert_eq!(
+            info.member_geometries,
+            vec![
+                vec![Some((Lat(510000000), Lon(-2000000)))],
+                vec![],
+                vec![
+                    Some((Lat(510000000), Lon(-2000000))),
+                    Some((Lat(511000000), Lon(-1000000)))
+                ],
+            ]
+        );
+        assert!(reader.next().is_none());
+    }
 }